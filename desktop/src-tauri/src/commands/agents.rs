@@ -0,0 +1,573 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+use super::config_preview::{apply_or_preview, ConfigChange, ConfigPreview, PendingPreviews};
+use super::openclaw_config::{
+    self, is_valid_agent_id, AgentBinding, AgentEntry, AgentModelConfig, BindingMatch,
+    OpenClawConfig,
+};
+use super::openclaw_paths::{expand_home, OpenClawPaths};
+use super::settings::SettingsState;
+use super::vault::{mask_key, Vault};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentAuthProfile {
+    /// Profile id as stored in auth-profiles.json, e.g. "openrouter:default".
+    pub id: String,
+    pub provider: Option<String>,
+    pub masked: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentSummary {
+    pub id: String,
+    pub name: Option<String>,
+    pub is_default: bool,
+    /// False for the implicit "main" agent of a config without `agents.list`.
+    pub explicit: bool,
+    /// The agent's own model override, if any.
+    pub model: Option<AgentModelConfig>,
+    /// The model the agent actually uses: its override, else `agents.defaults.model`.
+    pub effective_model: Option<String>,
+    pub agent_dir: String,
+    pub auth_profiles: Vec<AgentAuthProfile>,
+    pub bindings: Vec<BindingMatch>,
+}
+
+/// Directory holding an agent's auth profiles and model registry, honouring `agentDir`.
+pub fn agent_dir(paths: &OpenClawPaths, config: &OpenClawConfig, agent_id: &str) -> PathBuf {
+    config
+        .agent_list()
+        .iter()
+        .find(|a| a.id == agent_id)
+        .and_then(|a| a.agent_dir.as_deref())
+        .filter(|d| !d.trim().is_empty())
+        .map(expand_home)
+        .unwrap_or_else(|| paths.agent_dir(agent_id))
+}
+
+/// What deleting an agent's files removes: `agents/<id>` (its agent dir and sessions), or
+/// its custom `agentDir` as long as that holds nothing else we know of.
+fn agent_files_dir(
+    paths: &OpenClawPaths,
+    config: &OpenClawConfig,
+    agent_id: &str,
+) -> Result<PathBuf, String> {
+    let dir = agent_dir(paths, config, agent_id);
+    if dir == paths.agent_dir(agent_id) {
+        return Ok(paths.home.join("agents").join(agent_id));
+    }
+    let shared = paths.home.starts_with(&dir)
+        || dirs::home_dir().is_some_and(|home| home.starts_with(&dir))
+        || config
+            .agent_list()
+            .iter()
+            .filter(|a| a.id != agent_id)
+            .any(|a| agent_dir(paths, config, &a.id).starts_with(&dir));
+    if shared {
+        return Err(format!(
+            "Refusing to delete {}: it holds more than this agent's files",
+            dir.display()
+        ));
+    }
+    Ok(dir)
+}
+
+pub fn agent_auth_path(paths: &OpenClawPaths, config: &OpenClawConfig, agent_id: &str) -> PathBuf {
+    agent_dir(paths, config, agent_id).join("auth-profiles.json")
+}
+
+fn summarize(paths: &OpenClawPaths, config: &OpenClawConfig) -> Vec<AgentSummary> {
+    let default_id = config.default_agent_id();
+    let default_model = config
+        .agents
+        .as_ref()
+        .and_then(|a| a.defaults.as_ref())
+        .and_then(|d| d.model.as_ref())
+        .and_then(|m| m.primary.clone());
+
+    let explicit = !config.agent_list().is_empty();
+    let entries: Vec<AgentEntry> = if explicit {
+        config.agent_list().to_vec()
+    } else {
+        vec![AgentEntry {
+            id: default_id.clone(),
+            ..Default::default()
+        }]
+    };
+
+    entries
+        .into_iter()
+        .map(|agent| {
+            let dir = agent_dir(paths, config, &agent.id);
+            // An unreadable auth file shows as no profiles here; editing it reports the error.
+            let auth = openclaw_config::load_auth_profiles(&dir.join("auth-profiles.json"))
+                .unwrap_or_default();
+            let auth_profiles = openclaw_config::auth_profile_map(&auth)
+                .iter()
+                .filter(|(id, value)| openclaw_config::is_auth_profile(id, value))
+                .map(|(id, value)| AgentAuthProfile {
+                    provider: value
+                        .get("provider")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    masked: value
+                        .get("key")
+                        .and_then(|v| v.as_str())
+                        .filter(|k| !k.is_empty())
+                        .map(mask_key),
+                    id: id.clone(),
+                })
+                .collect();
+            let bindings = config
+                .bindings()
+                .iter()
+                .filter(|b| b.agent_id == agent.id)
+                .map(|b| b.match_rule.clone())
+                .collect();
+            AgentSummary {
+                is_default: agent.id == default_id,
+                explicit,
+                effective_model: agent
+                    .model
+                    .as_ref()
+                    .and_then(|m| m.primary.clone())
+                    .or_else(|| default_model.clone()),
+                model: agent.model,
+                agent_dir: dir.to_string_lossy().to_string(),
+                auth_profiles,
+                bindings,
+                name: agent.name,
+                id: agent.id,
+            }
+        })
+        .collect()
+}
+
+fn require_agent<'a>(
+    config: &'a mut OpenClawConfig,
+    id: &str,
+) -> Result<&'a mut AgentEntry, String> {
+    config
+        .agent_mut(id)
+        .ok_or_else(|| format!("Unknown agent: {}", id))
+}
+
+/// Whether `id` is in `agents.list`, or is the implicit default agent of a config without one.
+fn agent_exists(config: &OpenClawConfig, id: &str) -> bool {
+    if config.agent_list().is_empty() {
+        id == config.default_agent_id()
+    } else {
+        config.agent_list().iter().any(|a| a.id == id)
+    }
+}
+
+fn check_agent_id(id: &str) -> Result<(), String> {
+    if !is_valid_agent_id(id) {
+        return Err(format!(
+            "Agent id \"{}\" must be lowercase letters, digits, - or _",
+            id
+        ));
+    }
+    Ok(())
+}
+
+fn check_new_agent_id(config: &OpenClawConfig, id: &str) -> Result<(), String> {
+    check_agent_id(id)?;
+    if agent_exists(config, id) {
+        return Err(format!("Agent \"{}\" already exists", id));
+    }
+    Ok(())
+}
+
+/// Checks that `id` names an existing agent before it is used as a directory name.
+fn check_existing_agent_id(config: &OpenClawConfig, id: &str) -> Result<(), String> {
+    check_agent_id(id)?;
+    if !agent_exists(config, id) {
+        return Err(format!("Unknown agent: {}", id));
+    }
+    Ok(())
+}
+
+/// Checks one half of a `<provider>:<name>` profile id.
+fn check_profile_part(what: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() || value.contains(':') || value.chars().any(char::is_whitespace) {
+        return Err(format!(
+            "Invalid {} \"{}\": it can't be empty or contain \":\" or spaces",
+            what, value
+        ));
+    }
+    Ok(())
+}
+
+/// Stages the key as `<provider>:<profile_name>` in the agent's auth profiles.
+fn set_auth(
+    change: &mut ConfigChange,
+    paths: &OpenClawPaths,
+    id: &str,
+    provider: &str,
+    key: &str,
+    profile_name: Option<&str>,
+) -> Result<(), String> {
+    let profile_name = profile_name.unwrap_or("default");
+    check_profile_part("provider", provider)?;
+    check_profile_part("profile name", profile_name)?;
+    let config = change.config_mut()?;
+    check_existing_agent_id(config, id)?;
+    let auth_path = agent_auth_path(paths, config, id);
+    openclaw_config::auth_profile_map_mut(change.auth_at_mut(auth_path)?).insert(
+        format!("{}:{}", provider, profile_name),
+        serde_json::json!({
+            "provider": provider,
+            "mode": "api_key",
+            "key": key.trim()
+        }),
+    );
+    Ok(())
+}
+
+/// Stages removing `profile_id` from the agent's auth profiles.
+fn remove_auth(
+    change: &mut ConfigChange,
+    paths: &OpenClawPaths,
+    id: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    let config = change.config_mut()?;
+    check_existing_agent_id(config, id)?;
+    let auth_path = agent_auth_path(paths, config, id);
+    openclaw_config::remove_auth_profile(change.auth_at_mut(auth_path)?, profile_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn openclaw_agents_list(
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<AgentSummary>, String> {
+    let paths = settings.paths();
+    let config = openclaw_config::load_config(&paths)?;
+    Ok(summarize(&paths, &config))
+}
+
+#[tauri::command]
+pub fn openclaw_agent_create(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    model: Option<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let config = change.config_mut()?;
+    check_new_agent_id(config, &id)?;
+    config.agent_list_mut().push(AgentEntry {
+        id,
+        name: name.filter(|n| !n.trim().is_empty()),
+        model: model
+            .filter(|m| !m.trim().is_empty())
+            .map(|primary| AgentModelConfig {
+                primary: Some(primary),
+                ..Default::default()
+            }),
+        ..Default::default()
+    });
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Creates `new_id` with the same settings (model, name, tools, ...) and auth profiles as
+/// `source_id`. Workspace, agent dir and bindings are not copied.
+#[tauri::command]
+pub fn openclaw_agent_clone(
+    app: AppHandle,
+    source_id: String,
+    new_id: String,
+    name: Option<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    let config = change.config_mut()?;
+    check_new_agent_id(config, &new_id)?;
+    let source = require_agent(config, &source_id)?.clone();
+    let source_auth = agent_auth_path(&paths, config, &source_id);
+    config.agent_list_mut().push(AgentEntry {
+        id: new_id.clone(),
+        name: name.filter(|n| !n.trim().is_empty()).or(source.name),
+        default: None,
+        workspace: None,
+        agent_dir: None,
+        ..source
+    });
+    let target_auth = agent_auth_path(&paths, config, &new_id);
+
+    let profiles = change.auth_at_mut(source_auth)?.clone();
+    *change.auth_at_mut(target_auth)? = profiles;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Removes the agent and its bindings from the config. With `delete_files`, its directory
+/// (`agents/<id>` or its `agentDir`) is deleted too; previews never delete files.
+#[tauri::command]
+pub fn openclaw_agent_delete(
+    app: AppHandle,
+    id: String,
+    delete_files: Option<bool>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    let config = change.config_mut()?;
+    require_agent(config, &id)?;
+    if config.default_agent_id() == id {
+        return Err(format!(
+            "\"{}\" is the default agent; make another agent the default first",
+            id
+        ));
+    }
+    let delete_files = delete_files.unwrap_or(false);
+    let dir = if delete_files {
+        Some(agent_files_dir(&paths, config, &id)?)
+    } else {
+        None
+    };
+    config.agent_list_mut().retain(|a| a.id != id);
+    config.bindings_mut().retain(|b| b.agent_id != id);
+
+    let dry_run = dry_run.unwrap_or(false);
+    let preview = apply_or_preview(&app, change, dry_run, &previews)?;
+    if let Some(dir) = dir.filter(|d| !dry_run && d.exists()) {
+        std::fs::remove_dir_all(&dir).map_err(|e| {
+            format!(
+                "Agent removed from config but failed to delete {}: {}",
+                dir.display(),
+                e
+            )
+        })?;
+    }
+    Ok(preview)
+}
+
+#[tauri::command]
+pub fn openclaw_agent_set_default(
+    app: AppHandle,
+    id: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let config = change.config_mut()?;
+    require_agent(config, &id)?;
+    for agent in config.agent_list_mut() {
+        agent.default = (agent.id == id).then_some(true);
+    }
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Sets the agent's own model. `None` clears the override so it follows
+/// `agents.defaults.model`.
+#[tauri::command]
+pub fn openclaw_agent_set_model(
+    app: AppHandle,
+    id: String,
+    model: Option<AgentModelConfig>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let agent = require_agent(change.config_mut()?, &id)?;
+    // Keep a shorthand `"model": "provider/id"` as a string if it stays primary-only.
+    let shorthand = agent.model.as_ref().is_some_and(|m| m.shorthand);
+    agent.model = model
+        .filter(|m| m.primary.is_some() || !m.fallback_list().is_empty())
+        .map(|m| AgentModelConfig { shorthand, ..m });
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Writes a vault key into the agent's auth profiles as `<provider>:<profile_name>`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn openclaw_agent_set_auth(
+    app: AppHandle,
+    id: String,
+    provider: String,
+    key_ref: String,
+    profile_name: Option<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let key = vault.resolve(&key_ref)?;
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    set_auth(
+        &mut change,
+        &paths,
+        &id,
+        &provider,
+        &key,
+        profile_name.as_deref(),
+    )?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[tauri::command]
+pub fn openclaw_agent_remove_auth(
+    app: AppHandle,
+    id: String,
+    profile_id: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    remove_auth(&mut change, &paths, &id, &profile_id)?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Routes a channel (optionally one account of it) to the agent, replacing whichever
+/// agent it was bound to before.
+#[tauri::command]
+pub fn openclaw_agent_bind(
+    app: AppHandle,
+    id: String,
+    channel: String,
+    account_id: Option<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let config = change.config_mut()?;
+    require_agent(config, &id)?;
+    let account_id = account_id.filter(|a| !a.trim().is_empty());
+    config.bindings_mut().retain(|b| {
+        !(b.match_rule.channel == channel
+            && b.match_rule.account_id == account_id
+            && b.match_rule.peer.is_none())
+    });
+    config.bindings_mut().push(AgentBinding {
+        agent_id: id,
+        match_rule: BindingMatch {
+            channel,
+            account_id,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[tauri::command]
+pub fn openclaw_agent_unbind(
+    app: AppHandle,
+    id: String,
+    channel: String,
+    account_id: Option<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let config = change.config_mut()?;
+    let account_id = account_id.filter(|a| !a.trim().is_empty());
+    config.bindings_mut().retain(|b| {
+        !(b.agent_id == id
+            && b.match_rule.channel == channel
+            && b.match_rule.account_id == account_id)
+    });
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::TempHome;
+
+    fn staged_profiles(change: &mut ConfigChange, path: PathBuf) -> Vec<String> {
+        openclaw_config::auth_profile_map(change.auth_at_mut(path).unwrap())
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn sets_auth_only_for_existing_agents() {
+        let home = TempHome::new();
+        home.write(
+            "openclaw.json",
+            r#"{ "agents": { "list": [{ "id": "work" }] } }"#,
+        );
+        let paths = &home.paths;
+
+        let mut change = ConfigChange::begin(paths);
+        set_auth(&mut change, paths, "work", "anthropic", " sk-ant-1 ", None).unwrap();
+        set_auth(&mut change, paths, "work", "openai", "sk-1", Some("ci")).unwrap();
+        let path = paths.agent_dir("work").join("auth-profiles.json");
+        assert_eq!(
+            staged_profiles(&mut change, path),
+            ["anthropic:default", "openai:ci"]
+        );
+
+        for id in ["../../x", "Work", "", "main", "other"] {
+            let mut change = ConfigChange::begin(paths);
+            assert!(
+                set_auth(&mut change, paths, id, "anthropic", "sk", None).is_err(),
+                "{}",
+                id
+            );
+        }
+        for (provider, name) in [("open ai", None), ("a:b", None), ("openai", Some("c i"))] {
+            let mut change = ConfigChange::begin(paths);
+            let err = set_auth(&mut change, paths, "work", provider, "sk", name).unwrap_err();
+            assert!(err.starts_with("Invalid"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn sets_auth_for_the_implicit_default_agent() {
+        let home = TempHome::new();
+        let paths = &home.paths;
+        let mut change = ConfigChange::begin(paths);
+        set_auth(&mut change, paths, "main", "anthropic", "sk", None).unwrap();
+        assert!(set_auth(&mut change, paths, "work", "anthropic", "sk", None).is_err());
+    }
+
+    #[test]
+    fn removes_auth_only_for_existing_agents() {
+        let home = TempHome::new();
+        home.write(
+            "openclaw.json",
+            r#"{ "agents": { "list": [{ "id": "work" }] } }"#,
+        );
+        home.write(
+            "agents/work/agent/auth-profiles.json",
+            r#"{ "profiles": {
+                "anthropic:default": { "provider": "anthropic", "key": "sk-ant-1" },
+                "openai:default": { "provider": "openai", "key": "sk-1" }
+            } }"#,
+        );
+        let paths = &home.paths;
+
+        let mut change = ConfigChange::begin(paths);
+        remove_auth(&mut change, paths, "work", "anthropic:default").unwrap();
+        let path = paths.agent_dir("work").join("auth-profiles.json");
+        assert_eq!(staged_profiles(&mut change, path), ["openai:default"]);
+
+        for id in ["../../x", "../work", "main", "other"] {
+            let mut change = ConfigChange::begin(paths);
+            let err = remove_auth(&mut change, paths, id, "anthropic:default").unwrap_err();
+            assert!(err.contains(id), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use super::openclaw_paths::OpenClawPaths;
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+use super::vault::random_id;
+
+/// How many backups to keep per file before the oldest are pruned.
+const MAX_BACKUPS_PER_FILE: usize = 10;
+
+/// Separates the timestamp from the encoded file path in backup file names.
+const BACKUP_SEPARATOR: &str = "__";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigBackup {
+    pub id: String,
+    /// Path of the backed-up file relative to the OpenClaw home, e.g. "openclaw.json", or
+    /// absolute for files outside it (an agent's custom `agentDir`).
+    pub file: String,
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// Writes `content` to `path` via a temp file in the same directory and a rename, so a
+/// crash mid-write leaves either the old file or the new one, never a truncated mix.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", path.display()))?;
+    std::fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // Random so concurrent writers of the same file never share a temp file.
+    let tmp_path = parent.join(format!(".{}.{}", file_name, random_id("tmp")));
+
+    let result = (|| {
+        let mut tmp = std::fs::File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        tmp.write_all(content)
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        tmp.sync_all()
+            .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+        if let Ok(meta) = std::fs::metadata(path) {
+            let _ = std::fs::set_permissions(&tmp_path, meta.permissions());
+        }
+        std::fs::rename(&tmp_path, path)
+            .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Backs up the current contents of `path` (if any and if they differ) and then writes
+/// `content` atomically. Returns the id of the backup taken, if one was.
+pub fn write_with_backup(
+    paths: &OpenClawPaths,
+    path: &Path,
+    content: &[u8],
+) -> Result<Option<String>, String> {
+    let backup = match std::fs::read(path) {
+        Ok(existing) if existing == content => return Ok(None),
+        Ok(_) => Some(backup_file(paths, path)?),
+        Err(_) => None,
+    };
+    write_atomic(path, content)?;
+    Ok(backup)
+}
+
+/// Encodes a path as a flat file name: relative to the OpenClaw home when it's under it
+/// ("a/b.json" -> "a%2Fb.json"), absolute otherwise ("/x/a.json" -> "%2Fx%2Fa.json").
+fn encode_path(paths: &OpenClawPaths, path: &Path) -> String {
+    let path = path.strip_prefix(&paths.home).unwrap_or(path);
+    // "%" first, so a literal "%2F" in a name can't decode to a separator.
+    path.to_string_lossy()
+        .replace('%', "%25")
+        .replace(['/', '\\'], "%2F")
+        .replace(':', "%3A")
+}
+
+fn decode_path(encoded: &str) -> PathBuf {
+    // Every "%" in `encoded` starts an escape, so "%25" can go last.
+    PathBuf::from(
+        encoded
+            .replace("%3A", ":")
+            .replace("%2F", std::path::MAIN_SEPARATOR_STR)
+            .replace("%25", "%"),
+    )
+}
+
+/// Copies `path` into the backups directory and prunes old backups of the same file.
+pub fn backup_file(paths: &OpenClawPaths, path: &Path) -> Result<String, String> {
+    let encoded = encode_path(paths, path);
+    let dir = paths.backups_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backups dir: {}", e))?;
+
+    let mut created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    // Two writes in the same millisecond would otherwise collide.
+    while dir
+        .join(format!("{}{}{}", created_at, BACKUP_SEPARATOR, encoded))
+        .exists()
+    {
+        created_at += 1;
+    }
+    let id = format!("{}{}{}", created_at, BACKUP_SEPARATOR, encoded);
+    std::fs::copy(path, dir.join(&id))
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+
+    prune_backups(paths, &encoded);
+    Ok(id)
+}
+
+fn prune_backups(paths: &OpenClawPaths, encoded: &str) {
+    let suffix = format!("{}{}", BACKUP_SEPARATOR, encoded);
+    // list_backups() is newest first, so everything past the limit is the oldest.
+    let stale = list_backups(paths)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|b| b.id.ends_with(&suffix))
+        .skip(MAX_BACKUPS_PER_FILE);
+    for old in stale {
+        let _ = std::fs::remove_file(paths.backups_dir().join(old.id));
+    }
+}
+
+/// All backups, newest first.
+pub fn list_backups(paths: &OpenClawPaths) -> Result<Vec<ConfigBackup>, String> {
+    let dir = paths.backups_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read backups dir: {}", e))?;
+
+    let mut backups: Vec<ConfigBackup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let (timestamp, encoded) = id.split_once(BACKUP_SEPARATOR)?;
+            let created_at = timestamp.parse::<u64>().ok()?;
+            Some(ConfigBackup {
+                file: decode_path(encoded).to_string_lossy().to_string(),
+                created_at,
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                id,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Restores backup `id` over its original file. The file being replaced is itself backed
+/// up first, so a restore can be undone.
+pub fn restore_backup(paths: &OpenClawPaths, id: &str) -> Result<ConfigBackup, String> {
+    let backup = list_backups(paths)?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or_else(|| format!("Backup not found: {}", id))?;
+    let content = std::fs::read(paths.backups_dir().join(&backup.id))
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    serde_json::from_slice::<serde_json::Value>(&content)
+        .map_err(|e| format!("Backup {} is not valid JSON: {}", id, e))?;
+
+    // An absolute `file` (outside the home) replaces the home in the join.
+    let target = paths.home.join(&backup.file);
+    write_with_backup(paths, &target, &content)?;
+    Ok(backup)
+}
+
+/// Writes a JSON file with a backup, then re-reads it. If the written file isn't exactly
+/// what was written or doesn't parse back to the same value, the previous version is
+/// restored, or the file removed when there was none.
+pub fn write_json_with_rollback(
+    paths: &OpenClawPaths,
+    path: &Path,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    let existed = path.exists();
+    let backup = write_with_backup(paths, path, content.as_bytes())?;
+
+    // Exact float comparison relies on serde_json's `float_roundtrip` feature.
+    let verified = std::fs::read(path).ok().is_some_and(|bytes| {
+        bytes == content.as_bytes()
+            && serde_json::from_slice::<serde_json::Value>(&bytes).is_ok_and(|w| &w == value)
+    });
+    if !verified {
+        let outcome = match backup {
+            Some(id) => {
+                restore_backup(paths, &id)?;
+                "previous version restored"
+            }
+            None if !existed => {
+                let _ = std::fs::remove_file(path);
+                "file removed"
+            }
+            None => "file left unchanged",
+        };
+        return Err(format!(
+            "Verification of {} failed after writing; {}",
+            path.display(),
+            outcome
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn openclaw_config_backups_list(
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<ConfigBackup>, String> {
+    list_backups(&settings.paths())
+}
+
+#[tauri::command]
+pub fn openclaw_config_restore(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    id: String,
+) -> Result<ConfigBackup, String> {
+    let _claim = operations::claim(&app, "Restoring a config backup", &[ExclusionGroup::Config])?;
+    restore_backup(&settings.paths(), &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::TempHome;
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn round_trips_backup_file_names() {
+        let home = TempHome::new();
+        let paths = &home.paths;
+        for file in ["openclaw.json", "agents/main/agent/auth-profiles.json", "100%2F/a:b.json"] {
+            let encoded = encode_path(paths, &paths.home.join(file));
+            assert!(!encoded.contains('/'), "{}", encoded);
+            assert_eq!(decode_path(&encoded), PathBuf::from(file));
+        }
+        let outside = PathBuf::from("/srv/agent/auth-profiles.json");
+        assert_eq!(decode_path(&encode_path(paths, &outside)), outside);
+    }
+
+    #[test]
+    fn writes_atomically_and_keeps_permissions() {
+        let home = TempHome::new();
+        let path = home.paths.home.join("nested/openclaw.json");
+        write_atomic(&path, b"one").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+        write_atomic(&path, b"two").unwrap();
+
+        assert_eq!(read(&path), "two");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let leftovers: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, ["openclaw.json"]);
+    }
+
+    #[test]
+    fn prunes_old_backups_per_file() {
+        let home = TempHome::new();
+        let paths = &home.paths;
+        let config = paths.config_path();
+        let auth = paths.auth_profiles_path();
+        write_with_backup(paths, &auth, b"auth 0").unwrap();
+        write_with_backup(paths, &auth, b"auth 1").unwrap();
+        for i in 0..=MAX_BACKUPS_PER_FILE + 2 {
+            write_with_backup(paths, &config, format!("config {}", i).as_bytes()).unwrap();
+        }
+        // Unchanged content takes no backup.
+        assert_eq!(write_with_backup(paths, &config, b"config 12").unwrap(), None);
+
+        let backups = list_backups(paths).unwrap();
+        let of = |file: &str| -> Vec<String> {
+            backups
+                .iter()
+                .filter(|b| b.file == file)
+                .map(|b| read(&paths.backups_dir().join(&b.id)))
+                .collect()
+        };
+        let kept = of("openclaw.json");
+        assert_eq!(kept.len(), MAX_BACKUPS_PER_FILE);
+        assert_eq!(kept.first().map(String::as_str), Some("config 11"));
+        assert_eq!(kept.last().map(String::as_str), Some("config 2"));
+        assert_eq!(of("agents/main/agent/auth-profiles.json"), ["auth 0"]);
+    }
+
+    #[test]
+    fn restores_a_backup_and_backs_up_what_it_replaces() {
+        let home = TempHome::new();
+        let paths = &home.paths;
+        let config = paths.config_path();
+        write_with_backup(paths, &config, br#"{"v":1}"#).unwrap();
+        let first = write_with_backup(paths, &config, br#"{"v":2}"#)
+            .unwrap()
+            .unwrap();
+
+        let restored = restore_backup(paths, &first).unwrap();
+        assert_eq!(restored.file, "openclaw.json");
+        assert_eq!(read(&config), r#"{"v":1}"#);
+        let undo = list_backups(paths).unwrap();
+        assert_eq!(read(&paths.backups_dir().join(&undo[0].id)), r#"{"v":2}"#);
+
+        assert!(restore_backup(paths, "missing").is_err());
+        std::fs::write(paths.backups_dir().join(&first), "not json").unwrap();
+        assert!(restore_backup(paths, &first).is_err());
+        assert_eq!(read(&config), r#"{"v":1}"#);
+    }
+
+    #[test]
+    fn writes_json_and_verifies_it() {
+        let home = TempHome::new();
+        let paths = &home.paths;
+        let config = paths.config_path();
+        let value = serde_json::json!({ "temperature": 0.1 + 0.2, "agents": { "list": [] } });
+        write_json_with_rollback(paths, &config, &value).unwrap();
+        write_json_with_rollback(paths, &config, &value).unwrap();
+
+        let written: serde_json::Value = serde_json::from_str(&read(&config)).unwrap();
+        assert_eq!(written, value);
+        assert!(list_backups(paths).unwrap().is_empty());
+
+        let next = serde_json::json!({ "agents": { "list": [{ "id": "main" }] } });
+        write_json_with_rollback(paths, &config, &next).unwrap();
+        assert_eq!(list_backups(paths).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,485 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use super::config_files;
+use super::openclaw_config::{
+    self, AuthProfiles, ConfigIssue, OpenClawConfig, OpenClawConfigSnapshot,
+};
+use super::openclaw_paths::OpenClawPaths;
+use super::operations::{self, Claim, ExclusionGroup};
+use super::vault::random_id;
+
+/// Previews older than this many are dropped when a new one is staged.
+const MAX_PENDING_PREVIEWS: usize = 16;
+
+/// Task label while a change is written.
+const SAVE_LABEL: &str = "Saving the OpenClaw config";
+
+/// Object keys whose values are replaced with "[redacted]" in diffs: "key" itself and any
+/// name ending in one of these, e.g. "apiKey" or "botToken". Compared in lowercase.
+const SECRET_SUFFIXES: &[&str] = &["apikey", "token", "password", "secret"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffEntry {
+    /// Dotted path within the file, e.g. "gateway.auth".
+    pub path: String,
+    pub op: DiffOp,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiff {
+    pub file: String,
+    pub changes: Vec<DiffEntry>,
+}
+
+/// What a mutating command would write, returned instead of writing when `dry_run` is set.
+/// Pass `id` to `openclaw_config_apply_preview` to write exactly this.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigPreview {
+    pub id: String,
+    pub files: Vec<FileDiff>,
+    /// Validation issues in the resulting openclaw.json.
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// A file as it was read when the change began, plus its edited value.
+struct Staged<T> {
+    path: PathBuf,
+    base: Option<Vec<u8>>,
+    /// The file as parsed from disk, not re-serialized from `next`, so the diff also shows
+    /// anything the typed model would change on its own.
+    before: serde_json::Value,
+    next: T,
+}
+
+impl<T: Serialize> Staged<T> {
+    fn load(path: PathBuf, load: impl FnOnce() -> Result<T, String>) -> Result<Self, String> {
+        let base = std::fs::read(&path).ok();
+        let next = load()?;
+        let before = match &base {
+            Some(bytes) => serde_json::from_slice(bytes)
+                .map_err(|e| format!("{} is not valid JSON: {}", path.display(), e))?,
+            None => serde_json::Value::Object(Default::default()),
+        };
+        Ok(Self {
+            path,
+            base,
+            before,
+            next,
+        })
+    }
+
+    fn next_value(&self) -> Result<serde_json::Value, String> {
+        serde_json::to_value(&self.next)
+            .map_err(|e| format!("Failed to serialize {}: {}", self.path.display(), e))
+    }
+
+    fn is_stale(&self) -> bool {
+        std::fs::read(&self.path).ok() != self.base
+    }
+
+    fn diff(&self, file: &str) -> Result<FileDiff, String> {
+        Ok(FileDiff {
+            file: file.to_string(),
+            changes: diff_values(&self.before, &self.next_value()?),
+        })
+    }
+
+    fn write_if_changed(&self, paths: &OpenClawPaths) -> Result<(), String> {
+        let next = self.next_value()?;
+        if next != self.before {
+            config_files::write_json_with_rollback(paths, &self.path, &next)?;
+        }
+        Ok(())
+    }
+}
+
+/// An edit to openclaw.json and/or auth-profiles.json that is computed in memory and can
+/// then be applied or turned into a preview. Each file is only read once it's touched.
+pub struct ConfigChange {
+    paths: OpenClawPaths,
+    config: Option<Staged<OpenClawConfig>>,
+    /// auth-profiles.json files by path; one per agent touched.
+    auth: BTreeMap<PathBuf, Staged<AuthProfiles>>,
+}
+
+impl ConfigChange {
+    pub fn begin(paths: &OpenClawPaths) -> Self {
+        Self {
+            paths: paths.clone(),
+            config: None,
+            auth: BTreeMap::new(),
+        }
+    }
+
+    pub fn config_mut(&mut self) -> Result<&mut OpenClawConfig, String> {
+        if self.config.is_none() {
+            self.config = Some(Staged::load(self.paths.config_path(), || {
+                openclaw_config::load_config(&self.paths)
+            })?);
+        }
+        Ok(&mut self.config.as_mut().expect("config loaded above").next)
+    }
+
+    /// Auth profiles of the active profile's agent.
+    pub fn auth_mut(&mut self) -> Result<&mut AuthProfiles, String> {
+        self.auth_at_mut(self.paths.auth_profiles_path())
+    }
+
+    /// Auth profiles stored at `path` (see `agents::agent_auth_path`).
+    pub fn auth_at_mut(&mut self, path: PathBuf) -> Result<&mut AuthProfiles, String> {
+        if !self.auth.contains_key(&path) {
+            let staged = Staged::load(path.clone(), || openclaw_config::load_auth_profiles(&path))?;
+            self.auth.insert(path.clone(), staged);
+        }
+        Ok(&mut self.auth.get_mut(&path).expect("auth profiles loaded above").next)
+    }
+
+    fn diff(&self) -> Result<Vec<FileDiff>, String> {
+        let mut files = Vec::new();
+        if let Some(config) = &self.config {
+            files.push(config.diff("openclaw.json")?);
+        }
+        for (path, auth) in &self.auth {
+            let file = path
+                .strip_prefix(&self.paths.home)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string();
+            files.push(auth.diff(&file)?);
+        }
+        files.retain(|f| !f.changes.is_empty());
+        Ok(files)
+    }
+
+    fn issues(&self) -> Vec<ConfigIssue> {
+        self.config
+            .as_ref()
+            .map(|c| c.next.validate())
+            .unwrap_or_default()
+    }
+
+    /// Writes the files that changed, holding `ExclusionGroup::Config` while it does. Fails
+    /// without writing anything if another task holds it, or if either file was modified
+    /// on disk after this change began.
+    pub fn apply(self, app: &AppHandle) -> Result<(), String> {
+        let claim = operations::claim(app, SAVE_LABEL, &[ExclusionGroup::Config])?;
+        self.apply_held(&claim)
+    }
+
+    /// Like `apply`, for a task that already holds `ExclusionGroup::Config` in `claim`.
+    pub fn apply_held(self, claim: &Claim) -> Result<(), String> {
+        if !claim.holds(ExclusionGroup::Config) {
+            return Err("Config changes must be made while holding the config".to_string());
+        }
+        self.write()
+    }
+
+    fn write(self) -> Result<(), String> {
+        let config_stale = self.config.as_ref().is_some_and(|c| c.is_stale());
+        let auth_stale = self.auth.values().any(|a| a.is_stale());
+        if config_stale || auth_stale {
+            return Err(
+                "The OpenClaw config changed on disk since this change was prepared — preview it again"
+                    .to_string(),
+            );
+        }
+        if let Some(config) = &self.config {
+            config.write_if_changed(&self.paths)?;
+        }
+        for auth in self.auth.values() {
+            auth.write_if_changed(&self.paths)?;
+        }
+        Ok(())
+    }
+}
+
+/// Previews waiting to be applied, keyed by id. Oldest are evicted first.
+#[derive(Default)]
+pub struct PendingPreviews {
+    pending: Mutex<VecDeque<(String, ConfigChange)>>,
+}
+
+impl PendingPreviews {
+    /// Stores `change` and returns a redacted preview of it.
+    pub fn stage(&self, change: ConfigChange) -> Result<ConfigPreview, String> {
+        let preview = ConfigPreview {
+            id: random_id("preview"),
+            files: change.diff()?,
+            issues: change.issues(),
+        };
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.push_back((preview.id.clone(), change));
+        while pending.len() > MAX_PENDING_PREVIEWS {
+            pending.pop_front();
+        }
+        Ok(preview)
+    }
+
+    pub fn take(&self, id: &str) -> Option<ConfigChange> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let index = pending.iter().position(|(pending_id, _)| pending_id == id)?;
+        pending.remove(index).map(|(_, change)| change)
+    }
+}
+
+/// Applies `change` now, or stages it and returns its preview when `dry_run` is set.
+pub fn apply_or_preview(
+    app: &AppHandle,
+    change: ConfigChange,
+    dry_run: bool,
+    previews: &PendingPreviews,
+) -> Result<Option<ConfigPreview>, String> {
+    if dry_run {
+        return previews.stage(change).map(Some);
+    }
+    change.apply(app)?;
+    Ok(None)
+}
+
+/// `apply_or_preview` for a task that claims `ExclusionGroup::Config` itself whenever
+/// `dry_run` isn't set.
+pub fn apply_or_preview_held(
+    claim: Option<&Claim>,
+    change: ConfigChange,
+    dry_run: bool,
+    previews: &PendingPreviews,
+) -> Result<Option<ConfigPreview>, String> {
+    if dry_run {
+        return previews.stage(change).map(Some);
+    }
+    change.apply_held(claim.ok_or("Config changes must be made while holding the config")?)?;
+    Ok(None)
+}
+
+// --- Diffing ---
+
+/// Lists added, removed and changed leaves between two JSON values. Objects are compared
+/// key by key; arrays and scalars are compared as whole values.
+pub fn diff_values(before: &serde_json::Value, after: &serde_json::Value) -> Vec<DiffEntry> {
+    let mut changes = Vec::new();
+    diff_at("", "", before, after, &mut changes);
+    changes
+}
+
+fn diff_at(
+    path: &str,
+    key: &str,
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+    changes: &mut Vec<DiffEntry>,
+) {
+    use serde_json::Value;
+
+    if let (Value::Object(a), Value::Object(b)) = (before, after) {
+        for (child_key, old) in a {
+            let child = join_path(path, child_key);
+            match b.get(child_key) {
+                Some(new) => diff_at(&child, child_key, old, new, changes),
+                None => changes.push(entry(&child, child_key, DiffOp::Removed, Some(old), None)),
+            }
+        }
+        for (child_key, new) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+            let child = join_path(path, child_key);
+            changes.push(entry(&child, child_key, DiffOp::Added, None, Some(new)));
+        }
+    } else if before != after {
+        changes.push(entry(path, key, DiffOp::Changed, Some(before), Some(after)));
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Builds a diff entry for the field `key` at `path`, redacting secrets in both values.
+fn entry(
+    path: &str,
+    key: &str,
+    op: DiffOp,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+) -> DiffEntry {
+    let redact_leaf = is_secret_key(key);
+    let show = |value: &serde_json::Value| {
+        if redact_leaf {
+            serde_json::json!("[redacted]")
+        } else {
+            redact(value)
+        }
+    };
+    DiffEntry {
+        path: path.to_string(),
+        op,
+        before: before.map(show),
+        after: after.map(show),
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key == "key" || SECRET_SUFFIXES.iter().any(|s| key.ends_with(s))
+}
+
+/// Copies `value` with every secret-looking field replaced by "[redacted]".
+pub fn redact(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_secret_key(k) {
+                        serde_json::json!("[redacted]")
+                    } else {
+                        redact(v)
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(redact).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+#[tauri::command]
+pub fn openclaw_config_apply_preview(
+    app: AppHandle,
+    id: String,
+    previews: State<'_, PendingPreviews>,
+) -> Result<OpenClawConfigSnapshot, String> {
+    // Claimed before the preview is taken, so a busy config leaves it to retry.
+    let claim = operations::claim(&app, SAVE_LABEL, &[ExclusionGroup::Config])?;
+    let change = previews
+        .take(&id)
+        .ok_or_else(|| format!("Preview not found or already applied: {}", id))?;
+    let paths = change.paths.clone();
+    change.apply_held(&claim)?;
+    openclaw_config::current_snapshot(&paths)
+}
+
+#[tauri::command]
+pub fn openclaw_config_discard_preview(id: String, previews: State<'_, PendingPreviews>) {
+    previews.take(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::openclaw_config::ProviderConfig;
+    use crate::commands::test_support::TempHome;
+
+    const CONFIG: &str = r#"{
+        "gateway": { "mode": "local", "auth": { "mode": "token", "token": "gw-old-secret" } },
+        "channels": { "telegram": { "botToken": "123:tg-secret" } }
+    }"#;
+
+    fn staged_text(preview: &ConfigPreview) -> String {
+        serde_json::to_string(preview).unwrap()
+    }
+
+    #[test]
+    fn redacts_keys_tokens_and_passwords() {
+        let home = TempHome::new();
+        home.write("openclaw.json", CONFIG);
+        let mut change = ConfigChange::begin(&home.paths);
+        let config = change.config_mut().unwrap();
+        config.gateway_mut().auth = Some(serde_json::json!({
+            "mode": "password",
+            "password": "gw-new-secret"
+        }));
+        config.providers_mut().insert(
+            "local".to_string(),
+            ProviderConfig {
+                api_key: Some("sk-provider-secret".to_string()),
+                ..Default::default()
+            },
+        );
+        config.channels = None;
+        openclaw_config::auth_profile_map_mut(change.auth_mut().unwrap()).insert(
+            "openrouter:default".to_string(),
+            serde_json::json!({ "provider": "openrouter", "key": "sk-or-secret" }),
+        );
+
+        let preview = PendingPreviews::default().stage(change).unwrap();
+        let text = staged_text(&preview);
+        for secret in ["gw-old", "gw-new", "sk-provider", "sk-or", "tg-secret"] {
+            assert!(!text.contains(secret), "{} leaked: {}", secret, text);
+        }
+        let files: Vec<_> = preview.files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(files, ["openclaw.json", "agents/main/agent/auth-profiles.json"]);
+        let paths: Vec<_> = preview.files[0].changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "channels",
+                "gateway.auth.mode",
+                "gateway.auth.token",
+                "gateway.auth.password",
+                "models"
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_to_apply_a_stale_preview() {
+        let home = TempHome::new();
+        home.write("openclaw.json", CONFIG);
+        let previews = PendingPreviews::default();
+        let stage = || {
+            let mut change = ConfigChange::begin(&home.paths);
+            change.config_mut().unwrap().gateway_mut().mode = Some("remote".to_string());
+            previews.stage(change).unwrap().id
+        };
+
+        let stale = stage();
+        let fresh = stage();
+        home.write("openclaw.json", r#"{ "gateway": { "mode": "local" } }"#);
+        let err = previews.take(&stale).unwrap().write().unwrap_err();
+        assert!(err.contains("changed on disk"), "{}", err);
+        let on_disk = std::fs::read_to_string(home.paths.config_path()).unwrap();
+        assert_eq!(on_disk, r#"{ "gateway": { "mode": "local" } }"#);
+        assert!(previews.take(&fresh).unwrap().write().is_err());
+
+        let current = stage();
+        previews.take(&current).unwrap().write().unwrap();
+        let written = openclaw_config::load_config(&home.paths).unwrap();
+        assert_eq!(written.gateway.unwrap().mode.as_deref(), Some("remote"));
+    }
+
+    #[test]
+    fn keeps_the_newest_previews_until_taken() {
+        let home = TempHome::new();
+        let previews = PendingPreviews::default();
+        let ids: Vec<String> = (0..=MAX_PENDING_PREVIEWS)
+            .map(|_| previews.stage(ConfigChange::begin(&home.paths)).unwrap().id)
+            .collect();
+
+        // An untouched change has nothing to show.
+        let last = previews.stage(ConfigChange::begin(&home.paths)).unwrap();
+        assert!(last.files.is_empty() && last.issues.is_empty());
+        assert!(previews.take(&ids[0]).is_none());
+        assert!(previews.take(&ids[1]).is_none());
+        assert!(previews.take(&ids[2]).is_some());
+        assert!(previews.take(&ids[2]).is_none());
+        assert!(previews.take(&last.id).is_some());
+    }
+}
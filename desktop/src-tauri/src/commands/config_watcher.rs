@@ -0,0 +1,275 @@
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::config_preview::{diff_values, DiffEntry};
+use super::openclaw_config::{self, ConfigIssue};
+use super::openclaw_paths::OpenClawPaths;
+use super::settings::SettingsState;
+
+/// Bursts of writes (editors, the CLI rewriting a file) within this window become one event.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How often to retry watching directories that don't exist yet (e.g. before onboarding).
+const REATTACH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigChangedEvent {
+    /// "openclaw.json" or "auth-profiles.json"
+    pub file: String,
+    pub path: String,
+    pub exists: bool,
+    /// What changed since the last time the file was seen, with secrets redacted.
+    pub changes: Vec<DiffEntry>,
+    /// All current problems with the file.
+    pub issues: Vec<ConfigIssue>,
+    /// Problems that weren't there before this change.
+    pub new_issues: Vec<ConfigIssue>,
+}
+
+/// Emitted as `openclaw-config-watcher-error` when watching fails.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigWatcherError {
+    pub message: String,
+    /// The watcher gave up, so outside changes are no longer reported.
+    pub stopped: bool,
+}
+
+fn report_error(app: &AppHandle, message: String, stopped: bool) {
+    let _ = app.emit(
+        "openclaw-config-watcher-error",
+        &ConfigWatcherError { message, stopped },
+    );
+}
+
+/// The last state we saw of a watched file.
+struct WatchedFile {
+    name: &'static str,
+    path: PathBuf,
+    value: serde_json::Value,
+    issues: Vec<ConfigIssue>,
+}
+
+impl WatchedFile {
+    fn new(name: &'static str, path: PathBuf) -> Self {
+        let (value, issues) = read_and_validate(name, &path);
+        Self {
+            name,
+            path,
+            value,
+            issues,
+        }
+    }
+
+    /// Re-reads the file and returns an event if its contents or problems changed.
+    fn refresh(&mut self) -> Option<ConfigChangedEvent> {
+        let (value, issues) = read_and_validate(self.name, &self.path);
+        let changes = diff_values(&self.value, &value);
+        if changes.is_empty() && issues == self.issues {
+            return None;
+        }
+        let new_issues = issues
+            .iter()
+            .filter(|issue| !self.issues.contains(issue))
+            .cloned()
+            .collect();
+        self.value = value;
+        self.issues = issues.clone();
+        Some(ConfigChangedEvent {
+            file: self.name.to_string(),
+            path: self.path.to_string_lossy().to_string(),
+            exists: self.path.exists(),
+            changes,
+            issues,
+            new_issues,
+        })
+    }
+}
+
+/// Reads a watched file as JSON. A missing file reads as `{}`; one that can't be parsed
+/// keeps `{}` as its value and reports the parse error as an issue.
+fn read_and_validate(name: &str, path: &Path) -> (serde_json::Value, Vec<ConfigIssue>) {
+    let empty = serde_json::json!({});
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return (empty, Vec::new()),
+    };
+    let parse_error = |message: String| {
+        (
+            serde_json::json!({}),
+            vec![ConfigIssue {
+                path: String::new(),
+                severity: "error".to_string(),
+                message,
+            }],
+        )
+    };
+
+    if name == "openclaw.json" {
+        match openclaw_config::parse_config(&content) {
+            Ok(config) => {
+                let issues = config.validate();
+                (serde_json::to_value(&config).unwrap_or(empty), issues)
+            }
+            Err(e) => parse_error(e),
+        }
+    } else {
+        match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(value) if value.is_object() => (value, Vec::new()),
+            Ok(_) => parse_error(format!("{} must contain a JSON object", name)),
+            Err(e) => parse_error(format!("{} is not valid JSON: {}", name, e)),
+        }
+    }
+}
+
+/// `path` with symlinks in its directory resolved (e.g. /var -> /private/var on macOS), so
+/// event paths and configured paths compare equal. The file itself needn't exist.
+fn canonical_path(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) => dir
+            .canonicalize()
+            .map(|dir| dir.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn watched_files(paths: &OpenClawPaths) -> [WatchedFile; 2] {
+    [
+        WatchedFile::new("openclaw.json", paths.config_path()),
+        WatchedFile::new("auth-profiles.json", paths.auth_profiles_path()),
+    ]
+}
+
+/// Starts a background thread that watches the active profile's openclaw.json and
+/// auth-profiles.json and emits `openclaw-config-changed` when either changes, including
+/// changes made by this app. Switching profiles moves the watch.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        if let Err(e) = run(app.clone()) {
+            report_error(&app, format!("OpenClaw config watcher stopped: {}", e), true);
+        }
+    });
+}
+
+fn run(app: AppHandle) -> Result<(), String> {
+    let mut paths = app.state::<SettingsState>().paths();
+    let mut files = watched_files(&paths);
+
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer =
+        new_debouncer(DEBOUNCE, tx).map_err(|e| format!("Failed to start watcher: {}", e))?;
+
+    // Watch the parent directories rather than the files: editors and our own atomic
+    // writes replace the file, which would silently end a watch on the file itself.
+    let mut attached: HashSet<PathBuf> = HashSet::new();
+    loop {
+        let mut reattached = false;
+        let active = app.state::<SettingsState>().paths();
+        if active != paths {
+            for dir in attached.drain() {
+                let _ = debouncer.watcher().unwatch(&dir);
+            }
+            files = watched_files(&active);
+            paths = active;
+            reattached = true;
+        }
+
+        let dirs: HashSet<PathBuf> = files
+            .iter()
+            .filter_map(|f| f.path.parent().map(Path::to_path_buf))
+            .collect();
+        attached.retain(|dir| dir.exists());
+        for dir in dirs {
+            if !dir.exists() || attached.contains(&dir) {
+                continue;
+            }
+            if debouncer
+                .watcher()
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .is_ok()
+            {
+                attached.insert(dir);
+                reattached = true;
+            }
+        }
+
+        let changed_paths: Vec<PathBuf> = match rx.recv_timeout(REATTACH_INTERVAL) {
+            Ok(Ok(events)) => events.iter().map(|e| canonical_path(&e.path)).collect(),
+            Ok(Err(e)) => {
+                report_error(&app, format!("OpenClaw config watcher error: {}", e), false);
+                Vec::new()
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => Vec::new(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("watcher channel closed".to_string())
+            }
+        };
+
+        for file in files.iter_mut() {
+            // A (re)attached directory may hide changes made while it wasn't watched.
+            if !reattached && !changed_paths.contains(&canonical_path(&file.path)) {
+                continue;
+            }
+            if let Some(event) = file.refresh() {
+                let _ = app.emit("openclaw-config-changed", &event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::TempHome;
+
+    #[cfg(unix)]
+    #[test]
+    fn matches_event_paths_through_symlinks() {
+        let home = TempHome::new();
+        home.write("real/openclaw.json", "{}");
+        let link = home.paths.home.join("link");
+        std::os::unix::fs::symlink(home.paths.home.join("real"), &link).unwrap();
+
+        let configured = link.join("openclaw.json");
+        let reported = home.paths.home.canonicalize().unwrap().join("real/openclaw.json");
+        assert_ne!(configured, reported);
+        assert_eq!(canonical_path(&configured), canonical_path(&reported));
+        // Deleted files still match through their directory.
+        assert_eq!(
+            canonical_path(&link.join("gone.json")),
+            canonical_path(&reported.with_file_name("gone.json"))
+        );
+    }
+
+    #[test]
+    fn reports_changes_and_new_issues() {
+        let home = TempHome::new();
+        let mut file = WatchedFile::new("auth-profiles.json", home.paths.auth_profiles_path());
+        assert!(file.refresh().is_none());
+
+        home.write(
+            "agents/main/agent/auth-profiles.json",
+            r#"{ "profiles": { "openrouter:default": { "key": "sk-or-secret" } } }"#,
+        );
+        let event = file.refresh().unwrap();
+        assert!(event.exists && event.new_issues.is_empty());
+        assert_eq!(event.changes.len(), 1);
+        assert!(!serde_json::to_string(&event).unwrap().contains("sk-or-secret"));
+        assert!(file.refresh().is_none());
+
+        home.write("agents/main/agent/auth-profiles.json", "[]");
+        let event = file.refresh().unwrap();
+        assert_eq!(event.new_issues.len(), 1);
+        assert_eq!(event.issues, event.new_issues);
+
+        std::fs::remove_file(home.paths.auth_profiles_path()).unwrap();
+        let event = file.refresh().unwrap();
+        assert!(!event.exists && event.issues.is_empty());
+    }
+}
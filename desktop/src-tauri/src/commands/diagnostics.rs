@@ -2,8 +2,13 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use sysinfo::{Disks, System};
+use tauri::State;
 
-pub const OPENCLAW_BASE: &str = "http://127.0.0.1:18789";
+use super::gateway::{self, ProbeResult};
+use super::openclaw::find_openclaw;
+use super::openclaw_version::{self, Support};
+use super::settings::SettingsState;
+use super::vault::Vault;
 
 const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
 
@@ -124,7 +129,13 @@ pub fn resolve_login_shell_path() -> Option<String> {
 }
 
 #[tauri::command]
-pub async fn diagnostics_full(api_key: String) -> Result<DiagnosticsReport, String> {
+pub async fn diagnostics_full(
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+) -> Result<DiagnosticsReport, String> {
+    let api_key = settings.openrouter_key(&vault).unwrap_or_default();
+    let openclaw_paths = settings.paths();
+
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()
@@ -206,7 +217,8 @@ pub async fn diagnostics_full(api_key: String) -> Result<DiagnosticsReport, Stri
     });
 
     // 3. OpenClaw installed
-    let openclaw_path = check_binary_in_path("openclaw");
+    let openclaw_path = find_openclaw();
+    let openclaw_installed = openclaw_path.is_some();
     checks.push(DiagnosticCheck {
         id: "openclaw-installed".to_string(),
         name: "OpenClaw Installed".to_string(),
@@ -226,33 +238,88 @@ pub async fn diagnostics_full(api_key: String) -> Result<DiagnosticsReport, Stri
         action: None,
     });
 
-    // 4. OpenClaw gateway running
-    let openclaw_gateway = if let Ok(c) = Client::builder()
-        .timeout(std::time::Duration::from_secs(3))
-        .build()
-    {
-        match c.get(OPENCLAW_BASE).send().await {
-            Ok(resp) => resp.status().is_success() || resp.status().as_u16() < 500,
-            Err(_) => false,
-        }
-    } else {
-        false
-    };
+    // 3b. OpenClaw version supported by this app
+    if openclaw_installed {
+        let version = openclaw_version::check(&openclaw_paths).await;
+        let supported = version.support == Some(Support::Supported);
+        checks.push(DiagnosticCheck {
+            id: "openclaw-version".to_string(),
+            name: "OpenClaw Version".to_string(),
+            status: if supported { "pass" } else { "warn" }.to_string(),
+            message: match version.support {
+                Some(Support::Supported) => "Supported by this app".to_string(),
+                Some(Support::Untested) => "Newer than this app was tested with".to_string(),
+                Some(Support::Unsupported) => "Not supported — upgrade OpenClaw".to_string(),
+                None => "Couldn't determine the version".to_string(),
+            },
+            metric: version.version.clone(),
+            detail: Some(format!(
+                "{} — supported from {}, tested up to {}",
+                version.message, version.min_supported, version.pinned
+            )),
+            action: if version.support == Some(Support::Unsupported) {
+                Some(DiagnosticAction {
+                    label: format!("Install {}", version.pinned),
+                    command: "upgrade-openclaw".to_string(),
+                })
+            } else {
+                None
+            },
+        });
+    }
+
+    // 4. OpenClaw gateway running, and accepting our token
+    let gateway_target = gateway::target(&openclaw_paths);
+    let gateway_check = gateway::verify(&gateway_target).await;
+    let gateway_ok = gateway_check.reachable && gateway_check.authenticated != Some(false);
+    let gateway_label = if gateway_check.remote { "Remote gateway" } else { "Gateway" };
+
+    let probe = &gateway_check.probe;
+    let port_taken = probe.result == ProbeResult::Foreign;
+    let mut gateway_detail = vec![gateway_check.url.clone(), gateway_check.message.clone()];
+    if let Some(uptime) = probe.uptime_secs {
+        gateway_detail.push(format!("up {}h {}m", uptime / 3600, uptime % 3600 / 60));
+    }
+    if let Some(mode) = &probe.auth_mode {
+        gateway_detail.push(format!("auth: {}", mode));
+    }
+    if !probe.channels.is_empty() {
+        gateway_detail.push(format!("channels: {}", probe.channels.join(", ")));
+    }
+    if !probe.plugins.is_empty() {
+        gateway_detail.push(format!("plugins: {}", probe.plugins.join(", ")));
+    }
 
     checks.push(DiagnosticCheck {
         id: "openclaw-gateway".to_string(),
         name: "OpenClaw Gateway".to_string(),
-        status: if openclaw_gateway { "pass" } else { "warn" }.to_string(),
-        message: if openclaw_gateway {
-            "Gateway running".to_string()
+        status: if gateway_ok {
+            "pass"
+        } else if port_taken {
+            "fail"
         } else {
-            "Gateway not running".to_string()
+            "warn"
+        }
+        .to_string(),
+        message: if port_taken {
+            "Gateway port is taken by another program".to_string()
+        } else if !gateway_check.reachable {
+            format!("{} not running", gateway_label)
+        } else if gateway_check.authenticated == Some(false) {
+            format!("{} rejects our token", gateway_label)
+        } else {
+            format!("{} running", gateway_label)
         },
-        metric: None,
-        detail: Some(OPENCLAW_BASE.to_string()),
-        action: if !openclaw_gateway {
+        metric: probe.version.clone(),
+        detail: Some(gateway_detail.join(" — ")),
+        action: if !gateway_ok && !port_taken {
             Some(DiagnosticAction {
-                label: "Start Gateway".to_string(),
+                label: if gateway_check.remote {
+                    "Restart Remote Gateway"
+                } else {
+                    "Start Gateway"
+                }
+                .to_string(),
                 command: "start-openclaw-gateway".to_string(),
             })
         } else {
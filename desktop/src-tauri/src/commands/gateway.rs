@@ -0,0 +1,663 @@
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use super::config_preview::{apply_or_preview, ConfigChange, ConfigPreview, PendingPreviews};
+use super::gateway_token;
+use super::openclaw::spawn_and_stream;
+use super::openclaw_config::{self, RemoteGatewayConfig};
+use super::openclaw_paths::OpenClawPaths;
+use super::settings::SettingsState;
+use super::vault::Vault;
+
+/// The gateway this app talks to: the local one, or the one in `gateway.remote` when
+/// the config is in remote mode.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayTarget {
+    /// HTTP base URL, e.g. "http://127.0.0.1:18789".
+    pub url: String,
+    pub remote: bool,
+    #[serde(skip)]
+    pub token: Option<String>,
+    pub ssh_target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeResult {
+    Running,
+    NotRunning,
+    /// Something answers on the gateway's port, but it isn't OpenClaw.
+    Foreign,
+}
+
+/// What the gateway's health endpoint reports about itself.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayProbe {
+    pub url: String,
+    pub result: ProbeResult,
+    pub version: Option<String>,
+    pub uptime_secs: Option<u64>,
+    /// e.g. "token" or "none"; `None` when the gateway doesn't say.
+    pub auth_mode: Option<String>,
+    pub channels: Vec<String>,
+    pub plugins: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayCheck {
+    pub url: String,
+    pub remote: bool,
+    /// An OpenClaw gateway answers at `url`.
+    pub reachable: bool,
+    /// `None` when the gateway couldn't be asked, e.g. it is unreachable.
+    pub authenticated: Option<bool>,
+    pub message: String,
+    pub probe: GatewayProbe,
+}
+
+/// Input for `openclaw_gateway_set_remote`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteGatewaySetup {
+    /// ws://, wss://, http:// or https:// URL of the remote gateway.
+    pub url: String,
+    /// Vault reference for the remote gateway's token; `None` keeps the current one.
+    pub token_ref: Option<String>,
+    /// SSH destination used to restart the remote gateway.
+    pub ssh_target: Option<String>,
+}
+
+/// Converts a gateway URL to the HTTP base URL its HTTP endpoints live under.
+pub fn http_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let converted = if let Some(rest) = url.strip_prefix("ws://") {
+        format!("http://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("wss://") {
+        format!("https://{}", rest)
+    } else {
+        url.to_string()
+    };
+    let parsed =
+        reqwest::Url::parse(&converted).map_err(|e| format!("Invalid gateway URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Gateway URL must use ws, wss, http or https, not {}",
+            parsed.scheme()
+        ));
+    }
+    Ok(converted)
+}
+
+/// The WebSocket URL for an HTTP base URL.
+pub fn ws_url(http_url: &str) -> String {
+    if let Some(rest) = http_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = http_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        http_url.to_string()
+    }
+}
+
+/// Resolves the gateway for `paths`. An unreadable config falls back to the local gateway.
+pub fn target(paths: &OpenClawPaths) -> GatewayTarget {
+    let config = openclaw_config::load_config(paths).unwrap_or_default();
+    let gateway = config.gateway.as_ref();
+    let remote = gateway
+        .filter(|g| g.mode.as_deref() == Some("remote"))
+        .and_then(|g| g.remote.as_ref());
+    let remote_url = remote
+        .and_then(|r| r.url.as_deref())
+        .and_then(|u| http_url(u).ok());
+    match (remote, remote_url) {
+        (Some(remote), Some(url)) => GatewayTarget {
+            url,
+            remote: true,
+            token: remote.token.clone().filter(|t| !t.is_empty()),
+            ssh_target: remote.ssh_target.clone().filter(|t| !t.trim().is_empty()),
+        },
+        _ => GatewayTarget {
+            url: paths.gateway_url.clone(),
+            remote: false,
+            token: gateway_token::config_token(&config),
+            ssh_target: None,
+        },
+    }
+}
+
+/// Health payload keys only an OpenClaw gateway reports.
+const OPENCLAW_HEALTH_KEYS: [&str; 4] = ["channels", "uptimeMs", "heartbeatSeconds", "sessions"];
+
+/// Names from a health payload list, which is either an object keyed by name or an array
+/// of names or `{ id | name }` objects.
+fn health_names(value: Option<&serde_json::Value>) -> Vec<String> {
+    match value {
+        Some(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                serde_json::Value::String(name) => Some(name.clone()),
+                _ => item
+                    .get("id")
+                    .or_else(|| item.get("name"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// What `/health` answered: status, `x-openclaw-version` header, and the payload if it
+/// looks like OpenClaw's.
+struct HealthAnswer {
+    status: StatusCode,
+    header_version: Option<String>,
+    health: Option<serde_json::Value>,
+}
+
+async fn read_health(
+    client: &Client,
+    url: &str,
+    token: Option<&str>,
+) -> Result<HealthAnswer, reqwest::Error> {
+    let mut request = client.get(format!("{}/health", url));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let resp = request.send().await?;
+    let status = resp.status();
+    let header_version = resp
+        .headers()
+        .get("x-openclaw-version")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let health = resp.json().await.ok().filter(|v: &serde_json::Value| {
+        v.as_object()
+            .is_some_and(|o| OPENCLAW_HEALTH_KEYS.iter().any(|k| o.contains_key(*k)))
+    });
+    Ok(HealthAnswer {
+        status,
+        header_version,
+        health,
+    })
+}
+
+/// Asks `url` whether it is an OpenClaw gateway. Reads `/health` and falls back to the
+/// Control UI page, so a different program on the port reports as `Foreign` rather than as
+/// a running gateway. `token` is only sent once the gateway has identified itself, for the
+/// health details of a gateway that keeps them behind auth.
+pub async fn probe(url: &str, token: Option<&str>) -> GatewayProbe {
+    let result = |result, message: String| GatewayProbe {
+        url: url.to_string(),
+        result,
+        version: None,
+        uptime_secs: None,
+        auth_mode: None,
+        channels: Vec::new(),
+        plugins: Vec::new(),
+        message,
+    };
+    let client = match Client::builder().timeout(Duration::from_secs(3)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            return result(
+                ProbeResult::NotRunning,
+                format!("Failed to build HTTP client: {}", e),
+            )
+        }
+    };
+
+    let answer = match read_health(&client, url, None).await {
+        Ok(answer) => answer,
+        Err(e) if e.is_connect() || e.is_timeout() => {
+            return result(
+                ProbeResult::NotRunning,
+                format!("Nothing answers at {}", url),
+            )
+        }
+        Err(e) => {
+            return result(
+                ProbeResult::Foreign,
+                format!(
+                    "Something other than an HTTP server is listening at {}: {}",
+                    url, e
+                ),
+            )
+        }
+    };
+    let HealthAnswer {
+        mut status,
+        mut header_version,
+        health,
+    } = answer;
+
+    let health = match health {
+        Some(health) => health,
+        None => {
+            // The health endpoint didn't identify the gateway; the Control UI page names it.
+            let ui = match client.get(url).send().await {
+                Ok(resp) => resp.text().await.unwrap_or_default(),
+                Err(_) => String::new(),
+            };
+            if header_version.is_none() && !ui.to_lowercase().contains("openclaw") {
+                return result(
+                    ProbeResult::Foreign,
+                    format!(
+                        "The port at {} is taken by something that isn't OpenClaw",
+                        url
+                    ),
+                );
+            }
+            // It is OpenClaw, so the token can be sent for the details.
+            let authed = match token {
+                Some(token) => read_health(&client, url, Some(token)).await.ok(),
+                None => None,
+            };
+            match authed {
+                Some(HealthAnswer {
+                    health: Some(health),
+                    header_version: authed_version,
+                    ..
+                }) => {
+                    header_version = authed_version.or(header_version);
+                    health
+                }
+                authed => {
+                    if let Some(authed) = authed {
+                        status = authed.status;
+                        header_version = authed.header_version.or(header_version);
+                    }
+                    let mut probe = result(
+                        ProbeResult::Running,
+                        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+                        {
+                            "OpenClaw gateway running; its health details need a valid token"
+                                .to_string()
+                        } else {
+                            "OpenClaw gateway running".to_string()
+                        },
+                    );
+                    probe.version = header_version;
+                    return probe;
+                }
+            }
+        }
+    };
+
+    let str_at = |pointer: &str| {
+        health
+            .pointer(pointer)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let version = str_at("/version")
+        .or_else(|| str_at("/gateway/version"))
+        .or(header_version);
+    let uptime_secs = health
+        .get("uptimeMs")
+        .and_then(|v| v.as_u64())
+        .map(|ms| ms / 1000)
+        .or_else(|| health.get("uptimeSeconds").and_then(|v| v.as_u64()));
+    GatewayProbe {
+        url: url.to_string(),
+        result: ProbeResult::Running,
+        message: match &version {
+            Some(version) => format!("OpenClaw gateway {} running", version),
+            None => "OpenClaw gateway running".to_string(),
+        },
+        version,
+        uptime_secs,
+        auth_mode: str_at("/auth/mode").or_else(|| str_at("/authMode")),
+        channels: health_names(health.get("channels")),
+        plugins: health_names(health.get("plugins")),
+    }
+}
+
+/// Checks that an OpenClaw gateway answers and accepts `token`. The tools endpoint sits
+/// behind gateway auth, so an empty invocation is rejected with 401/403 when the token is
+/// wrong. Any other answer, usually 400 for the empty body, comes from past the auth check.
+pub async fn verify(target: &GatewayTarget) -> GatewayCheck {
+    let probe = probe(&target.url, target.token.as_deref()).await;
+    let check = |reachable, authenticated, message: String| GatewayCheck {
+        url: target.url.clone(),
+        remote: target.remote,
+        reachable,
+        authenticated,
+        message,
+        probe: probe.clone(),
+    };
+    if probe.result != ProbeResult::Running {
+        return check(false, None, probe.message.clone());
+    }
+    let client = match Client::builder().timeout(Duration::from_secs(5)).build() {
+        Ok(client) => client,
+        Err(e) => return check(true, None, format!("Failed to build HTTP client: {}", e)),
+    };
+    let mut request = client
+        .post(format!("{}/tools/invoke", target.url))
+        .json(&serde_json::json!({}));
+    if let Some(token) = &target.token {
+        request = request.bearer_auth(token);
+    }
+    match request.send().await {
+        Ok(resp) => match resp.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => check(
+                true,
+                Some(false),
+                if target.token.is_some() {
+                    "The gateway rejected the token".to_string()
+                } else {
+                    "The gateway requires a token".to_string()
+                },
+            ),
+            _ if target.token.is_some() => {
+                check(true, Some(true), "Connected and authenticated".to_string())
+            }
+            _ => check(
+                true,
+                Some(true),
+                "Connected; the gateway doesn't require a token".to_string(),
+            ),
+        },
+        Err(e) => check(true, None, format!("Auth check failed: {}", e)),
+    }
+}
+
+/// Restarts the gateway: locally through the CLI, or over SSH on the remote host.
+pub async fn restart(app: &AppHandle, paths: &OpenClawPaths) -> Result<(), String> {
+    let target = target(paths);
+    if !target.remote {
+        return spawn_and_stream(app, paths, "openclaw", &["gateway", "restart"]).await;
+    }
+    let ssh_target = target
+        .ssh_target
+        .ok_or("The remote gateway can't be restarted from here; set gateway.remote.sshTarget")?;
+    check_ssh_target(&ssh_target)?;
+    spawn_and_stream(
+        app,
+        paths,
+        "ssh",
+        &[
+            "-o",
+            "BatchMode=yes",
+            "--",
+            &ssh_target,
+            "openclaw",
+            "gateway",
+            "restart",
+        ],
+    )
+    .await
+}
+
+/// Rejects SSH destinations ssh would read as an option, e.g. "-oProxyCommand=...".
+fn check_ssh_target(ssh_target: &str) -> Result<(), String> {
+    if ssh_target.starts_with('-') {
+        return Err(format!("Invalid SSH target: {}", ssh_target));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_target(
+    settings: State<'_, SettingsState>,
+) -> Result<GatewayTarget, String> {
+    Ok(target(&settings.paths()))
+}
+
+#[tauri::command]
+pub async fn openclaw_gateway_verify(
+    settings: State<'_, SettingsState>,
+) -> Result<GatewayCheck, String> {
+    Ok(verify(&target(&settings.paths())).await)
+}
+
+/// Points OpenClaw at a gateway on another machine. The gateway is checked first unless
+/// `force` is set.
+#[tauri::command]
+pub async fn openclaw_gateway_set_remote(
+    app: AppHandle,
+    setup: RemoteGatewaySetup,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let url = http_url(&setup.url)?;
+    let ssh_target = setup
+        .ssh_target
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    if let Some(ssh_target) = &ssh_target {
+        check_ssh_target(ssh_target)?;
+    }
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    let gateway = change.config_mut()?.gateway_mut();
+    let remote = gateway
+        .remote
+        .get_or_insert_with(RemoteGatewayConfig::default);
+    remote.url = Some(ws_url(&url));
+    if let Some(key_ref) = &setup.token_ref {
+        remote.token = Some(vault.resolve(key_ref)?.trim().to_string());
+    }
+    remote.ssh_target = ssh_target;
+    let target = GatewayTarget {
+        url,
+        remote: true,
+        token: remote.token.clone(),
+        ssh_target: remote.ssh_target.clone(),
+    };
+    gateway.mode = Some("remote".to_string());
+
+    if !force.unwrap_or(false) {
+        let check = verify(&target).await;
+        if !check.reachable || check.authenticated == Some(false) {
+            return Err(check.message);
+        }
+    }
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Switches back to the local gateway. `gateway.remote` is kept so switching back to
+/// remote doesn't need the details again.
+#[tauri::command]
+pub fn openclaw_gateway_set_local(
+    app: AppHandle,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let config = change.config_mut()?;
+    config.gateway_mut().mode = Some("local".to_string());
+    gateway_token::ensure_config_token(config);
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{closed_addr, MockResponse, MockServer, TempHome};
+
+    /// A stand-in gateway that accepts `token` on the tools endpoint and, like OpenClaw,
+    /// checks the token before the body.
+    async fn mock_gateway(token: &'static str) -> MockServer {
+        let bearer = format!("Bearer {}", token);
+        MockServer::start(move |req| match req.path.as_str() {
+            "/tools/invoke" if req.header("authorization") != Some(bearer.as_str()) => {
+                MockResponse::new(401, "unauthorized")
+            }
+            "/tools/invoke" => match serde_json::from_slice::<serde_json::Value>(&req.body) {
+                Ok(body) if body.get("tool").and_then(|t| t.as_str()).is_some() => {
+                    MockResponse::json(200, serde_json::json!({ "ok": true }))
+                }
+                _ => MockResponse::json(400, serde_json::json!({ "error": "tool is required" })),
+            },
+            "/health" => MockResponse::json(
+                200,
+                serde_json::json!({
+                    "version": "2026.2.1",
+                    "uptimeMs": 90_000,
+                    "channels": { "telegram": {} },
+                    "auth": { "mode": "token" },
+                }),
+            ),
+            _ => MockResponse::new(404, "not found"),
+        })
+        .await
+    }
+
+    fn target_at(url: String, token: Option<&str>) -> GatewayTarget {
+        GatewayTarget {
+            url,
+            remote: true,
+            token: token.map(String::from),
+            ssh_target: None,
+        }
+    }
+
+    #[test]
+    fn converts_between_ws_and_http_urls() {
+        assert_eq!(http_url("ws://10.0.0.2:18789/").unwrap(), "http://10.0.0.2:18789");
+        assert_eq!(http_url(" wss://gw.example.com ").unwrap(), "https://gw.example.com");
+        assert_eq!(http_url("http://127.0.0.1:18789").unwrap(), "http://127.0.0.1:18789");
+        assert!(http_url("ftp://gw.example.com").is_err());
+        assert!(http_url("not a url").is_err());
+
+        assert_eq!(ws_url("http://127.0.0.1:18789"), "ws://127.0.0.1:18789");
+        assert_eq!(ws_url("https://gw.example.com"), "wss://gw.example.com");
+    }
+
+    #[test]
+    fn target_follows_the_gateway_mode() {
+        let home = TempHome::new();
+        home.write(
+            "openclaw.json",
+            r#"{ "gateway": { "mode": "local", "auth": { "mode": "token", "token": "local-tok" },
+                "remote": { "url": "wss://gw.example.com", "token": "remote-tok" } } }"#,
+        );
+        let local = target(&home.paths);
+        assert!(!local.remote);
+        assert_eq!(local.url, home.paths.gateway_url);
+        assert_eq!(local.token.as_deref(), Some("local-tok"));
+
+        home.write(
+            "openclaw.json",
+            r#"{ "gateway": { "mode": "remote", "remote": { "url": "wss://gw.example.com/",
+                "token": "remote-tok", "sshTarget": "me@gw" } } }"#,
+        );
+        let remote = target(&home.paths);
+        assert!(remote.remote);
+        assert_eq!(remote.url, "https://gw.example.com");
+        assert_eq!(remote.token.as_deref(), Some("remote-tok"));
+        assert_eq!(remote.ssh_target.as_deref(), Some("me@gw"));
+
+        // A remote mode without a usable URL falls back to the local gateway.
+        home.write("openclaw.json", r#"{ "gateway": { "mode": "remote", "remote": {} } }"#);
+        assert!(!target(&home.paths).remote);
+    }
+
+    #[test]
+    fn rejects_ssh_targets_that_look_like_options() {
+        assert!(check_ssh_target("me@gw.example.com").is_ok());
+        assert!(check_ssh_target("-oProxyCommand=touch /tmp/x").is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_checks_the_token_against_the_gateway() {
+        let server = mock_gateway("right").await;
+
+        let check = verify(&target_at(server.url(""), Some("right"))).await;
+        assert!(check.reachable);
+        assert_eq!(check.authenticated, Some(true));
+        assert_eq!(check.probe.version.as_deref(), Some("2026.2.1"));
+        assert_eq!(check.probe.uptime_secs, Some(90));
+        assert_eq!(check.probe.channels, vec!["telegram".to_string()]);
+
+        let check = verify(&target_at(server.url(""), Some("wrong"))).await;
+        assert!(check.reachable);
+        assert_eq!(check.authenticated, Some(false));
+        assert_eq!(check.message, "The gateway rejected the token");
+
+        let check = verify(&target_at(server.url(""), None)).await;
+        assert_eq!(check.authenticated, Some(false));
+        assert_eq!(check.message, "The gateway requires a token");
+    }
+
+    #[tokio::test]
+    async fn verify_takes_answers_past_auth_as_accepted() {
+        for status in [400, 404, 500] {
+            let server = MockServer::start(move |req| match req.path.as_str() {
+                "/health" => MockResponse::json(200, serde_json::json!({ "uptimeMs": 1000 })),
+                _ => MockResponse::new(status, "nope"),
+            })
+            .await;
+            let check = verify(&target_at(server.url(""), Some("tok"))).await;
+            assert!(check.reachable);
+            assert_eq!(check.authenticated, Some(true), "HTTP {}", status);
+
+            let check = verify(&target_at(server.url(""), None)).await;
+            assert_eq!(check.authenticated, Some(true), "HTTP {}", status);
+            assert!(check.message.contains("doesn't require a token"));
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_sends_the_token_only_to_an_identified_gateway() {
+        let seen_token = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let seen = seen_token.clone();
+        let foreign = MockServer::start(move |req| {
+            if req.header("authorization").is_some() {
+                seen.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+            MockResponse::new(200, "<html>Grafana</html>")
+        })
+        .await;
+        let probed = probe(&foreign.url(""), Some("secret")).await;
+        assert_eq!(probed.result, ProbeResult::Foreign);
+        assert!(!seen_token.load(std::sync::atomic::Ordering::SeqCst));
+
+        // A gateway that keeps its health details behind auth gets the token once it has
+        // named itself.
+        let gateway = MockServer::start(|req| {
+            match (req.path.as_str(), req.header("authorization")) {
+                ("/health", Some("Bearer secret")) => MockResponse::json(
+                    200,
+                    serde_json::json!({ "version": "2026.2.1", "uptimeMs": 5000 }),
+                ),
+                ("/health", _) => {
+                    MockResponse::new(401, "unauthorized").header("x-openclaw-version", "2026.2.1")
+                }
+                _ => MockResponse::new(200, "<title>OpenClaw Control</title>"),
+            }
+        })
+        .await;
+        let probed = probe(&gateway.url(""), Some("secret")).await;
+        assert_eq!(probed.result, ProbeResult::Running);
+        assert_eq!(probed.uptime_secs, Some(5));
+
+        let probed = probe(&gateway.url(""), Some("wrong")).await;
+        assert_eq!(probed.result, ProbeResult::Running);
+        assert_eq!(probed.version.as_deref(), Some("2026.2.1"));
+        assert!(probed.message.contains("need a valid token"), "{}", probed.message);
+    }
+
+    #[tokio::test]
+    async fn verify_reports_unreachable_and_foreign_gateways() {
+        let check = verify(&target_at(format!("http://{}", closed_addr().await), None)).await;
+        assert!(!check.reachable);
+        assert_eq!(check.probe.result, ProbeResult::NotRunning);
+
+        let server = MockServer::start(|_| MockResponse::new(200, "<html>Grafana</html>")).await;
+        let check = verify(&target_at(server.url(""), None)).await;
+        assert!(!check.reachable);
+        assert_eq!(check.authenticated, None);
+        assert_eq!(check.probe.result, ProbeResult::Foreign);
+    }
+}
@@ -0,0 +1,219 @@
+use reqwest::{Client, StatusCode};
+use tauri::{AppHandle, Emitter, State};
+
+use super::config_preview::{apply_or_preview, ConfigChange, ConfigPreview, PendingPreviews};
+use super::gateway::{self, GatewayTarget};
+use super::openclaw_config::is_valid_agent_id;
+use super::openrouter::{read_chat_response, ChatResponse, Message};
+use super::settings::SettingsState;
+
+/// Header the gateway reads to pick the agent that answers.
+const AGENT_HEADER: &str = "x-openclaw-agent-id";
+
+/// Sends a conversation to an OpenClaw agent through the gateway's OpenAI-compatible
+/// endpoint, so the reply comes with the agent's tools, memory and skills. Streams
+/// `chat-token` events like `openrouter_chat`. `agent_id` defaults to the profile's agent.
+#[tauri::command]
+pub async fn openclaw_gateway_chat(
+    app: AppHandle,
+    messages: Vec<Message>,
+    stream: bool,
+    agent_id: Option<String>,
+    settings: State<'_, SettingsState>,
+) -> Result<ChatResponse, String> {
+    let paths = settings.paths();
+    let agent_id = agent_id.unwrap_or_else(|| paths.agent_id.clone());
+    if !is_valid_agent_id(&agent_id) {
+        return Err(format!("Invalid agent id: {}", agent_id));
+    }
+    let resp = send_chat(&gateway::target(&paths), &agent_id, &messages, stream).await?;
+    read_chat_response(resp, stream, |event| {
+        let _ = app.emit("chat-token", event);
+    })
+    .await
+}
+
+/// Posts the conversation to `target`'s chat endpoint and turns error statuses into
+/// messages the user can act on.
+async fn send_chat(
+    target: &GatewayTarget,
+    agent_id: &str,
+    messages: &[Message],
+    stream: bool,
+) -> Result<reqwest::Response, String> {
+    let body = serde_json::json!({
+        "model": format!("openclaw:{}", agent_id),
+        "messages": messages,
+        "stream": stream,
+    });
+    let mut request = Client::new()
+        .post(format!("{}/v1/chat/completions", target.url))
+        .header(AGENT_HEADER, agent_id)
+        .json(&body);
+    if let Some(token) = &target.token {
+        request = request.bearer_auth(token);
+    }
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the OpenClaw gateway: {}", e))?;
+
+    match resp.status() {
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+            return Err(
+                "The gateway's chat endpoint is off — enable it in Settings → Gateway".to_string(),
+            )
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            return Err("The gateway rejected our token".to_string())
+        }
+        status if !status.is_success() => {
+            let err_text = resp.text().await.unwrap_or_default();
+            return Err(format!(
+                "Gateway chat failed: HTTP {}: {}",
+                status, err_text
+            ));
+        }
+        _ => {}
+    }
+    Ok(resp)
+}
+
+/// Turns on the gateway's OpenAI-compatible `/v1/chat/completions` endpoint, which
+/// OpenClaw ships disabled. The gateway picks it up on restart.
+#[tauri::command]
+pub fn openclaw_gateway_chat_enable(
+    app: AppHandle,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let mut change = ConfigChange::begin(&settings.paths());
+    let gateway = change.config_mut()?.gateway_mut();
+    let mut http = gateway
+        .extra
+        .remove("http")
+        .filter(|v| v.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    // Indexing a missing key creates it; anything that isn't an object is replaced.
+    if !http["endpoints"].is_object() {
+        http["endpoints"] = serde_json::json!({});
+    }
+    if !http["endpoints"]["chatCompletions"].is_object() {
+        http["endpoints"]["chatCompletions"] = serde_json::json!({});
+    }
+    http["endpoints"]["chatCompletions"]["enabled"] = true.into();
+    gateway.extra.insert("http".to_string(), http);
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::openrouter::ChatTokenEvent;
+    use crate::commands::test_support::{MockResponse, MockServer};
+    use std::sync::Mutex;
+
+    fn target(server: &MockServer) -> GatewayTarget {
+        GatewayTarget {
+            url: server.url(""),
+            remote: false,
+            token: Some("gw-token".to_string()),
+            ssh_target: None,
+        }
+    }
+
+    fn hello() -> Vec<Message> {
+        vec![Message {
+            role: "user".to_string(),
+            content: "hello".to_string(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn streams_the_agents_reply() {
+        let server = MockServer::start(|req| {
+            assert_eq!(req.method, "POST");
+            assert_eq!(req.path, "/v1/chat/completions");
+            assert_eq!(req.header("authorization"), Some("Bearer gw-token"));
+            assert_eq!(req.header(AGENT_HEADER), Some("work"));
+            let body = req.json();
+            assert_eq!(body["model"], "openclaw:work");
+            assert_eq!(body["stream"], true);
+            assert_eq!(body["messages"][0]["content"], "hello");
+            let sse = [
+                r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#,
+                r#"data: {"choices":[{"delta":{"content":"Hi "}}]}"#,
+                r#"data: {"choices":[{"delta":{"content":"there"}}]}"#,
+                "data: [DONE]",
+            ]
+            .map(|event| format!("{}\n\n", event))
+            .concat();
+            MockResponse::new(200, sse).header("content-type", "text/event-stream")
+        })
+        .await;
+
+        let resp = send_chat(&target(&server), "work", &hello(), true).await.unwrap();
+        let tokens = Mutex::new(Vec::<ChatTokenEvent>::new());
+        let reply = read_chat_response(resp, true, |event| tokens.lock().unwrap().push(event))
+            .await
+            .unwrap();
+        assert!(reply.done);
+        assert_eq!(reply.message.content, "Hi there");
+        let tokens = tokens.into_inner().unwrap();
+        let contents: Vec<&str> = tokens.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, ["Hi ", "there", ""]);
+        assert!(tokens.last().unwrap().done);
+    }
+
+    #[tokio::test]
+    async fn reads_a_whole_reply() {
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                serde_json::json!({
+                    "choices": [{ "message": { "role": "assistant", "content": "Hi there" } }]
+                }),
+            )
+        })
+        .await;
+
+        let resp = send_chat(&target(&server), "main", &hello(), false).await.unwrap();
+        let reply = read_chat_response(resp, false, |_| panic!("no tokens without streaming"))
+            .await
+            .unwrap();
+        assert_eq!(reply.message.content, "Hi there");
+    }
+
+    #[tokio::test]
+    async fn points_to_the_setting_when_the_endpoint_is_off() {
+        for status in [404, 405] {
+            let server = MockServer::start(move |_| MockResponse::new(status, "")).await;
+            let err = send_chat(&target(&server), "main", &hello(), true)
+                .await
+                .unwrap_err();
+            assert!(err.contains("enable it in Settings"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_a_rejected_token() {
+        for status in [401, 403] {
+            let server = MockServer::start(move |_| MockResponse::new(status, "")).await;
+            let err = send_chat(&target(&server), "main", &hello(), true)
+                .await
+                .unwrap_err();
+            assert_eq!(err, "The gateway rejected our token");
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_other_gateway_errors_through() {
+        let server = MockServer::start(|_| MockResponse::new(502, "upstream model failed")).await;
+        let err = send_chat(&target(&server), "main", &hello(), true)
+            .await
+            .unwrap_err();
+        assert!(err.contains("HTTP 502"), "{}", err);
+        assert!(err.contains("upstream model failed"), "{}", err);
+    }
+}
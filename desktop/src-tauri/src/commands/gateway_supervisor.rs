@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Notify;
+
+use super::gateway::{self, GatewayCheck, GatewayTarget, ProbeResult};
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+use super::vault::now_millis;
+
+/// How often a healthy gateway is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// How often a gateway that is starting, degraded or down is checked.
+const FAST_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Missed checks in a row before a gateway that was up counts as down.
+const FAILURES_BEFORE_DOWN: u32 = 3;
+/// How long a restarted gateway may take to answer before it counts as down again.
+const STARTUP_GRACE: Duration = Duration::from_secs(60);
+/// Delay before each automatic restart attempt; the last one repeats.
+const RESTART_BACKOFF: [Duration; 5] = [
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+    Duration::from_secs(60),
+    Duration::from_secs(120),
+    Duration::from_secs(300),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayState {
+    /// Not checked yet, or restarted and not answering yet.
+    Starting,
+    Healthy,
+    /// Answering but rejecting our token, or missing a few checks in a row.
+    Degraded,
+    Down,
+}
+
+/// Payload of `openclaw-gateway-status`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GatewayStatus {
+    pub state: GatewayState,
+    pub url: String,
+    pub remote: bool,
+    /// When the gateway entered `state`, in milliseconds since the epoch.
+    pub since: u64,
+    pub last_check: Option<u64>,
+    pub message: String,
+    pub consecutive_failures: u32,
+    /// Automatic restarts since the gateway was last healthy.
+    pub restart_attempts: u32,
+    pub next_restart_at: Option<u64>,
+    pub auto_restart: bool,
+}
+
+impl GatewayStatus {
+    fn new(target: &GatewayTarget, auto_restart: bool) -> Self {
+        Self {
+            state: GatewayState::Starting,
+            url: target.url.clone(),
+            remote: target.remote,
+            since: now_millis(),
+            last_check: None,
+            message: "Checking the gateway".to_string(),
+            consecutive_failures: 0,
+            restart_attempts: 0,
+            next_restart_at: None,
+            auto_restart,
+        }
+    }
+}
+
+/// Supervisor state kept between checks.
+struct Tracker {
+    status: GatewayStatus,
+    /// Only a gateway that has been up is restarted automatically; one that never started
+    /// (not onboarded yet, say) is left for the user.
+    seen_healthy: bool,
+    restart_due: Option<Instant>,
+    starting_until: Option<Instant>,
+}
+
+impl Tracker {
+    fn new(target: &GatewayTarget, auto_restart: bool) -> Self {
+        Self {
+            status: GatewayStatus::new(target, auto_restart),
+            seen_healthy: false,
+            restart_due: None,
+            starting_until: None,
+        }
+    }
+
+    fn set_state(&mut self, state: GatewayState, message: String) {
+        if self.status.state != state {
+            self.status.state = state;
+            self.status.since = now_millis();
+        }
+        self.status.message = message;
+    }
+
+    /// Folds a health check into the state. Returns whether a restart is due now.
+    fn observe(
+        &mut self,
+        target: &GatewayTarget,
+        check: &GatewayCheck,
+        auto_restart: bool,
+    ) -> bool {
+        if self.status.url != target.url || self.status.remote != target.remote {
+            *self = Self::new(target, auto_restart);
+        }
+        self.status.auto_restart = auto_restart;
+        let now = Instant::now();
+
+        if check.reachable && check.authenticated != Some(false) {
+            self.seen_healthy = true;
+            self.status.consecutive_failures = 0;
+            self.status.restart_attempts = 0;
+            self.restart_due = None;
+            self.starting_until = None;
+            self.set_state(GatewayState::Healthy, check.message.clone());
+        } else if check.reachable {
+            self.status.consecutive_failures = 0;
+            self.restart_due = None;
+            self.starting_until = None;
+            self.set_state(GatewayState::Degraded, check.message.clone());
+        } else {
+            self.status.consecutive_failures += 1;
+            if self.starting_until.is_some_and(|until| now < until) {
+                self.set_state(
+                    GatewayState::Starting,
+                    "Waiting for the gateway to start".to_string(),
+                );
+            } else if self.seen_healthy
+                && self.status.state != GatewayState::Down
+                && self.status.consecutive_failures < FAILURES_BEFORE_DOWN
+            {
+                let message = format!(
+                    "Missed {} health check(s): {}",
+                    self.status.consecutive_failures, check.message
+                );
+                self.set_state(GatewayState::Degraded, message);
+            } else {
+                self.starting_until = None;
+                self.set_state(GatewayState::Down, check.message.clone());
+            }
+        }
+
+        // Restarting can't free a port another program holds, so a foreign listener is
+        // left for the user rather than restarted over and over.
+        let restartable = (!target.remote || target.ssh_target.is_some())
+            && check.probe.result != ProbeResult::Foreign;
+        if self.status.state != GatewayState::Down
+            || !auto_restart
+            || !self.seen_healthy
+            || !restartable
+        {
+            self.restart_due = None;
+            self.status.next_restart_at = None;
+        } else if self.restart_due.is_none() {
+            let attempt = (self.status.restart_attempts as usize).min(RESTART_BACKOFF.len() - 1);
+            let delay = RESTART_BACKOFF[attempt];
+            self.restart_due = Some(now + delay);
+            self.status.next_restart_at = Some(now_millis() + delay.as_millis() as u64);
+        }
+        self.status.last_check = Some(now_millis());
+        self.restart_due.is_some_and(|due| due <= now)
+    }
+
+    fn restarting(&mut self) {
+        self.status.restart_attempts += 1;
+        self.status.consecutive_failures = 0;
+        self.status.next_restart_at = None;
+        self.restart_due = None;
+        self.starting_until = Some(Instant::now() + STARTUP_GRACE);
+        let message = format!(
+            "Restarting the gateway (attempt {})",
+            self.status.restart_attempts
+        );
+        self.set_state(GatewayState::Starting, message);
+    }
+
+    /// Records a restart that failed outright; the next attempt follows the backoff.
+    fn restart_failed(&mut self, error: &str) {
+        self.starting_until = None;
+        self.set_state(
+            GatewayState::Down,
+            format!("Failed to restart the gateway: {}", error),
+        );
+    }
+}
+
+/// Managed state shared between the supervisor task and commands.
+#[derive(Default)]
+pub struct GatewaySupervisor {
+    tracker: Mutex<Option<Tracker>>,
+    wake: Notify,
+}
+
+impl GatewaySupervisor {
+    pub fn status(&self) -> Option<GatewayStatus> {
+        self.tracker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|t| t.status.clone())
+    }
+
+    /// Runs the next health check now instead of waiting for the interval.
+    pub fn check_now(&self) {
+        self.wake.notify_one();
+    }
+
+    fn with_tracker<T>(
+        &self,
+        target: &GatewayTarget,
+        auto_restart: bool,
+        f: impl FnOnce(&mut Tracker) -> T,
+    ) -> (T, GatewayStatus) {
+        let mut guard = self.tracker.lock().unwrap_or_else(|e| e.into_inner());
+        let tracker = guard.get_or_insert_with(|| Tracker::new(target, auto_restart));
+        let result = f(tracker);
+        (result, tracker.status.clone())
+    }
+}
+
+/// Starts the background task that polls the active profile's gateway, emits
+/// `openclaw-gateway-status` when its state changes and restarts it with backoff after
+/// it goes down.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move { run(app).await });
+}
+
+async fn run(app: AppHandle) {
+    loop {
+        let paths = app.state::<SettingsState>().paths();
+        let auto_restart = app
+            .state::<SettingsState>()
+            .get()
+            .gateway_auto_restart
+            .unwrap_or(true);
+        let target = gateway::target(&paths);
+        let check = gateway::verify(&target).await;
+
+        let supervisor = app.state::<GatewaySupervisor>();
+        let previous = supervisor.status();
+        let (restart_due, status) = supervisor.with_tracker(&target, auto_restart, |t| {
+            t.observe(&target, &check, auto_restart)
+        });
+        // Every check updates last_check; only report checks that changed something else.
+        let changed = previous.is_none_or(|p| {
+            GatewayStatus {
+                last_check: status.last_check,
+                ..p
+            } != status
+        });
+        if changed {
+            let _ = app.emit("openclaw-gateway-status", &status);
+        }
+
+        // A command already stopping or starting the gateway (onboarding, say) takes
+        // precedence; the restart stays due and is retried on the next check.
+        let claim = if restart_due {
+            operations::claim(&app, "Restarting the gateway", &[ExclusionGroup::Gateway]).ok()
+        } else {
+            None
+        };
+        if let Some(_claim) = claim {
+            let (_, status) = supervisor.with_tracker(&target, auto_restart, Tracker::restarting);
+            let _ = app.emit("openclaw-gateway-status", &status);
+            if let Err(e) = gateway::restart(&app, &paths).await {
+                let (_, status) =
+                    supervisor.with_tracker(&target, auto_restart, |t| t.restart_failed(&e));
+                let _ = app.emit("openclaw-gateway-status", &status);
+            }
+        }
+
+        let interval = match status.state {
+            GatewayState::Healthy => POLL_INTERVAL,
+            _ => FAST_POLL_INTERVAL,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = supervisor.wake.notified() => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_status(
+    supervisor: State<'_, GatewaySupervisor>,
+    settings: State<'_, SettingsState>,
+) -> Result<GatewayStatus, String> {
+    Ok(supervisor.status().unwrap_or_else(|| {
+        let auto_restart = settings.get().gateway_auto_restart.unwrap_or(true);
+        GatewayStatus::new(&gateway::target(&settings.paths()), auto_restart)
+    }))
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_check_now(supervisor: State<'_, GatewaySupervisor>) -> Result<(), String> {
+    supervisor.check_now();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::gateway::GatewayProbe;
+
+    fn local_target() -> GatewayTarget {
+        GatewayTarget {
+            url: "http://127.0.0.1:18789".to_string(),
+            remote: false,
+            token: None,
+            ssh_target: None,
+        }
+    }
+
+    fn check(result: ProbeResult) -> GatewayCheck {
+        let running = result == ProbeResult::Running;
+        GatewayCheck {
+            url: local_target().url,
+            remote: false,
+            reachable: running,
+            authenticated: running.then_some(true),
+            message: format!("{:?}", result),
+            probe: GatewayProbe {
+                url: local_target().url,
+                result,
+                version: None,
+                uptime_secs: None,
+                auth_mode: None,
+                channels: Vec::new(),
+                plugins: Vec::new(),
+                message: String::new(),
+            },
+        }
+    }
+
+    /// Observes `result` until the gateway counts as down.
+    fn take_down(tracker: &mut Tracker, result: ProbeResult) {
+        for _ in 0..FAILURES_BEFORE_DOWN {
+            tracker.observe(&local_target(), &check(result), true);
+        }
+        assert_eq!(tracker.status.state, GatewayState::Down);
+    }
+
+    #[test]
+    fn schedules_a_restart_for_a_gateway_that_went_down() {
+        let mut tracker = Tracker::new(&local_target(), true);
+        tracker.observe(&local_target(), &check(ProbeResult::Running), true);
+        take_down(&mut tracker, ProbeResult::NotRunning);
+        assert!(tracker.status.next_restart_at.is_some());
+    }
+
+    #[test]
+    fn leaves_a_foreign_listener_alone() {
+        let mut tracker = Tracker::new(&local_target(), true);
+        tracker.observe(&local_target(), &check(ProbeResult::Running), true);
+        take_down(&mut tracker, ProbeResult::Foreign);
+        assert_eq!(tracker.status.next_restart_at, None);
+        assert!(!tracker.observe(&local_target(), &check(ProbeResult::Foreign), true));
+    }
+
+    #[test]
+    fn reports_a_failed_restart() {
+        let mut tracker = Tracker::new(&local_target(), true);
+        tracker.observe(&local_target(), &check(ProbeResult::Running), true);
+        take_down(&mut tracker, ProbeResult::NotRunning);
+        tracker.restarting();
+        tracker.restart_failed("openclaw: command not found");
+        assert_eq!(tracker.status.state, GatewayState::Down);
+        assert_eq!(
+            tracker.status.message,
+            "Failed to restart the gateway: openclaw: command not found"
+        );
+        assert_eq!(tracker.status.restart_attempts, 1);
+    }
+}
@@ -0,0 +1,575 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use super::config_files::write_atomic;
+use super::config_preview::ConfigChange;
+use super::openclaw::spawn_and_stream;
+use super::openclaw_config::{self, OpenClawConfig};
+use super::openclaw_paths::{expand_home, OpenClawPaths, STATE_DIR_ENV};
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+use super::vault::{mask_key, random_bytes};
+
+/// Environment variable the gateway service reads its token from.
+const TOKEN_ENV: &str = "OPENCLAW_GATEWAY_TOKEN";
+const TOKEN_BYTES: usize = 32;
+
+/// Where `openclaw onboard --install-daemon` puts the gateway service definition.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceKind {
+    Launchd,
+    Systemd,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServiceTokenStatus {
+    pub kind: ServiceKind,
+    pub path: String,
+    pub masked: Option<String>,
+    /// Whether the service passes the same token as the config.
+    pub matches: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayTokenStatus {
+    /// `gateway.auth.mode`, e.g. "token"; `None` means the gateway runs without auth.
+    pub mode: Option<String>,
+    pub masked: Option<String>,
+    /// Installed service definitions; empty when the gateway isn't run as a service.
+    pub services: Vec<ServiceTokenStatus>,
+    /// Config and every service definition agree on one token.
+    pub consistent: bool,
+    pub issues: Vec<String>,
+}
+
+pub fn generate_token() -> String {
+    random_bytes(TOKEN_BYTES)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The token in `gateway.auth`, if token auth is configured.
+pub fn config_token(config: &OpenClawConfig) -> Option<String> {
+    let auth = config
+        .gateway
+        .as_ref()?
+        .auth
+        .as_ref()
+        .filter(|a| !a.is_null())?;
+    let mode = auth.get("mode").and_then(|m| m.as_str()).unwrap_or("token");
+    if mode != "token" {
+        return None;
+    }
+    auth.get("token")
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+}
+
+/// Sets token auth on the gateway, keeping any other `gateway.auth` options.
+pub fn set_config_token(config: &mut OpenClawConfig, token: &str) {
+    let gateway = config.gateway_mut();
+    let mut auth = match gateway.auth.take() {
+        Some(serde_json::Value::Object(auth)) => auth,
+        _ => serde_json::Map::new(),
+    };
+    auth.insert("mode".to_string(), "token".into());
+    auth.insert("token".to_string(), token.into());
+    gateway.auth = Some(serde_json::Value::Object(auth));
+}
+
+/// Makes sure the gateway has a token, generating one if it has none. Returns the token.
+pub fn ensure_config_token(config: &mut OpenClawConfig) -> String {
+    match config_token(config) {
+        Some(token) => token,
+        None => {
+            let token = generate_token();
+            set_config_token(config, &token);
+            token
+        }
+    }
+}
+
+// --- Service definitions ---
+
+/// Gateway services: LaunchAgents labelled `ai.openclaw.*` and systemd user units named
+/// `openclaw-gateway*`, one per OpenClaw profile that installed one.
+fn service_candidates() -> Vec<(ServiceKind, PathBuf)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let dirs = [
+        (
+            ServiceKind::Launchd,
+            home.join("Library").join("LaunchAgents"),
+        ),
+        (
+            ServiceKind::Systemd,
+            home.join(".config").join("systemd").join("user"),
+        ),
+    ];
+    let mut services = Vec::new();
+    for (kind, dir) in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let matches = match kind {
+                ServiceKind::Launchd => {
+                    name.starts_with("ai.openclaw.") && name.ends_with(".plist")
+                }
+                ServiceKind::Systemd => {
+                    name.starts_with("openclaw-gateway") && name.ends_with(".service")
+                }
+            };
+            if matches {
+                services.push((kind, entry.path()));
+            }
+        }
+    }
+    services.sort_by(|(_, a), (_, b)| a.cmp(b));
+    services
+}
+
+/// The active profile's installed service definitions. A service belongs to the profile
+/// whose state dir it runs with (`OPENCLAW_STATE_DIR`, else `~/.openclaw`), so token and
+/// uninstall changes never touch another profile's gateway.
+pub fn installed_services(paths: &OpenClawPaths) -> Vec<(ServiceKind, PathBuf)> {
+    let default_home = dirs::home_dir().map(|h| h.join(".openclaw"));
+    service_candidates()
+        .into_iter()
+        .filter(|(kind, path)| {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                return false;
+            };
+            let state_dir = read_service_env(*kind, &content, STATE_DIR_ENV)
+                .map(|dir| expand_home(&dir))
+                .or_else(|| default_home.clone());
+            state_dir.as_ref() == Some(&paths.home)
+        })
+        .collect()
+}
+
+fn plist_env_re(var: &str) -> Regex {
+    Regex::new(&format!(
+        r"(<key>{}</key>\s*<string>)([^<]*)(</string>)",
+        regex::escape(var)
+    ))
+    .expect("valid regex")
+}
+
+/// Splits the value of a systemd `Environment=` line into its assignments, which are
+/// separated by spaces and may be quoted, e.g. `A=1 "B=two words"`. Quotes are kept.
+fn systemd_assignments(value: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (i, c) in value.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            None if c.is_whitespace() => {
+                if let Some(s) = start.take() {
+                    words.push(&value[s..i]);
+                }
+            }
+            None => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(s) = start {
+        words.push(&value[s..]);
+    }
+    words
+}
+
+/// The value an assignment gives `var`, if it assigns it.
+fn systemd_assigned(assignment: &str, var: &str) -> Option<String> {
+    let unquoted = assignment.replace(['"', '\''], "");
+    unquoted
+        .strip_prefix(var)
+        .and_then(|rest| rest.strip_prefix('='))
+        .map(String::from)
+}
+
+fn read_service_env(kind: ServiceKind, content: &str, var: &str) -> Option<String> {
+    let value = match kind {
+        ServiceKind::Launchd => plist_env_re(var)
+            .captures(content)?
+            .get(2)?
+            .as_str()
+            .to_string(),
+        // Later assignments override earlier ones.
+        ServiceKind::Systemd => content
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix("Environment="))
+            .flat_map(systemd_assignments)
+            .filter_map(|a| systemd_assigned(a, var))
+            .next_back()?,
+    };
+    Some(value.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+fn read_service_token(kind: ServiceKind, content: &str) -> Option<String> {
+    read_service_env(kind, content, TOKEN_ENV)
+}
+
+/// Returns the service definition with its token set to `token`, adding the variable
+/// where it is missing.
+fn with_service_token(kind: ServiceKind, content: &str, token: &str) -> Result<String, String> {
+    match kind {
+        ServiceKind::Launchd => {
+            let re = plist_env_re(TOKEN_ENV);
+            if re.is_match(content) {
+                return Ok(re
+                    .replace(content, |c: &regex::Captures| {
+                        format!("{}{}{}", &c[1], token, &c[3])
+                    })
+                    .to_string());
+            }
+            let entry = format!(
+                "\n\t\t<key>{}</key>\n\t\t<string>{}</string>",
+                TOKEN_ENV, token
+            );
+            let env_dict =
+                Regex::new(r"<key>EnvironmentVariables</key>\s*<dict>").expect("valid regex");
+            if let Some(m) = env_dict.find(content) {
+                return Ok(format!(
+                    "{}{}{}",
+                    &content[..m.end()],
+                    entry,
+                    &content[m.end()..]
+                ));
+            }
+            let end = content
+                .rfind("</dict>")
+                .ok_or("LaunchAgent plist has no top-level <dict>")?;
+            Ok(format!(
+                "{}\t<key>EnvironmentVariables</key>\n\t<dict>{}\n\t</dict>\n{}",
+                &content[..end],
+                entry,
+                &content[end..]
+            ))
+        }
+        ServiceKind::Systemd => {
+            // Drop every earlier assignment, standalone or among others on a line, so none
+            // can override the new one. A line that only set the token is replaced in place.
+            let token_line = format!("Environment={}={}", TOKEN_ENV, token);
+            let mut lines: Vec<String> = Vec::new();
+            let mut at = None;
+            for line in content.lines() {
+                let Some(value) = line.trim_start().strip_prefix("Environment=") else {
+                    lines.push(line.to_string());
+                    continue;
+                };
+                let assignments = systemd_assignments(value);
+                let kept: Vec<&str> = assignments
+                    .iter()
+                    .copied()
+                    .filter(|a| systemd_assigned(a, TOKEN_ENV).is_none())
+                    .collect();
+                if kept.len() == assignments.len() {
+                    lines.push(line.to_string());
+                } else if kept.is_empty() {
+                    at.get_or_insert(lines.len());
+                } else {
+                    lines.push(format!("Environment={}", kept.join(" ")));
+                }
+            }
+            let at = match at {
+                Some(at) => at,
+                None => {
+                    lines
+                        .iter()
+                        .position(|l| l.trim() == "[Service]")
+                        .ok_or("systemd unit has no [Service] section")?
+                        + 1
+                }
+            };
+            lines.insert(at, token_line);
+            let mut next = lines.join("\n");
+            if content.ends_with('\n') {
+                next.push('\n');
+            }
+            Ok(next)
+        }
+    }
+}
+
+fn write_service_token(kind: ServiceKind, path: &Path, token: &str) -> Result<bool, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let next = with_service_token(kind, &content, token)?;
+    if next == content {
+        return Ok(false);
+    }
+    write_atomic(path, next.as_bytes())?;
+    Ok(true)
+}
+
+/// Writes `token` into the profile's service definitions. Returns whether any changed,
+/// in which case the service manager has to reload them before a restart picks it up.
+fn write_services(paths: &OpenClawPaths, token: &str) -> Result<bool, String> {
+    let mut changed = false;
+    for (kind, path) in installed_services(paths) {
+        changed |= write_service_token(kind, &path, token)?;
+    }
+    Ok(changed)
+}
+
+/// Makes the gateway's services use the same token as the config, generating one if the
+/// config has none. A disagreement between them causes "device token mismatch".
+pub fn sync(paths: &OpenClawPaths) -> Result<bool, String> {
+    let mut config = openclaw_config::load_config(paths)?;
+    let token = match config_token(&config) {
+        Some(token) => token,
+        None => {
+            let token = ensure_config_token(&mut config);
+            openclaw_config::save_config(paths, &config)?;
+            token
+        }
+    };
+    write_services(paths, &token)
+}
+
+/// Makes systemd re-read changed unit files; launchd re-reads a plist on the next start.
+pub async fn reload_services(paths: &OpenClawPaths) {
+    if installed_services(paths)
+        .iter()
+        .any(|(kind, _)| *kind == ServiceKind::Systemd)
+    {
+        let _ = tokio::process::Command::new("systemctl")
+            .args(["--user", "daemon-reload"])
+            .status()
+            .await;
+    }
+}
+
+/// Reloads changed service definitions and restarts the gateway so it uses the new token.
+async fn restart_gateway(app: &AppHandle, paths: &OpenClawPaths) -> Result<(), String> {
+    reload_services(paths).await;
+    // A stop/start (rather than restart) makes launchd re-read the plist.
+    let _ = spawn_and_stream(app, paths, "openclaw", &["gateway", "stop"]).await;
+    spawn_and_stream(app, paths, "openclaw", &["gateway", "start"]).await
+}
+
+fn status(paths: &OpenClawPaths) -> Result<GatewayTokenStatus, String> {
+    let config = openclaw_config::load_config(paths)?;
+    let token = config_token(&config);
+    let mode = config
+        .gateway
+        .as_ref()
+        .and_then(|g| g.auth.as_ref())
+        .filter(|a| !a.is_null())
+        .map(|a| {
+            a.get("mode")
+                .and_then(|m| m.as_str())
+                .unwrap_or("token")
+                .to_string()
+        });
+
+    let mut issues = Vec::new();
+    if mode.is_none() {
+        issues.push("The gateway has no auth; anything on this machine can use it".to_string());
+    } else if mode.as_deref() == Some("token") && token.is_none() {
+        issues.push("gateway.auth.mode is token but no token is set".to_string());
+    }
+
+    let mut services = Vec::new();
+    for (kind, path) in installed_services(paths) {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let service_token = read_service_token(kind, &content);
+        let matches = service_token == token;
+        if !matches {
+            issues.push(match &service_token {
+                Some(_) => format!(
+                    "{} passes a different token than the config",
+                    path.display()
+                ),
+                None => format!("{} doesn't pass the gateway token", path.display()),
+            });
+        }
+        services.push(ServiceTokenStatus {
+            kind,
+            path: path.to_string_lossy().to_string(),
+            masked: service_token.as_deref().map(mask_key),
+            matches,
+        });
+    }
+
+    Ok(GatewayTokenStatus {
+        consistent: services.iter().all(|s| s.matches),
+        mode,
+        masked: token.as_deref().map(mask_key),
+        services,
+        issues,
+    })
+}
+
+/// Token commands rewrite the config and service definitions, and restart the gateway
+/// when asked to.
+fn token_groups(restart: bool) -> Vec<ExclusionGroup> {
+    if restart {
+        vec![ExclusionGroup::Config, ExclusionGroup::Gateway]
+    } else {
+        vec![ExclusionGroup::Config]
+    }
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_token_status(
+    settings: State<'_, SettingsState>,
+) -> Result<GatewayTokenStatus, String> {
+    status(&settings.paths())
+}
+
+/// Writes the config's token (generating one if needed) into the service definitions and,
+/// with `restart`, restarts the gateway when anything changed.
+#[tauri::command]
+pub async fn openclaw_gateway_token_sync(
+    app: AppHandle,
+    restart: Option<bool>,
+    settings: State<'_, SettingsState>,
+) -> Result<GatewayTokenStatus, String> {
+    let paths = settings.paths();
+    let restart = restart.unwrap_or(false);
+    let _claim = operations::claim(&app, "Syncing the gateway token", &token_groups(restart))?;
+    if sync(&paths)? && restart {
+        restart_gateway(&app, &paths).await?;
+    }
+    status(&paths)
+}
+
+/// Replaces the gateway token everywhere. Clients holding the old token must reconnect.
+/// There is no dry run: the config and service definitions have to change together.
+#[tauri::command]
+pub async fn openclaw_gateway_token_rotate(
+    app: AppHandle,
+    restart: Option<bool>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let paths = settings.paths();
+    let restart = restart.unwrap_or(true);
+    let claim = operations::claim(&app, "Rotating the gateway token", &token_groups(restart))?;
+    let token = generate_token();
+    let mut change = ConfigChange::begin(&paths);
+    set_config_token(change.config_mut()?, &token);
+    change.apply_held(&claim)?;
+    write_services(&paths, &token)?;
+    if restart {
+        restart_gateway(&app, &paths).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>ai.openclaw.gateway</string>
+	<key>EnvironmentVariables</key>
+	<dict>
+		<key>OPENCLAW_GATEWAY_TOKEN</key>
+		<string>old</string>
+	</dict>
+</dict>
+</plist>
+"#;
+
+    const UNIT: &str = "[Unit]\nDescription=OpenClaw Gateway\n\n[Service]\n\
+        ExecStart=/usr/bin/openclaw gateway run\n\
+        Environment=HOME=/home/me OPENCLAW_GATEWAY_TOKEN=old \"LABEL=a b\"\n\n\
+        [Install]\nWantedBy=default.target\n";
+
+    fn rewrite(kind: ServiceKind, content: &str) -> String {
+        let next = with_service_token(kind, content, "new").unwrap();
+        assert_eq!(read_service_token(kind, &next).as_deref(), Some("new"));
+        next
+    }
+
+    #[test]
+    fn rewrites_plist_tokens() {
+        assert_eq!(
+            read_service_token(ServiceKind::Launchd, PLIST).as_deref(),
+            Some("old")
+        );
+        assert_eq!(
+            rewrite(ServiceKind::Launchd, PLIST),
+            PLIST.replace("<string>old</string>", "<string>new</string>")
+        );
+
+        // Added to an existing EnvironmentVariables dict, or a new one.
+        let without_token = PLIST.replace(
+            "\t\t<key>OPENCLAW_GATEWAY_TOKEN</key>\n\t\t<string>old</string>\n",
+            "\t\t<key>HOME</key>\n\t\t<string>/Users/me</string>\n",
+        );
+        let next = rewrite(ServiceKind::Launchd, &without_token);
+        assert_eq!(
+            read_service_env(ServiceKind::Launchd, &next, "HOME").as_deref(),
+            Some("/Users/me")
+        );
+        let without_env =
+            "<plist>\n<dict>\n\t<key>Label</key>\n\t<string>x</string>\n</dict>\n</plist>\n";
+        let next = rewrite(ServiceKind::Launchd, without_env);
+        assert!(next.contains("<key>EnvironmentVariables</key>"), "{}", next);
+        assert!(with_service_token(ServiceKind::Launchd, "<plist/>", "new").is_err());
+    }
+
+    #[test]
+    fn rewrites_unit_tokens() {
+        // Among other variables on one line.
+        assert_eq!(
+            read_service_token(ServiceKind::Systemd, UNIT).as_deref(),
+            Some("old")
+        );
+        let next = rewrite(ServiceKind::Systemd, UNIT);
+        assert!(
+            next.contains("Environment=HOME=/home/me \"LABEL=a b\"\n"),
+            "{}",
+            next
+        );
+        assert!(!next.contains("old"), "{}", next);
+        assert!(next.ends_with("WantedBy=default.target\n"));
+        assert_eq!(
+            read_service_env(ServiceKind::Systemd, &next, "LABEL").as_deref(),
+            Some("a b")
+        );
+
+        // A standalone line is replaced in place; a later duplicate is dropped so it can't
+        // override the new value.
+        let standalone = "[Service]\nEnvironment=OPENCLAW_GATEWAY_TOKEN=old\nExecStart=x\n\
+            Environment=\"OPENCLAW_GATEWAY_TOKEN=older\"\n";
+        assert_eq!(
+            read_service_token(ServiceKind::Systemd, standalone).as_deref(),
+            Some("older")
+        );
+        assert_eq!(
+            rewrite(ServiceKind::Systemd, standalone),
+            "[Service]\nEnvironment=OPENCLAW_GATEWAY_TOKEN=new\nExecStart=x\n"
+        );
+
+        // Unchanged when it already passes the token; added under [Service] when missing.
+        let same = "[Service]\nEnvironment=OPENCLAW_GATEWAY_TOKEN=new\n";
+        assert_eq!(rewrite(ServiceKind::Systemd, same), same);
+        assert_eq!(
+            rewrite(ServiceKind::Systemd, "[Unit]\n[Service]\nExecStart=x\n"),
+            "[Unit]\n[Service]\nEnvironment=OPENCLAW_GATEWAY_TOKEN=new\nExecStart=x\n"
+        );
+        assert!(with_service_token(ServiceKind::Systemd, "[Unit]\n", "new").is_err());
+    }
+}
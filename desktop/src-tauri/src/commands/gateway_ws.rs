@@ -0,0 +1,719 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{mpsc, oneshot, Notify};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::gateway::{self, GatewayTarget};
+use super::settings::SettingsState;
+use super::vault::{now_millis, random_id};
+
+/// Gateway protocol version this client speaks.
+const PROTOCOL_VERSION: u32 = 3;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// How often a live connection checks whether the active gateway has changed.
+const RETARGET_INTERVAL: Duration = Duration::from_secs(10);
+/// Gateway methods the frontend may call directly. Only reads: changes go through the
+/// config commands, so they get dry-run previews and take part in operation exclusion.
+const READ_METHODS: [&str; 10] = [
+    "health",
+    "status",
+    "presence",
+    "channels.status",
+    "sessions.list",
+    "chat.history",
+    "agents.list",
+    "models.list",
+    "config.get",
+    "logs.tail",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+/// Payload of `openclaw-gateway-connection`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayConnection {
+    pub state: ConnectionState,
+    /// WebSocket URL, e.g. "ws://127.0.0.1:18789".
+    pub url: String,
+    pub message: Option<String>,
+    pub connected_since: Option<u64>,
+    pub server_version: Option<String>,
+    pub next_retry_at: Option<u64>,
+}
+
+/// Payload of `openclaw-gateway-event`: a gateway event as sent, e.g. "chat" for channel
+/// messages, "agent" for agent runs, "presence" or "health" for channel changes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GatewayEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub seq: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Frame {
+    Res {
+        id: String,
+        ok: bool,
+        #[serde(default)]
+        payload: serde_json::Value,
+        #[serde(default)]
+        error: Option<serde_json::Value>,
+    },
+    Event {
+        event: String,
+        #[serde(default)]
+        payload: serde_json::Value,
+        #[serde(default)]
+        seq: Option<u64>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+fn request_frame(id: &str, method: &str, params: serde_json::Value) -> String {
+    serde_json::json!({ "type": "req", "id": id, "method": method, "params": params }).to_string()
+}
+
+fn error_message(error: Option<serde_json::Value>) -> String {
+    error
+        .as_ref()
+        .and_then(|e| e.get("message").and_then(|m| m.as_str()))
+        .unwrap_or("The gateway returned an error")
+        .to_string()
+}
+
+type Pending = HashMap<String, oneshot::Sender<Result<serde_json::Value, String>>>;
+
+/// What the connection needs from the app: which gateway is active, and where to report
+/// connection changes (`openclaw-gateway-connection`) and events (`openclaw-gateway-event`).
+trait GatewayHost: Send + Sync {
+    fn target(&self) -> GatewayTarget;
+    fn connection_changed(&self, status: &GatewayConnection);
+    fn event(&self, event: &GatewayEvent);
+}
+
+impl GatewayHost for AppHandle {
+    fn target(&self) -> GatewayTarget {
+        gateway::target(&self.state::<SettingsState>().paths())
+    }
+
+    fn connection_changed(&self, status: &GatewayConnection) {
+        let _ = self.emit("openclaw-gateway-connection", status);
+    }
+
+    fn event(&self, event: &GatewayEvent) {
+        let _ = self.emit("openclaw-gateway-event", event);
+    }
+}
+
+/// Managed state for the WebSocket connection to the active gateway.
+pub struct GatewayClient {
+    status: Mutex<GatewayConnection>,
+    outgoing: Mutex<Option<mpsc::UnboundedSender<String>>>,
+    pending: Mutex<Pending>,
+    wake: Notify,
+}
+
+impl Default for GatewayClient {
+    fn default() -> Self {
+        Self {
+            status: Mutex::new(GatewayConnection {
+                state: ConnectionState::Disconnected,
+                url: String::new(),
+                message: None,
+                connected_since: None,
+                server_version: None,
+                next_retry_at: None,
+            }),
+            outgoing: Mutex::new(None),
+            pending: Mutex::new(HashMap::new()),
+            wake: Notify::new(),
+        }
+    }
+}
+
+impl GatewayClient {
+    pub fn status(&self) -> GatewayConnection {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set_status(&self, host: &dyn GatewayHost, status: GatewayConnection) {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status.clone();
+        host.connection_changed(&status);
+    }
+
+    /// Drops the reconnect delay and connects again right away.
+    pub fn reconnect_now(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Calls a method from `READ_METHODS`; see `openclaw_gateway_call`.
+    pub async fn call(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        if !READ_METHODS.contains(&method) {
+            return Err(format!(
+                "Gateway method {} can't be called from here",
+                method
+            ));
+        }
+        self.request(method, params.unwrap_or_else(|| serde_json::json!({})))
+            .await
+    }
+
+    /// Sends a request over the live connection and waits for its response.
+    pub async fn request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let id = random_id("req");
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.clone(), tx);
+        let sent = self
+            .outgoing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|out| out.send(request_frame(&id, method, params)).is_ok());
+        if !sent {
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id);
+            return Err("Not connected to the gateway".to_string());
+        }
+        match tokio::time::timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Gateway connection closed".to_string()),
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id);
+                Err(format!("Gateway didn't answer {} in time", method))
+            }
+        }
+    }
+
+    fn resolve(&self, id: &str, result: Result<serde_json::Value, String>) {
+        if let Some(tx) = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id)
+        {
+            let _ = tx.send(result);
+        }
+    }
+
+    fn close(&self) {
+        *self.outgoing.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        for (_, tx) in self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+        {
+            let _ = tx.send(Err("Gateway connection closed".to_string()));
+        }
+    }
+}
+
+/// Starts the background task that keeps a WebSocket connection to the active profile's
+/// gateway, reconnecting with backoff, and forwards its events as `openclaw-gateway-event`.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move { run(&app, &app.state::<GatewayClient>()).await });
+}
+
+async fn run(host: &dyn GatewayHost, client: &GatewayClient) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let target = host.target();
+        let url = gateway::ws_url(&target.url);
+        client.set_status(
+            host,
+            GatewayConnection {
+                state: ConnectionState::Connecting,
+                url: url.clone(),
+                message: None,
+                connected_since: None,
+                server_version: None,
+                next_retry_at: None,
+            },
+        );
+
+        let (established, reason) = session(host, client, &target, &url).await;
+        client.close();
+        if established {
+            backoff = MIN_BACKOFF;
+        }
+        client.set_status(
+            host,
+            GatewayConnection {
+                state: ConnectionState::Disconnected,
+                url,
+                message: Some(reason),
+                connected_since: None,
+                server_version: None,
+                next_retry_at: Some(now_millis() + backoff.as_millis() as u64),
+            },
+        );
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = client.wake.notified() => {}
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Connects, performs the `connect` handshake and pumps frames until the connection ends
+/// or the active gateway changes. Returns whether the handshake succeeded, and why the
+/// session ended.
+async fn session(
+    host: &dyn GatewayHost,
+    client: &GatewayClient,
+    target: &GatewayTarget,
+    url: &str,
+) -> (bool, String) {
+    let ws = match tokio::time::timeout(CONNECT_TIMEOUT, connect_async(url)).await {
+        Ok(Ok((ws, _))) => ws,
+        Ok(Err(e)) => return (false, format!("Failed to connect to {}: {}", url, e)),
+        Err(_) => return (false, format!("Timed out connecting to {}", url)),
+    };
+    let (mut write, mut read) = ws.split();
+
+    let connect_id = random_id("connect");
+    let mut params = serde_json::json!({
+        "minProtocol": PROTOCOL_VERSION,
+        "maxProtocol": PROTOCOL_VERSION,
+        "client": {
+            "id": "daemon-desktop",
+            "version": env!("CARGO_PKG_VERSION"),
+            "platform": std::env::consts::OS,
+            "mode": "backend"
+        },
+        "role": "operator",
+        "scopes": ["operator.read"]
+    });
+    if let Some(token) = &target.token {
+        params["auth"] = serde_json::json!({ "token": token });
+    }
+    if let Err(e) = write
+        .send(Message::Text(
+            request_frame(&connect_id, "connect", params).into(),
+        ))
+        .await
+    {
+        return (false, format!("Failed to send connect: {}", e));
+    }
+
+    // The gateway may send events (e.g. a connect challenge) before answering.
+    let hello = loop {
+        let frame = match tokio::time::timeout(CONNECT_TIMEOUT, read.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => serde_json::from_str::<Frame>(&text).ok(),
+            Ok(Some(Ok(_))) => None,
+            Ok(Some(Err(e))) => return (false, format!("Connection error: {}", e)),
+            Ok(None) => return (false, "The gateway closed the connection".to_string()),
+            Err(_) => return (false, "The gateway didn't answer connect".to_string()),
+        };
+        match frame {
+            Some(Frame::Res {
+                id,
+                ok,
+                payload,
+                error,
+            }) if id == connect_id => {
+                if !ok {
+                    return (
+                        false,
+                        format!("Gateway refused the connection: {}", error_message(error)),
+                    );
+                }
+                break payload;
+            }
+            _ => continue,
+        }
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    *client.outgoing.lock().unwrap_or_else(|e| e.into_inner()) = Some(tx);
+    client.set_status(
+        host,
+        GatewayConnection {
+            state: ConnectionState::Connected,
+            url: url.to_string(),
+            message: None,
+            connected_since: Some(now_millis()),
+            server_version: hello
+                .pointer("/server/version")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            next_retry_at: None,
+        },
+    );
+
+    let mut retarget = tokio::time::interval(RETARGET_INTERVAL);
+    retarget.tick().await;
+    loop {
+        tokio::select! {
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Frame>(&text) {
+                    Ok(Frame::Res { id, ok, payload, error }) => {
+                        let result = if ok { Ok(payload) } else { Err(error_message(error)) };
+                        client.resolve(&id, result);
+                    }
+                    Ok(Frame::Event { event, payload, seq }) => {
+                        host.event(&GatewayEvent { event, payload, seq });
+                    }
+                    Ok(Frame::Other) | Err(_) => {}
+                },
+                Some(Ok(Message::Close(frame))) => {
+                    let reason = frame
+                        .map(|f| f.reason.to_string())
+                        .filter(|r| !r.is_empty())
+                        .unwrap_or_else(|| "no reason given".to_string());
+                    return (true, format!("The gateway closed the connection: {}", reason));
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return (true, format!("Connection error: {}", e)),
+                None => return (true, "The gateway closed the connection".to_string()),
+            },
+            Some(frame) = rx.recv() => {
+                if let Err(e) = write.send(Message::Text(frame.into())).await {
+                    return (true, format!("Failed to send to the gateway: {}", e));
+                }
+            }
+            _ = retarget.tick() => {
+                let current = host.target();
+                if current.url != target.url || current.token != target.token {
+                    let _ = write.close().await;
+                    return (true, "Switching to another gateway".to_string());
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_connection(
+    client: State<'_, GatewayClient>,
+) -> Result<GatewayConnection, String> {
+    Ok(client.status())
+}
+
+#[tauri::command]
+pub fn openclaw_gateway_reconnect(client: State<'_, GatewayClient>) -> Result<(), String> {
+    client.reconnect_now();
+    Ok(())
+}
+
+/// Calls a read-only gateway method over the WebSocket connection, e.g. "health" or
+/// "status".
+#[tauri::command]
+pub async fn openclaw_gateway_call(
+    method: String,
+    params: Option<serde_json::Value>,
+    client: State<'_, GatewayClient>,
+) -> Result<serde_json::Value, String> {
+    client.call(&method, params).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::closed_addr;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::WebSocketStream;
+
+    type ServerSocket = WebSocketStream<TcpStream>;
+
+    /// Stands in for the app: a settable target, and what was reported to it.
+    struct Host {
+        target: Mutex<GatewayTarget>,
+        statuses: Mutex<Vec<(u64, GatewayConnection)>>,
+        events: Mutex<Vec<GatewayEvent>>,
+    }
+
+    impl Host {
+        fn new(url: String) -> Self {
+            Self {
+                target: Mutex::new(GatewayTarget {
+                    url,
+                    remote: false,
+                    token: Some("gw-token".to_string()),
+                    ssh_target: None,
+                }),
+                statuses: Mutex::default(),
+                events: Mutex::default(),
+            }
+        }
+
+        fn states(&self) -> Vec<ConnectionState> {
+            let statuses = self.statuses.lock().unwrap();
+            statuses.iter().map(|(_, s)| s.state).collect()
+        }
+    }
+
+    impl GatewayHost for Host {
+        fn target(&self) -> GatewayTarget {
+            self.target.lock().unwrap().clone()
+        }
+
+        fn connection_changed(&self, status: &GatewayConnection) {
+            self.statuses
+                .lock()
+                .unwrap()
+                .push((now_millis(), status.clone()));
+        }
+
+        fn event(&self, event: &GatewayEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    /// A stand-in gateway that accepts one connection and hands it to `script`.
+    async fn gateway<F, Fut>(script: F) -> (String, tokio::task::JoinHandle<()>)
+    where
+        F: FnOnce(ServerSocket) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            script(tokio_tungstenite::accept_async(stream).await.unwrap()).await;
+        });
+        (url, server)
+    }
+
+    async fn next_request(ws: &mut ServerSocket) -> serde_json::Value {
+        loop {
+            match ws.next().await.unwrap().unwrap() {
+                Message::Text(text) => {
+                    let frame: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    assert_eq!(frame["type"], "req");
+                    return frame;
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn send(ws: &mut ServerSocket, frame: serde_json::Value) {
+        ws.send(Message::Text(frame.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    async fn reply(ws: &mut ServerSocket, request: &serde_json::Value, payload: serde_json::Value) {
+        let frame = serde_json::json!({
+            "type": "res", "id": request["id"], "ok": true, "payload": payload
+        });
+        send(ws, frame).await;
+    }
+
+    /// Answers `connect` after a challenge event, as the gateway does.
+    async fn accept_connect(ws: &mut ServerSocket) -> serde_json::Value {
+        let connect = next_request(ws).await;
+        assert_eq!(connect["method"], "connect");
+        let challenge = serde_json::json!({
+            "type": "event", "event": "connect.challenge", "payload": { "nonce": "n" }
+        });
+        send(ws, challenge).await;
+        let hello = serde_json::json!({ "server": { "version": "2026.2.1" } });
+        reply(ws, &connect, hello).await;
+        connect
+    }
+
+    async fn wait_connected(client: &GatewayClient) {
+        while client.status().state != ConnectionState::Connected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    async fn session_for(host: &Host, client: &GatewayClient) -> (bool, String) {
+        let target = host.target();
+        session(host, client, &target, &gateway::ws_url(&target.url)).await
+    }
+
+    #[tokio::test]
+    async fn connects_forwards_events_and_answers_calls() {
+        let (url, server) = gateway(|mut ws| async move {
+            let connect = accept_connect(&mut ws).await;
+            let params = &connect["params"];
+            assert_eq!(params["minProtocol"], PROTOCOL_VERSION);
+            assert_eq!(params["role"], "operator");
+            assert_eq!(params["scopes"], serde_json::json!(["operator.read"]));
+            assert_eq!(params["auth"]["token"], "gw-token");
+
+            let presence = serde_json::json!({
+                "type": "event", "event": "presence", "payload": { "online": 1 }, "seq": 7
+            });
+            send(&mut ws, presence).await;
+            // Answered out of order, so each call must get its own answer back.
+            let first = next_request(&mut ws).await;
+            let second = next_request(&mut ws).await;
+            let error = serde_json::json!({
+                "type": "res", "id": second["id"], "ok": false,
+                "error": { "message": "no such session" }
+            });
+            send(&mut ws, error).await;
+            let echo = serde_json::json!({ "method": first["method"], "params": first["params"] });
+            reply(&mut ws, &first, echo).await;
+            let bye = CloseFrame {
+                code: CloseCode::Normal,
+                reason: "restarting".into(),
+            };
+            ws.close(Some(bye)).await.unwrap();
+        })
+        .await;
+        let host = Host::new(url);
+        let client = GatewayClient::default();
+
+        let calls = async {
+            wait_connected(&client).await;
+            let first = client.call("health", Some(serde_json::json!({ "probe": true })));
+            let second = client.call("chat.history", None);
+            tokio::join!(first, second)
+        };
+        let ((established, reason), (first, second)) =
+            tokio::join!(session_for(&host, &client), calls);
+        server.await.unwrap();
+
+        assert!(established);
+        assert_eq!(reason, "The gateway closed the connection: restarting");
+        assert_eq!(
+            first.unwrap(),
+            serde_json::json!({ "method": "health", "params": { "probe": true } })
+        );
+        assert_eq!(second.unwrap_err(), "no such session");
+        let status = client.status();
+        assert_eq!(status.server_version.as_deref(), Some("2026.2.1"));
+        assert!(status.connected_since.is_some());
+        let events = host.events.lock().unwrap();
+        let names: Vec<_> = events.iter().map(|e| (e.event.as_str(), e.seq)).collect();
+        assert_eq!(names, [("presence", Some(7))]);
+    }
+
+    #[tokio::test]
+    async fn reports_a_refused_connect() {
+        let (url, server) = gateway(|mut ws| async move {
+            let connect = next_request(&mut ws).await;
+            let refused = serde_json::json!({
+                "type": "res", "id": connect["id"], "ok": false,
+                "error": { "code": "unauthorized", "message": "gateway token mismatch" }
+            });
+            send(&mut ws, refused).await;
+        })
+        .await;
+        let host = Host::new(url);
+        let client = GatewayClient::default();
+
+        let (established, reason) = session_for(&host, &client).await;
+        server.await.unwrap();
+        assert!(!established);
+        assert_eq!(
+            reason,
+            "Gateway refused the connection: gateway token mismatch"
+        );
+        assert!(host.states().is_empty());
+        assert_eq!(
+            client.call("health", None).await.unwrap_err(),
+            "Not connected to the gateway"
+        );
+    }
+
+    #[tokio::test]
+    async fn only_calls_read_methods() {
+        let client = GatewayClient::default();
+        for method in ["config.apply", "chat.send", "agents.delete"] {
+            let err = client.call(method, None).await.unwrap_err();
+            assert_eq!(
+                err,
+                format!("Gateway method {} can't be called from here", method)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn leaves_a_gateway_that_is_no_longer_active() {
+        let (url, server) = gateway(|mut ws| async move {
+            accept_connect(&mut ws).await;
+            // Held open until the client leaves.
+            while let Some(Ok(message)) = ws.next().await {
+                if message.is_close() {
+                    break;
+                }
+            }
+        })
+        .await;
+        let host = Host::new(url);
+        let client = GatewayClient::default();
+
+        let switch = async {
+            wait_connected(&client).await;
+            host.target.lock().unwrap().token = Some("rotated".to_string());
+            // Skips ahead to the next check of the active gateway.
+            tokio::time::pause();
+        };
+        let ((established, reason), ()) = tokio::join!(session_for(&host, &client), switch);
+        server.await.unwrap();
+        assert!(established);
+        assert_eq!(reason, "Switching to another gateway");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backs_off_between_failed_attempts() {
+        let host = Host::new(format!("http://{}", closed_addr().await));
+        let client = GatewayClient::default();
+        let attempts = async {
+            while host.states().len() < 14 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::select! {
+            _ = run(&host, &client) => unreachable!(),
+            _ = attempts => {}
+        }
+
+        let statuses = host.statuses.lock().unwrap();
+        let delays: Vec<u64> = statuses
+            .iter()
+            .filter(|(_, s)| s.state == ConnectionState::Disconnected)
+            // Rounded, since the wall clock moves on a little between the two readings.
+            .map(|(at, s)| (s.next_retry_at.unwrap() - at + 500) / 1000)
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+        assert!(statuses
+            .iter()
+            .all(|(_, s)| s.url.starts_with("ws://127.0.0.1:")));
+    }
+}
@@ -0,0 +1,877 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncWriteExt;
+
+use super::diagnostics::check_binary_in_path;
+use super::openclaw_paths::OpenClawPaths;
+use super::openclaw_version::{known_integrity, KNOWN_RELEASES};
+use super::operations::{self, ExclusionGroup, OperationSpec};
+use super::settings::SettingsState;
+use super::vault::random_id;
+
+/// npm package OpenClaw ships as.
+const PACKAGE: &str = "openclaw";
+/// Release installed when no version is asked for. Bump after testing a new OpenClaw release,
+/// and add its checksum to `openclaw_version::KNOWN_RELEASES`.
+pub const PINNED_VERSION: &str = "2026.2.17";
+/// OpenClaw's own installer, used while the pinned release can't be verified.
+const INSTALL_SCRIPT: &str = "curl -fsSL https://openclaw.ai/install.sh | bash";
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+/// Timeout for `npm install`, which fetches dependencies unless offline.
+const INSTALL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Bytes downloaded between progress events.
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+// --- Types ---
+
+/// Where to install OpenClaw from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum InstallSource {
+    /// A release from the npm registry, checked against the registry's `dist.integrity` and,
+    /// for versions in `openclaw_version::KNOWN_RELEASES`, against the checksum listed there.
+    #[serde(rename_all = "camelCase")]
+    Registry {
+        /// Defaults to `PINNED_VERSION`.
+        version: Option<String>,
+        /// Defaults to `DEFAULT_REGISTRY`; a mirror or local server for testing.
+        registry: Option<String>,
+        /// Expected SRI integrity, e.g. from release notes; must match the registry's too.
+        integrity: Option<String>,
+    },
+    /// An `npm pack` tarball on disk, for machines without internet access. Checked
+    /// against `integrity`, or the SRI string in `<path>.integrity` next to it. Offline, it
+    /// has to bundle its dependencies (`bundleDependencies`), as npm can't fetch them.
+    #[serde(rename_all = "camelCase")]
+    Tarball {
+        path: String,
+        integrity: Option<String>,
+        /// Install without the network. Defaults to true.
+        offline: Option<bool>,
+    },
+    /// OpenClaw's install script, which installs wherever it sees fit and isn't verified.
+    /// The default until `PINNED_VERSION` has a known checksum.
+    Script,
+}
+
+impl Default for InstallSource {
+    fn default() -> Self {
+        if known_integrity(PINNED_VERSION).is_none() {
+            return InstallSource::Script;
+        }
+        InstallSource::Registry {
+            version: None,
+            registry: None,
+            integrity: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallPhase {
+    Resolving,
+    Downloading,
+    Verifying,
+    Installing,
+    /// A version change failed and the previous version is being reinstalled.
+    RollingBack,
+    Done,
+}
+
+/// Payload of `openclaw-install-progress`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallProgress {
+    pub phase: InstallPhase,
+    pub message: String,
+    pub bytes: Option<u64>,
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallResult {
+    pub version: Option<String>,
+    /// The SRI integrity the tarball was checked against; none for the install script.
+    pub integrity: Option<String>,
+    pub prefix: String,
+    pub binary: Option<String>,
+}
+
+/// The `dist` block of a version in the registry's package metadata.
+#[derive(Debug, Deserialize)]
+struct Dist {
+    tarball: String,
+    integrity: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionMetadata {
+    version: String,
+    dist: Dist,
+}
+
+// --- Install location ---
+
+/// User-local npm prefix OpenClaw is installed into, so no admin rights are needed:
+/// `~/.local` (binary in `~/.local/bin`), or `%LOCALAPPDATA%\openclaw` on Windows.
+pub fn prefix() -> PathBuf {
+    if cfg!(target_os = "windows") {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("openclaw")
+    } else {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".local")
+    }
+}
+
+/// Where npm puts executables for `prefix()`.
+pub fn bin_dir() -> PathBuf {
+    if cfg!(target_os = "windows") {
+        prefix()
+    } else {
+        prefix().join("bin")
+    }
+}
+
+/// The binary installed by this app, if present.
+pub fn installed_binary() -> Option<PathBuf> {
+    let name = if cfg!(target_os = "windows") {
+        "openclaw.cmd"
+    } else {
+        "openclaw"
+    };
+    Some(bin_dir().join(name)).filter(|p| p.is_file())
+}
+
+/// `base` (or this process's PATH) with `bin_dir()` in front, so a user-local install is
+/// found by spawned commands even when the user's PATH lacks it.
+pub fn search_path(base: Option<String>) -> String {
+    let base = base.unwrap_or_else(|| std::env::var("PATH").unwrap_or_default());
+    let separator = if cfg!(target_os = "windows") {
+        ";"
+    } else {
+        ":"
+    };
+    let bin = bin_dir().to_string_lossy().to_string();
+    if base.split(separator).any(|entry| entry == bin) {
+        base
+    } else if base.is_empty() {
+        bin
+    } else {
+        format!("{}{}{}", bin, separator, base)
+    }
+}
+
+// --- Integrity ---
+
+/// Digests of a downloaded or local tarball.
+struct Digests {
+    sha256: Vec<u8>,
+    sha512: Vec<u8>,
+}
+
+impl Digests {
+    fn sha512_integrity(&self) -> String {
+        format!("sha512-{}", BASE64.encode(&self.sha512))
+    }
+}
+
+/// Checks digests against an SRI string ("sha512-<base64>", possibly several separated by
+/// spaces). At least one sha256 or sha512 entry must be present, and one must match.
+fn check_integrity(expected: &str, digests: &Digests) -> Result<(), String> {
+    let mut supported = false;
+    for entry in expected.split_whitespace() {
+        let Some((algorithm, rest)) = entry.split_once('-') else {
+            continue;
+        };
+        // SRI allows "?options" after the digest.
+        let encoded = rest.split('?').next().unwrap_or(rest);
+        let actual = match algorithm {
+            "sha512" => &digests.sha512,
+            "sha256" => &digests.sha256,
+            _ => continue,
+        };
+        supported = true;
+        if BASE64.decode(encoded).ok().as_deref() == Some(actual.as_slice()) {
+            return Ok(());
+        }
+    }
+    if !supported {
+        return Err(format!(
+            "No sha256 or sha512 checksum to verify against in \"{}\"",
+            expected
+        ));
+    }
+    Err(format!(
+        "Checksum mismatch: expected {}, got {}",
+        expected,
+        digests.sha512_integrity()
+    ))
+}
+
+fn digest_file(path: &Path) -> Result<Digests, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut sha256 = Sha256::new();
+    let mut sha512 = Sha512::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        sha256.update(&buf[..n]);
+        sha512.update(&buf[..n]);
+    }
+    Ok(Digests {
+        sha256: sha256.finalize().to_vec(),
+        sha512: sha512.finalize().to_vec(),
+    })
+}
+
+// --- Download ---
+
+fn emit_progress(
+    app: &AppHandle,
+    phase: InstallPhase,
+    message: &str,
+    bytes: Option<u64>,
+    total: Option<u64>,
+) {
+    let _ = app.emit(
+        "openclaw-install-progress",
+        InstallProgress {
+            phase,
+            message: message.to_string(),
+            bytes,
+            total,
+        },
+    );
+}
+
+async fn resolve(
+    client: &reqwest::Client,
+    registry: &str,
+    version: &str,
+) -> Result<VersionMetadata, String> {
+    let url = format!("{}/{}/{}", registry.trim_end_matches('/'), PACKAGE, version);
+    let resp = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the npm registry: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "OpenClaw {} not found in {}: HTTP {}",
+            version,
+            registry,
+            resp.status()
+        ));
+    }
+    resp.json()
+        .await
+        .map_err(|e| format!("Failed to parse package metadata: {}", e))
+}
+
+/// Streams `url` into `dest`, hashing as it goes.
+async fn download(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    on_progress: &impl Fn(InstallPhase, &str, Option<u64>, Option<u64>),
+) -> Result<Digests, String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Failed to download {}: HTTP {}",
+            url,
+            resp.status()
+        ));
+    }
+    let total = resp.content_length();
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut sha256 = Sha256::new();
+    let mut sha512 = Sha512::new();
+    let mut bytes = 0u64;
+    let mut reported = 0u64;
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        sha256.update(&chunk);
+        sha512.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        bytes += chunk.len() as u64;
+        if bytes - reported >= PROGRESS_STEP {
+            reported = bytes;
+            on_progress(
+                InstallPhase::Downloading,
+                "Downloading OpenClaw",
+                Some(bytes),
+                total,
+            );
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    on_progress(
+        InstallPhase::Downloading,
+        "Downloaded OpenClaw",
+        Some(bytes),
+        total,
+    );
+    Ok(Digests {
+        sha256: sha256.finalize().to_vec(),
+        sha512: sha512.finalize().to_vec(),
+    })
+}
+
+/// Resolves `version` in `registry`, downloads its tarball to `dest` and checks it. The
+/// registry's checksum has to match `expected` and the one `known` has for the version.
+/// Returns the resolved version and the checksum it was checked against.
+async fn fetch_release(
+    client: &reqwest::Client,
+    registry: &str,
+    version: &str,
+    expected: Option<&str>,
+    known: &[(&str, &str)],
+    dest: &Path,
+    on_progress: &impl Fn(InstallPhase, &str, Option<u64>, Option<u64>),
+) -> Result<(String, String), String> {
+    on_progress(
+        InstallPhase::Resolving,
+        &format!("Looking up OpenClaw {}", version),
+        None,
+        None,
+    );
+    let metadata = resolve(client, registry, version).await?;
+    let published = metadata.dist.integrity.ok_or_else(|| {
+        format!(
+            "The registry has no sha512 integrity for OpenClaw {}; refusing to install it",
+            metadata.version
+        )
+    })?;
+    let pinned = known
+        .iter()
+        .find(|(v, _)| *v == metadata.version)
+        .map(|(_, integrity)| *integrity);
+    for expected in [pinned, expected].into_iter().flatten() {
+        if expected.trim() != published.trim() {
+            return Err(format!(
+                "The registry's checksum for OpenClaw {} ({}) differs from the expected {}",
+                metadata.version, published, expected
+            ));
+        }
+    }
+
+    let digests = download(client, &metadata.dist.tarball, dest, on_progress).await?;
+    on_progress(InstallPhase::Verifying, "Verifying checksum", None, None);
+    check_integrity(&published, &digests)?;
+    Ok((metadata.version, published))
+}
+
+/// Checks that an `npm pack` tarball bundles every dependency it declares, so npm can
+/// install it without the network.
+fn check_bundled(path: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut manifest: Option<serde_json::Value> = None;
+    let mut bundled = BTreeSet::new();
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .to_string_lossy()
+            .to_string();
+        if name == "package/package.json" {
+            let mut content = String::new();
+            entry
+                .read_to_string(&mut content)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            manifest = Some(
+                serde_json::from_str(&content)
+                    .map_err(|e| format!("Invalid package.json in {}: {}", path.display(), e))?,
+            );
+        } else if let Some(module) = name
+            .strip_prefix("package/node_modules/")
+            .and_then(|rest| rest.strip_suffix("/package.json"))
+        {
+            // Direct dependencies only: "name" or "@scope/name".
+            if !module.contains("/node_modules/") {
+                bundled.insert(module.to_string());
+            }
+        }
+    }
+    let manifest =
+        manifest.ok_or_else(|| format!("{} is not an npm package tarball", path.display()))?;
+    let missing: Vec<&String> = manifest
+        .get("dependencies")
+        .and_then(|d| d.as_object())
+        .map(|deps| deps.keys().filter(|d| !bundled.contains(*d)).collect())
+        .unwrap_or_default();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} doesn't bundle {} and npm can't fetch them offline; pack it with \
+            bundleDependencies, or install with offline set to false",
+            path.display(),
+            missing
+                .iter()
+                .map(|m| m.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Removes a downloaded tarball when dropped, whichever way the install ends.
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+// --- Install ---
+
+/// Fetches (or reads) and verifies an OpenClaw tarball, then installs it into `prefix()`
+/// with npm as operation `op_id`. Nothing is installed unless the checksum matches.
+pub async fn install(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    source: InstallSource,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<InstallResult, String> {
+    if check_binary_in_path("npm").is_none() {
+        return Err("npm not found. OpenClaw needs Node.js 22 or newer; \
+            install it from https://nodejs.org and try again."
+            .to_string());
+    }
+
+    let mut npm_args: Vec<String> = Vec::new();
+    let _download: Option<TempFile>;
+    let (tarball, version, integrity) = match source {
+        InstallSource::Registry {
+            version,
+            registry,
+            integrity,
+        } => {
+            let version = version.unwrap_or_else(|| PINNED_VERSION.to_string());
+            let registry = registry.unwrap_or_else(|| DEFAULT_REGISTRY.to_string());
+            let dest = std::env::temp_dir().join(format!("{}.tgz", random_id("openclaw")));
+            _download = Some(TempFile(dest.clone()));
+            let (version, published) = fetch_release(
+                &reqwest::Client::new(),
+                &registry,
+                &version,
+                integrity.as_deref(),
+                KNOWN_RELEASES,
+                &dest,
+                &|phase, message, bytes, total| emit_progress(app, phase, message, bytes, total),
+            )
+            .await?;
+
+            // Dependencies come from the same registry.
+            npm_args.push(format!("--registry={}", registry));
+            (dest, Some(version), published)
+        }
+        InstallSource::Tarball {
+            path,
+            integrity,
+            offline,
+        } => {
+            _download = None;
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(format!("Tarball not found: {}", path.display()));
+            }
+            let sidecar = PathBuf::from(format!("{}.integrity", path.display()));
+            let expected = match integrity {
+                Some(integrity) => integrity,
+                None => std::fs::read_to_string(&sidecar)
+                    .map(|s| s.trim().to_string())
+                    .map_err(|_| {
+                        format!(
+                            "No checksum for {}: pass one, or put the SRI integrity \
+                            from `npm pack` in {}",
+                            path.display(),
+                            sidecar.display()
+                        )
+                    })?,
+            };
+
+            emit_progress(
+                app,
+                InstallPhase::Verifying,
+                "Verifying checksum",
+                None,
+                None,
+            );
+            let digests = digest_file(&path)?;
+            check_integrity(&expected, &digests)?;
+
+            if offline.unwrap_or(true) {
+                check_bundled(&path)?;
+                npm_args.push("--offline".to_string());
+            }
+            (path, None, expected)
+        }
+        InstallSource::Script => return install_script(app, paths, op_id, timeout_secs).await,
+    };
+
+    emit_progress(
+        app,
+        InstallPhase::Installing,
+        "Installing OpenClaw",
+        None,
+        None,
+    );
+    let prefix = prefix();
+    let tarball = tarball.to_string_lossy().to_string();
+    let prefix_arg = prefix.to_string_lossy().to_string();
+    let mut args = vec![
+        "install",
+        "--global",
+        "--prefix",
+        prefix_arg.as_str(),
+        "--no-fund",
+        "--no-audit",
+    ];
+    args.extend(npm_args.iter().map(String::as_str));
+    args.push(tarball.as_str());
+    let spec = OperationSpec::new("npm", &args)
+        .id(op_id)
+        .label("npm install openclaw")
+        .timeout(timeout_secs, INSTALL_TIMEOUT);
+    operations::run(app, paths, spec, None).await?;
+
+    let binary = installed_binary();
+    if binary.is_none() {
+        return Err(format!(
+            "npm finished but no openclaw binary appeared in {}",
+            bin_dir().display()
+        ));
+    }
+    emit_progress(app, InstallPhase::Done, "OpenClaw installed", None, None);
+    Ok(InstallResult {
+        version,
+        integrity: Some(integrity),
+        prefix: prefix_arg,
+        binary: binary.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// Runs OpenClaw's install script, as the app did before it installed releases itself.
+async fn install_script(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<InstallResult, String> {
+    emit_progress(
+        app,
+        InstallPhase::Installing,
+        "Installing OpenClaw with its install script",
+        None,
+        None,
+    );
+    let spec = OperationSpec::new("sh", &["-c", INSTALL_SCRIPT])
+        .id(op_id)
+        .label("OpenClaw install script")
+        .timeout(timeout_secs, INSTALL_TIMEOUT);
+    operations::run(app, paths, spec, None).await?;
+
+    let binary = super::openclaw::find_openclaw()
+        .ok_or("The install script finished but no openclaw binary is in PATH")?;
+    emit_progress(app, InstallPhase::Done, "OpenClaw installed", None, None);
+    Ok(InstallResult {
+        version: None,
+        integrity: None,
+        prefix: prefix().to_string_lossy().to_string(),
+        binary: Some(binary),
+    })
+}
+
+// --- Commands ---
+
+/// Installs OpenClaw from `source` (the pinned registry release by default, or the install
+/// script while it has no known checksum), reporting
+/// `openclaw-install-progress` events; npm's output streams as `openclaw-log` tagged
+/// with `op_id`.
+#[tauri::command]
+pub async fn openclaw_install(
+    app: AppHandle,
+    source: Option<InstallSource>,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+    settings: State<'_, SettingsState>,
+) -> Result<InstallResult, String> {
+    let _claim = operations::claim(&app, "Installing OpenClaw", &[ExclusionGroup::Install])?;
+    install(
+        &app,
+        &settings.paths(),
+        source.unwrap_or_default(),
+        op_id,
+        timeout_secs,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{MockResponse, MockServer};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    /// An `npm pack`-style tarball holding `files` under `package/`.
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, format!("package/{}", name), content.as_bytes())
+                .unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn digests(bytes: &[u8]) -> Digests {
+        Digests {
+            sha256: Sha256::digest(bytes).to_vec(),
+            sha512: Sha512::digest(bytes).to_vec(),
+        }
+    }
+
+    fn digests_integrity(bytes: &[u8]) -> String {
+        digests(bytes).sha512_integrity()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}", random_id("installer-test"), name))
+    }
+
+    #[test]
+    fn checks_sri_strings() {
+        let digests = digests(b"openclaw");
+        let sha512 = digests.sha512_integrity();
+        let sha256 = format!("sha256-{}", BASE64.encode(&digests.sha256));
+
+        assert!(check_integrity(&sha512, &digests).is_ok());
+        assert!(check_integrity(&sha256, &digests).is_ok());
+        assert!(check_integrity(&format!("{}?opt", sha512), &digests).is_ok());
+        // Any matching entry of several will do.
+        let other = digests_integrity(b"something else");
+        assert!(check_integrity(&format!("{} {}", other, sha512), &digests).is_ok());
+
+        let err = check_integrity(&other, &digests).unwrap_err();
+        assert!(err.starts_with("Checksum mismatch"), "{}", err);
+        let err = check_integrity("sha1-abc md5-def", &digests).unwrap_err();
+        assert!(err.starts_with("No sha256 or sha512 checksum"), "{}", err);
+        assert!(check_integrity("", &digests).is_err());
+    }
+
+    #[test]
+    fn offline_tarballs_must_bundle_their_dependencies() {
+        let manifest =
+            r#"{ "name": "openclaw", "dependencies": { "ws": "^8", "@scope/util": "1" } }"#;
+        let bundled = temp_path("bundled.tgz");
+        std::fs::write(
+            &bundled,
+            tarball(&[
+                ("package.json", manifest),
+                ("node_modules/ws/package.json", "{}"),
+                ("node_modules/@scope/util/package.json", "{}"),
+            ]),
+        )
+        .unwrap();
+        let unbundled = temp_path("unbundled.tgz");
+        std::fs::write(
+            &unbundled,
+            tarball(&[
+                ("package.json", manifest),
+                (
+                    "node_modules/ws/node_modules/@scope/util/package.json",
+                    "{}",
+                ),
+            ]),
+        )
+        .unwrap();
+        let not_a_package = temp_path("other.tgz");
+        std::fs::write(&not_a_package, tarball(&[("README.md", "hi")])).unwrap();
+
+        let results = (
+            check_bundled(&bundled),
+            check_bundled(&unbundled),
+            check_bundled(&not_a_package),
+        );
+        for path in [&bundled, &unbundled, &not_a_package] {
+            let _ = std::fs::remove_file(path);
+        }
+        assert!(results.0.is_ok(), "{:?}", results.0);
+        let err = results.1.unwrap_err();
+        assert!(err.contains("@scope/util, ws"), "{}", err);
+        assert!(results
+            .2
+            .unwrap_err()
+            .contains("not an npm package tarball"));
+    }
+
+    /// A stand-in npm registry serving `version` with `published` as its integrity.
+    async fn registry(
+        version: &'static str,
+        tgz: Vec<u8>,
+        published: Option<String>,
+    ) -> MockServer {
+        let tarball_path = format!("/openclaw/-/openclaw-{}.tgz", version);
+        let addr = std::sync::Arc::new(std::sync::OnceLock::<String>::new());
+        let base = addr.clone();
+        let server = MockServer::start(move |req| {
+            if req.path == format!("/openclaw/{}", version) {
+                let mut dist = serde_json::json!({
+                    "tarball": format!("{}{}", base.get().unwrap(), tarball_path),
+                });
+                if let Some(published) = &published {
+                    dist["integrity"] = published.clone().into();
+                }
+                MockResponse::json(200, serde_json::json!({ "version": version, "dist": dist }))
+            } else if req.path == tarball_path {
+                MockResponse::new(200, tgz.clone())
+            } else {
+                MockResponse::json(404, serde_json::json!({ "error": "Not found" }))
+            }
+        })
+        .await;
+        addr.set(server.url("")).unwrap();
+        server
+    }
+
+    async fn fetch(
+        server: &MockServer,
+        version: &str,
+        expected: Option<&str>,
+        known: &[(&str, &str)],
+    ) -> (Result<(String, String), String>, Option<Vec<u8>>) {
+        let dest = temp_path("download.tgz");
+        let result = fetch_release(
+            &reqwest::Client::new(),
+            &server.url(""),
+            version,
+            expected,
+            known,
+            &dest,
+            &|_, _, _, _| {},
+        )
+        .await;
+        let downloaded = std::fs::read(&dest).ok();
+        let _ = std::fs::remove_file(&dest);
+        (result, downloaded)
+    }
+
+    #[tokio::test]
+    async fn fetches_and_verifies_a_release() {
+        let tgz = tarball(&[("package.json", r#"{ "name": "openclaw" }"#)]);
+        let published = digests(&tgz).sha512_integrity();
+        let server = registry("2026.1.5", tgz.clone(), Some(published.clone())).await;
+
+        let (result, downloaded) = fetch(&server, "2026.1.5", None, &[]).await;
+        assert_eq!(result.unwrap(), ("2026.1.5".to_string(), published.clone()));
+        assert_eq!(downloaded.unwrap(), tgz);
+
+        let (result, _) = fetch(&server, "2026.1.5", Some(&published), &[]).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn verifies_the_pinned_release_against_its_known_checksum() {
+        let tgz = tarball(&[("package.json", r#"{ "name": "openclaw" }"#)]);
+        let published = digests(&tgz).sha512_integrity();
+        let server = registry(PINNED_VERSION, tgz.clone(), Some(published.clone())).await;
+
+        let known = [(PINNED_VERSION, published.as_str())];
+        let (result, downloaded) = fetch(&server, PINNED_VERSION, None, &known).await;
+        assert_eq!(
+            result.unwrap(),
+            (PINNED_VERSION.to_string(), published.clone())
+        );
+        assert_eq!(downloaded.unwrap(), tgz);
+
+        // A registry serving a different tarball than the one tested is refused.
+        let other = digests_integrity(b"x");
+        let known = [(PINNED_VERSION, other.as_str())];
+        let (result, downloaded) = fetch(&server, PINNED_VERSION, None, &known).await;
+        assert!(result.unwrap_err().contains("differs from the expected"));
+        assert!(downloaded.is_none());
+    }
+
+    #[test]
+    fn defaults_to_the_script_until_the_pinned_release_is_known() {
+        match (InstallSource::default(), known_integrity(PINNED_VERSION)) {
+            (
+                InstallSource::Registry {
+                    version: None,
+                    registry: None,
+                    integrity: None,
+                },
+                Some(_),
+            ) => {}
+            (InstallSource::Script, None) => {}
+            other => panic!("unexpected default {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_a_release_that_fails_a_check() {
+        let tgz = tarball(&[("package.json", r#"{ "name": "openclaw" }"#)]);
+        let published = digests(&tgz).sha512_integrity();
+
+        // The registry vouches for a different tarball than it serves.
+        let server = registry("2026.1.5", tgz.clone(), Some(digests_integrity(b"x"))).await;
+        let (result, _) = fetch(&server, "2026.1.5", None, &[]).await;
+        assert!(result.unwrap_err().starts_with("Checksum mismatch"));
+
+        // The registry's checksum differs from the one the caller expects; nothing is
+        // downloaded.
+        let server = registry("2026.1.5", tgz.clone(), Some(published)).await;
+        let (result, downloaded) =
+            fetch(&server, "2026.1.5", Some(&digests_integrity(b"x")), &[]).await;
+        assert!(result.unwrap_err().contains("differs from the expected"));
+        assert!(downloaded.is_none());
+
+        let (result, _) = fetch(&server, "2099.1.1", None, &[]).await;
+        assert!(result.unwrap_err().contains("not found"));
+
+        let server = registry("2026.1.5", tgz, None).await;
+        let (result, _) = fetch(&server, "2026.1.5", None, &[]).await;
+        assert!(result.unwrap_err().contains("no sha512 integrity"));
+    }
+}
@@ -1,4 +1,26 @@
+pub mod agents;
+pub mod config_files;
+pub mod config_preview;
+pub mod config_watcher;
 pub mod diagnostics;
+pub mod gateway;
+pub mod gateway_chat;
+pub mod gateway_supervisor;
+pub mod gateway_token;
+pub mod gateway_ws;
+pub mod installer;
 pub mod openclaw;
+pub mod openclaw_config;
+pub mod openclaw_paths;
+pub mod openclaw_version;
 pub mod openrouter;
+pub mod operations;
+pub mod provider_keys;
+pub mod providers;
+pub mod settings;
 pub mod setup;
+pub mod snapshots;
+#[cfg(test)]
+pub mod test_support;
+pub mod uninstall;
+pub mod vault;
@@ -1,21 +1,47 @@
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
-
-use super::diagnostics::{check_binary_in_path, resolve_login_shell_path, OPENCLAW_BASE};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::diagnostics::{check_binary_in_path, resolve_login_shell_path};
+use super::config_preview::{
+    apply_or_preview, apply_or_preview_held, ConfigChange, ConfigPreview, PendingPreviews,
+};
+use super::agents;
+use super::gateway::{self, GatewayProbe, ProbeResult};
+use super::gateway_supervisor::GatewaySupervisor;
+use super::gateway_token;
+use super::installer;
+use super::openclaw_config::{
+    self, is_valid_agent_id, AgentBinding, AgentEntry, AgentModelConfig, BindingMatch,
+    OpenClawConfig, ProviderConfig,
+};
+use super::openclaw_paths::OpenClawPaths;
+use super::openrouter;
+use super::operations::{self, ExclusionGroup, LineHook, OperationSpec};
+use super::provider_keys;
+use super::providers;
+use super::settings::SettingsState;
+use super::vault::{mask_key, Vault};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OpenClawStatus {
     pub installed: bool,
     pub path: Option<String>,
     pub gateway_running: bool,
+    pub gateway_url: String,
+    /// The gateway runs on another machine (`gateway.mode` is "remote").
+    pub gateway_remote: bool,
+    /// Set when the health probe ran; says what is (or isn't) on the gateway's port.
+    pub gateway: Option<GatewayProbe>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OpenClawLogEvent {
     pub line: String,
     pub stream: String, // "stdout" or "stderr"
+    /// Operation that produced the line.
+    pub op_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,441 +49,243 @@ pub struct OpenClawQrEvent {
     pub data: String,
 }
 
-fn find_openclaw() -> Option<String> {
-    check_binary_in_path("openclaw")
-}
-
-fn openclaw_config_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("~"))
-        .join(".openclaw")
-        .join("openclaw.json")
-}
-
-fn openclaw_agent_auth_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("~"))
-        .join(".openclaw")
-        .join("agents")
-        .join("main")
-        .join("agent")
-        .join("auth-profiles.json")
-}
-
-/// Ensures gateway.mode=local in ~/.openclaw/openclaw.json so the gateway can start
-/// (OpenClaw blocks startup unless this is set or --allow-unconfigured is passed).
-/// For local mode, removes gateway.auth to avoid "device token mismatch" errors that
-/// doctor --fix can introduce (see openclaw/openclaw#18225).
-fn ensure_gateway_mode() -> Result<(), String> {
-    let config_path = openclaw_config_path();
-
-    if let Some(parent) = config_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    let mut config: serde_json::Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config: {}", e))?;
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-
-    let obj = config
-        .as_object_mut()
-        .ok_or("Config is not a JSON object")?;
-
-    let gateway = obj
-        .entry("gateway")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("gateway must be an object")?;
-
-    gateway.insert("mode".to_string(), serde_json::json!("local"));
-
-    // For local loopback gateways, gateway.auth causes "device token mismatch" errors
-    // when doctor --fix adds it. Remove auth so the gateway accepts local connections.
-    gateway.remove("auth");
-
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-    std::fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
-
-    Ok(())
-}
-
-/// Removes gateway.auth from config for local mode. doctor --fix adds it and causes
-/// "device token mismatch" for loopback gateways (openclaw/openclaw#18225).
-fn remove_gateway_auth_for_local() -> Result<(), String> {
-    let config_path = openclaw_config_path();
-    if !config_path.exists() {
-        return Ok(());
-    }
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-    let mut config: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| format!("Invalid config: {}", e))?;
-    let gateway = config
-        .get_mut("gateway")
-        .and_then(|g| g.as_object_mut());
-    if let Some(gw) = gateway {
-        gw.remove("auth");
-    }
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    std::fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
-    Ok(())
+/// The installer's user-local binary comes first, matching the PATH commands run with.
+pub fn find_openclaw() -> Option<String> {
+    installer::installed_binary()
+        .map(|p| p.to_string_lossy().to_string())
+        .or_else(|| check_binary_in_path("openclaw"))
 }
 
-/// On macOS, removes OPENCLAW_GATEWAY_TOKEN from the LaunchAgent plist. The token
-/// causes "device token mismatch" when it doesn't match the config (which we clear).
-#[cfg(target_os = "macos")]
-fn remove_gateway_token_from_launchd() -> Result<(), String> {
-    let home = dirs::home_dir().ok_or("Cannot determine home directory")?;
-    let plist_path = home
-        .join("Library")
-        .join("LaunchAgents")
-        .join("ai.openclaw.gateway.plist");
-    if !plist_path.exists() {
-        return Ok(());
-    }
-    let status = std::process::Command::new("/usr/libexec/PlistBuddy")
-        .args([
-            "-c",
-            "Delete :EnvironmentVariables:OPENCLAW_GATEWAY_TOKEN",
-            plist_path.to_str().unwrap(),
-        ])
-        .status()
-        .map_err(|e| format!("Failed to run PlistBuddy: {}", e))?;
-    // Exit code 0 = success, 1 = key didn't exist (also fine)
-    if !status.success() && status.code() != Some(1) {
-        return Err(format!(
-            "PlistBuddy failed with code {:?}",
-            status.code()
-        ));
+/// Ensures gateway.mode is set in ~/.openclaw/openclaw.json so the gateway can start
+/// (OpenClaw blocks startup unless this is set or --allow-unconfigured is passed),
+/// and that a local gateway has an auth token. Remote mode is left alone.
+fn ensure_gateway_mode(config: &mut OpenClawConfig) {
+    let gateway = config.gateway_mut();
+    if gateway.mode.as_deref() == Some("remote") {
+        return;
     }
-    Ok(())
+    gateway.mode = Some("local".to_string());
+    gateway_token::ensure_config_token(config);
 }
 
-#[cfg(not(target_os = "macos"))]
-fn remove_gateway_token_from_launchd() -> Result<(), String> {
-    Ok(())
-}
+/// Timeout for onboarding, which can wait on prompts.
+const ONBOARD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Timeout for `channels login`, which waits for the QR code to be scanned.
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
-/// Helper: spawn a shell command and stream stdout/stderr as openclaw-log events.
+/// Helper: run a command as an operation with the default timeout, streaming
+/// stdout/stderr as openclaw-log events.
+/// The command runs against the OpenClaw installation in `paths`.
 /// Returns an error string if the process exits with a non-zero code.
-async fn spawn_and_stream(
+pub async fn spawn_and_stream(
     app: &AppHandle,
+    paths: &OpenClawPaths,
     program: &str,
     args: &[&str],
 ) -> Result<(), String> {
-    use tokio::io::{AsyncBufReadExt, BufReader};
-    use tokio::process::Command;
-
-    let mut cmd = Command::new(program);
-    cmd.args(args)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-
-    // On macOS, GUI apps get a minimal PATH. Use the login shell PATH so we
-    // can find binaries like openclaw, ollama, etc.
-    if cfg!(target_os = "macos") {
-        if let Some(ref path) = resolve_login_shell_path() {
-            cmd.env("PATH", path);
-        }
-    }
-
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn {} {:?}: {}", program, args, e))?;
-
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-    let app_out = app.clone();
-    let app_err = app.clone();
-
-    let stdout_handle = tokio::spawn(async move {
-        if let Some(stdout) = stdout {
-            let mut reader = BufReader::new(stdout).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
-                let _ = app_out.emit(
-                    "openclaw-log",
-                    OpenClawLogEvent {
-                        line,
-                        stream: "stdout".to_string(),
-                    },
-                );
-            }
-        }
-    });
-
-    let stderr_handle = tokio::spawn(async move {
-        if let Some(stderr) = stderr {
-            let mut reader = BufReader::new(stderr).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
-                let _ = app_err.emit(
-                    "openclaw-log",
-                    OpenClawLogEvent {
-                        line,
-                        stream: "stderr".to_string(),
-                    },
-                );
-            }
-        }
-    });
-
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| format!("Failed to wait for process: {}", e))?;
-
-    let _ = stdout_handle.await;
-    let _ = stderr_handle.await;
-
-    if !status.success() {
-        return Err(format!(
-            "{} exited with code {:?}",
-            program,
-            status.code()
-        ));
-    }
-
-    Ok(())
+    operations::run(app, paths, OperationSpec::new(program, args), None).await
 }
 
 #[tauri::command]
-pub async fn openclaw_check() -> Result<OpenClawStatus, String> {
+pub async fn openclaw_check(settings: State<'_, SettingsState>) -> Result<OpenClawStatus, String> {
+    let target = gateway::target(&settings.paths());
     let path = find_openclaw();
     let installed = path.is_some();
-
-    let gateway_running = if let Ok(client) = Client::builder()
-        .timeout(std::time::Duration::from_secs(3))
-        .build()
-    {
-        match client.get(OPENCLAW_BASE).send().await {
-            Ok(resp) => resp.status().is_success() || resp.status().as_u16() < 500,
-            Err(_) => false,
-        }
-    } else {
-        false
-    };
+    let probe = gateway::probe(&target.url, target.token.as_deref()).await;
 
     Ok(OpenClawStatus {
         installed,
         path,
-        gateway_running,
+        gateway_running: probe.result == ProbeResult::Running,
+        gateway_url: target.url,
+        gateway_remote: target.remote,
+        gateway: Some(probe),
     })
 }
 
+/// With `dry_run`, returns a preview of the config change made before onboarding and
+/// doesn't run the wizard. The wizard runs as operation `op_id`; its prompts are answered
+/// with `operation_write_stdin`.
 #[tauri::command]
-pub async fn openclaw_install(app: AppHandle) -> Result<(), String> {
-    spawn_and_stream(
-        &app,
-        "sh",
-        &["-c", "curl -fsSL https://openclaw.ai/install.sh | bash"],
-    )
-    .await
-}
-
-#[tauri::command]
-pub async fn openclaw_onboard(app: AppHandle) -> Result<(), String> {
+pub async fn openclaw_onboard(
+    app: AppHandle,
+    dry_run: Option<bool>,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let paths = settings.paths();
+    let claim = match dry_run {
+        Some(true) => None,
+        _ => Some(operations::claim(
+            &app,
+            "Onboarding",
+            &[ExclusionGroup::Config, ExclusionGroup::Gateway],
+        )?),
+    };
     // Ensure gateway.mode=local before onboarding so the LaunchAgent can start
-    ensure_gateway_mode()?;
-    spawn_and_stream(&app, "openclaw", &["onboard", "--install-daemon"]).await
-}
-
-/// Check if the gateway is reachable (required for channels login to work).
-async fn gateway_reachable() -> bool {
-    if let Ok(client) = Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-    {
-        if let Ok(resp) = client.get(OPENCLAW_BASE).send().await {
-            return resp.status().is_success() || resp.status().as_u16() < 500;
-        }
+    let mut change = ConfigChange::begin(&paths);
+    ensure_gateway_mode(change.config_mut()?);
+    let preview =
+        apply_or_preview_held(claim.as_ref(), change, dry_run.unwrap_or(false), &previews)?;
+    if let Some(preview) = preview {
+        return Ok(Some(preview));
     }
-    false
+    let spec = OperationSpec::new("openclaw", &["onboard", "--install-daemon"])
+        .id(op_id)
+        .timeout(timeout_secs, ONBOARD_TIMEOUT);
+    operations::run(&app, &paths, spec, None).await?;
+    Ok(None)
 }
 
+/// With `dry_run`, returns a preview of the config change made before login and stops
+/// there. Later edits (doctor --fix, self-chat allowlist) depend on the CLI and the linked
+/// number, so they can't be previewed. `channels login` runs as operation `op_id`.
 #[tauri::command]
-pub async fn openclaw_connect_whatsapp(app: AppHandle) -> Result<(), String> {
-    use tokio::io::{AsyncBufReadExt, BufReader};
-    use tokio::process::Command;
-
+pub async fn openclaw_connect_whatsapp(
+    app: AppHandle,
+    dry_run: Option<bool>,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let paths = settings.paths();
+    let claim = match dry_run {
+        Some(true) => None,
+        _ => Some(operations::claim(
+            &app,
+            "Connecting WhatsApp",
+            &[ExclusionGroup::Config, ExclusionGroup::Gateway],
+        )?),
+    };
+    let step = |what: &str| {
+        if let Some(claim) = &claim {
+            claim.step(what);
+        }
+    };
     // Ensure gateway.mode=local so the gateway can start
-    ensure_gateway_mode()?;
-
-    // Fix invalid config (e.g. legacy root-level provider/model/contextWindow/maxTokens)
-    // before attempting login; doctor --fix removes unrecognized keys
-    let _ = spawn_and_stream(&app, "openclaw", &["doctor", "--fix", "--yes"]).await;
-
-    // doctor --fix can add gateway.auth which causes "device token mismatch" for local
-    // loopback gateways (openclaw/openclaw#18225). Remove it so the gateway accepts connections.
-    remove_gateway_auth_for_local()?;
-
-    // On macOS, the LaunchAgent plist has OPENCLAW_GATEWAY_TOKEN baked in; remove it too.
-    remove_gateway_token_from_launchd()?;
+    let mut change = ConfigChange::begin(&paths);
+    ensure_gateway_mode(change.config_mut()?);
+    let preview =
+        apply_or_preview_held(claim.as_ref(), change, dry_run.unwrap_or(false), &previews)?;
+    if let Some(preview) = preview {
+        return Ok(Some(preview));
+    }
 
-    // Stop any running gateway (may be using old token), then start fresh with no auth.
-    let _ = spawn_and_stream(&app, "openclaw", &["gateway", "stop"]).await;
-    let _ = spawn_and_stream(&app, "openclaw", &["gateway", "start"]).await;
-    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    // A remote gateway is managed on its own host; only prepare a local one.
+    let target = gateway::target(&paths);
+    if !target.remote {
+        // Fix invalid config (e.g. legacy root-level provider/model/contextWindow/maxTokens)
+        // before attempting login; doctor --fix removes unrecognized keys
+        step("Repairing the config");
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["doctor", "--fix", "--yes"]).await;
+
+        // doctor --fix can write a new gateway token to the config or the service definition;
+        // a mismatch between them causes "device token mismatch" (openclaw/openclaw#18225).
+        gateway_token::sync(&paths)?;
+
+        // Stop any running gateway (may be using an old token), then start it with the synced one.
+        step("Restarting the gateway");
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["gateway", "stop"]).await;
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["gateway", "start"]).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    }
 
     // WhatsApp is a plugin that is disabled by default; enable it before login
-    let _ = spawn_and_stream(&app, "openclaw", &["plugins", "enable", "whatsapp"]).await;
+    step("Enabling the WhatsApp plugin");
+    let _ = spawn_and_stream(&app, &paths, "openclaw", &["plugins", "enable", "whatsapp"]).await;
 
     // Gateway must be reachable for channels login to display the QR and complete pairing.
     // If it times out, channels login will fail and the QR may never appear.
-    if !gateway_reachable().await {
-        return Err(
+    let probe = gateway::probe(&target.url, target.token.as_deref()).await;
+    if probe.result == ProbeResult::Foreign {
+        return Err(format!(
+            "{}. Stop that program or point OpenClaw at another port.",
+            probe.message
+        ));
+    }
+    if probe.result != ProbeResult::Running {
+        return Err(format!(
             "OpenClaw gateway is not reachable. The gateway must be running for WhatsApp login. \
             Try: openclaw gateway stop (to clear any stuck process), then openclaw gateway start. \
-            Or open http://127.0.0.1:18789/ in a browser to use the Control UI.".to_string(),
-        );
+            Or open {}/ in a browser to use the Control UI.",
+            target.url
+        ));
     }
 
-    let mut cmd = Command::new("openclaw");
-    cmd.args(["channels", "login", "--channel", "whatsapp"])
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
+    step("Waiting for the QR code to be scanned");
+    let spec = OperationSpec::new("openclaw", &["channels", "login", "--channel", "whatsapp"])
+        .id(op_id)
+        .timeout(timeout_secs, LOGIN_TIMEOUT);
+    operations::run(&app, &paths, spec, Some(qr_scanner(app.clone()))).await?;
 
-    if cfg!(target_os = "macos") {
-        if let Some(ref path) = resolve_login_shell_path() {
-            cmd.env("PATH", path);
-        }
-    }
+    // After successful WhatsApp login, discover the linked number and configure
+    // channels.whatsapp.allowFrom + dmPolicy so self-chat safeguards activate.
+    // Without this, the bot replies to its own messages creating an infinite loop.
+    step("Setting up self-chat safeguards");
+    configure_whatsapp_self_chat(&paths).await.ok();
 
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn openclaw channels login: {}", e))?;
-
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-    let app_out = app.clone();
-    let app_err = app.clone();
-    let app_qr = app.clone();
-
-    let stdout_handle = tokio::spawn(async move {
-        if let Some(stdout) = stdout {
-            let mut reader = BufReader::new(stdout).lines();
-            let mut qr_lines: Vec<String> = Vec::new();
-            let mut in_qr = false;
-
-            while let Ok(Some(line)) = reader.next_line().await {
-                // Detect QR code blocks (lines with block characters)
-                let is_qr_line = line.contains('\u{2588}')  // █ Full block
-                    || line.contains('\u{2584}')             // ▄ Lower half block
-                    || line.contains('\u{2580}')             // ▀ Upper half block
-                    || line.contains('\u{2591}')             // ░ Light shade
-                    || line.contains('\u{2592}')             // ▒ Medium shade
-                    || line.contains('\u{2593}');            // ▓ Dark shade
-
-                if is_qr_line {
-                    if !in_qr {
-                        in_qr = true;
-                        qr_lines.clear();
-                    }
-                    qr_lines.push(line.clone());
-                } else if in_qr {
-                    // QR block ended — emit only if it looks like a real QR (square-ish, min size)
-                    in_qr = false;
-                    let rows = qr_lines.len();
-                    let cols = qr_lines.iter().map(|l| l.len()).max().unwrap_or(0);
-                    let is_squareish = rows >= 10 && cols >= 10
-                        && (rows as f64 / cols as f64) >= 0.3
-                        && (rows as f64 / cols as f64) <= 3.0;
-                    if is_squareish {
-                        let qr_data = qr_lines.join("\n");
-                        let payload = OpenClawQrEvent { data: qr_data.clone() };
-                        let _ = app_qr.emit("openclaw-qr", &payload);
-                        // Also emit to main window in case global emit doesn't reach the webview
-                        let _ = app_qr.emit_to("main", "openclaw-qr", &payload);
-                    }
-                    qr_lines.clear();
-                }
-
-                let _ = app_out.emit(
-                    "openclaw-log",
-                    OpenClawLogEvent {
-                        line,
-                        stream: "stdout".to_string(),
-                    },
-                );
-            }
+    Ok(None)
+}
 
-            // Emit any trailing QR data (with same filtering)
-            if !qr_lines.is_empty() {
-                let rows = qr_lines.len();
-                let cols = qr_lines.iter().map(|l| l.len()).max().unwrap_or(0);
-                let is_squareish = rows >= 10 && cols >= 10
-                    && (rows as f64 / cols as f64) >= 0.3
-                    && (rows as f64 / cols as f64) <= 3.0;
-                if is_squareish {
-                    let qr_data = qr_lines.join("\n");
-                    let payload = OpenClawQrEvent { data: qr_data.clone() };
-                    let _ = app_qr.emit("openclaw-qr", &payload);
-                    let _ = app_qr.emit_to("main", "openclaw-qr", &payload);
-                }
+/// Watches `channels login` output for terminal QR codes (block-character lines) and
+/// emits each as an openclaw-qr event. A block still open when output ends is emitted too.
+fn qr_scanner(app: AppHandle) -> LineHook {
+    let mut qr_lines: Vec<String> = Vec::new();
+    Box::new(move |line: Option<&str>| {
+        if let Some(line) = line {
+            // Detect QR code blocks (lines with block characters)
+            let is_qr_line = line.contains('\u{2588}')  // █ Full block
+                || line.contains('\u{2584}')             // ▄ Lower half block
+                || line.contains('\u{2580}')             // ▀ Upper half block
+                || line.contains('\u{2591}')             // ░ Light shade
+                || line.contains('\u{2592}')             // ▒ Medium shade
+                || line.contains('\u{2593}');            // ▓ Dark shade
+
+            if is_qr_line {
+                qr_lines.push(line.to_string());
+                return;
             }
         }
-    });
-
-    let stderr_handle = tokio::spawn(async move {
-        if let Some(stderr) = stderr {
-            let mut reader = BufReader::new(stderr).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
-                let _ = app_err.emit(
-                    "openclaw-log",
-                    OpenClawLogEvent {
-                        line,
-                        stream: "stderr".to_string(),
-                    },
-                );
-            }
+        if qr_lines.is_empty() {
+            return;
         }
-    });
-
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| format!("Failed to wait for process: {}", e))?;
-
-    let _ = stdout_handle.await;
-    let _ = stderr_handle.await;
-
-    if !status.success() {
-        return Err(format!(
-            "openclaw channels login exited with code {:?}",
-            status.code()
-        ));
-    }
-
-    // After successful WhatsApp login, discover the linked number and configure
-    // channels.whatsapp.allowFrom + dmPolicy so self-chat safeguards activate.
-    // Without this, the bot replies to its own messages creating an infinite loop.
-    configure_whatsapp_self_chat().await.ok();
-
-    Ok(())
+        // QR block ended — emit only if it looks like a real QR (square-ish, min size)
+        let rows = qr_lines.len();
+        let cols = qr_lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let is_squareish = rows >= 10 && cols >= 10
+            && (rows as f64 / cols as f64) >= 0.3
+            && (rows as f64 / cols as f64) <= 3.0;
+        if is_squareish {
+            let payload = OpenClawQrEvent { data: qr_lines.join("\n") };
+            let _ = app.emit("openclaw-qr", &payload);
+            // Also emit to main window in case global emit doesn't reach the webview
+            let _ = app.emit_to("main", "openclaw-qr", &payload);
+        }
+        qr_lines.clear();
+    })
 }
 
 /// Discovers the linked WhatsApp number and writes channels.whatsapp config
 /// to enable self-chat safeguards (loop prevention). Without allowFrom set,
 /// OpenClaw doesn't know which messages are from "self" and will reply to
 /// its own error messages, creating an infinite feedback loop.
-async fn configure_whatsapp_self_chat() -> Result<(), String> {
+async fn configure_whatsapp_self_chat(paths: &OpenClawPaths) -> Result<(), String> {
     // Get the linked phone number
     let mut cmd = tokio::process::Command::new("openclaw");
     cmd.args(["directory", "self", "--channel", "whatsapp", "--json"]);
 
-    if cfg!(target_os = "macos") {
-        if let Some(ref path) = resolve_login_shell_path() {
-            cmd.env("PATH", path);
-        }
-    }
+    // On macOS, GUI apps get a minimal PATH; use the login shell's. Either way, include
+    // the installer's prefix.
+    let base = if cfg!(target_os = "macos") {
+        resolve_login_shell_path()
+    } else {
+        None
+    };
+    cmd.env("PATH", installer::search_path(base));
+    paths.apply_env(&mut cmd);
 
     let output = cmd
         .output()
@@ -487,333 +315,397 @@ async fn configure_whatsapp_self_chat() -> Result<(), String> {
         return Err("Self phone number is empty".to_string());
     }
 
-    // Write channels.whatsapp config to openclaw.json
-    let config_path = openclaw_config_path();
-    let mut config: serde_json::Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config: {}", e))?;
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-
-    let obj = config.as_object_mut().ok_or("Config is not a JSON object")?;
-    let channels = obj
-        .entry("channels")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("channels must be an object")?;
-    let whatsapp = channels
-        .entry("whatsapp")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("channels.whatsapp must be an object")?;
-
     // allowlist + own number activates self-chat safeguards in OpenClaw:
     // - skips read receipts for self-chat
     // - ignores mention-JID auto-triggers
     // - prevents the bot from replying to its own messages
-    whatsapp.insert("dmPolicy".to_string(), serde_json::json!("allowlist"));
-    whatsapp.insert("allowFrom".to_string(), serde_json::json!([phone]));
-
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    std::fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
+    openclaw_config::update_config(paths, |config| {
+        let whatsapp = config.whatsapp_mut();
+        whatsapp.dm_policy = Some("allowlist".to_string());
+        whatsapp.allow_from = Some(vec![phone]);
+        Ok(())
+    })?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn openclaw_configure_model(model: String, api_key: String) -> Result<(), String> {
-    let config_path = openclaw_config_path();
+pub async fn openclaw_configure_model(
+    app: AppHandle,
+    model: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let setup = ModelSetup {
+        primary: ModelChoice {
+            primary: model,
+            fallbacks: None,
+        },
+        ..Default::default()
+    };
+    openclaw_configure_models(app, setup, dry_run, settings, vault, previews).await
+}
+
+/// A primary OpenRouter model (catalog id, e.g. "google/gemini-2.5-flash") and the ones
+/// OpenClaw falls back to, in order. `fallbacks: None` keeps the existing fallbacks.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelChoice {
+    pub primary: String,
+    pub fallbacks: Option<Vec<String>>,
+}
 
-    // Ensure parent directory exists
-    if let Some(parent) = config_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+impl ModelChoice {
+    fn ids(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.primary).chain(self.fallbacks.iter().flatten())
     }
 
-    // Read existing config or start with empty object
-    let mut config: serde_json::Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config: {}", e))?;
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
+    /// Sets the selection's primary, and its fallbacks when given, keeping anything else
+    /// already in it.
+    fn apply(&self, selection: &mut Option<AgentModelConfig>) {
+        let selection = selection.get_or_insert_with(Default::default);
+        selection.primary = Some(openrouter_ref(&self.primary));
+        if let Some(fallbacks) = &self.fallbacks {
+            if !fallbacks.is_empty() || selection.fallbacks.is_some() {
+                selection.fallbacks = Some(fallbacks.iter().map(|m| openrouter_ref(m)).collect());
+            }
+        }
+    }
+}
 
-    let obj = config.as_object_mut().ok_or("Config is not a JSON object")?;
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelSetup {
+    /// Default model for every agent (`agents.defaults.model`).
+    pub primary: ModelChoice,
+    /// Used for messages with images (`agents.defaults.imageModel`).
+    pub image_model: Option<ModelChoice>,
+    /// Extra models to register without assigning them, e.g. every tier we offer.
+    pub models: Vec<String>,
+    /// Overrides by agent id.
+    pub agents: BTreeMap<String, ModelChoice>,
+    /// Overrides by channel (e.g. "whatsapp"), applied to the agent that handles it.
+    pub channels: BTreeMap<String, ModelChoice>,
+    /// Lets a channel override give a channel that has no agent of its own a new one, with
+    /// a fresh workspace. Otherwise such overrides are refused.
+    pub dedicated_channel_agents: bool,
+}
+
+fn openrouter_ref(model_id: &str) -> String {
+    format!("openrouter/{}", model_id)
+}
+
+/// The agent whose model decides replies on `channel`: the one bound to it. A channel
+/// that has no binding, or shares its agent with other channels, only gets a dedicated
+/// agent named after it with `dedicated`, since that agent starts with a fresh workspace
+/// (and memory); it gets the default agent's keys.
+fn channel_agent(
+    change: &mut ConfigChange,
+    paths: &OpenClawPaths,
+    channel: &str,
+    dedicated: bool,
+) -> Result<String, String> {
+    let config = change.config_mut()?;
+    let default_id = config.default_agent_id();
+    let bound = config
+        .bindings()
+        .iter()
+        .find(|b| {
+            b.match_rule.channel == channel
+                && b.match_rule.account_id.is_none()
+                && b.match_rule.peer.is_none()
+        })
+        .map(|b| b.agent_id.clone());
+    if let Some(agent_id) = &bound {
+        let shared = agent_id == &default_id
+            || config
+                .bindings()
+                .iter()
+                .any(|b| &b.agent_id == agent_id && b.match_rule.channel != channel);
+        if !shared {
+            return Ok(agent_id.clone());
+        }
+    }
+    if !dedicated {
+        return Err(format!(
+            "{} has no agent of its own, so a model override would also apply elsewhere; \
+             set the model on agent \"{}\" or allow a dedicated agent for the channel",
+            channel,
+            bound.unwrap_or(default_id)
+        ));
+    }
+
+    let agent_id = channel
+        .to_lowercase()
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "-");
+    if !is_valid_agent_id(&agent_id) {
+        return Err(format!(
+            "Can't derive an agent id from channel \"{}\"",
+            channel
+        ));
+    }
+    let default_auth = agents::agent_auth_path(paths, config, &default_id);
+    if config.agent_mut(&agent_id).is_none() {
+        config.agent_list_mut().push(AgentEntry {
+            id: agent_id.clone(),
+            name: Some(format!("{} agent", channel)),
+            ..Default::default()
+        });
+        let target_auth = agents::agent_auth_path(paths, config, &agent_id);
+        let profiles = change.auth_at_mut(default_auth)?.clone();
+        *change.auth_at_mut(target_auth)? = profiles;
+    }
+    let config = change.config_mut()?;
+    config.bindings_mut().retain(|b| {
+        !(b.match_rule.channel == channel
+            && b.match_rule.account_id.is_none()
+            && b.match_rule.peer.is_none())
+    });
+    config.bindings_mut().push(AgentBinding {
+        agent_id: agent_id.clone(),
+        match_rule: BindingMatch {
+            channel: channel.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    Ok(agent_id)
+}
 
-    // Ensure gateway.mode=local so the gateway can start (required by OpenClaw)
-    let gateway = obj
-        .entry("gateway")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("gateway must be an object")?;
-    gateway.insert("mode".to_string(), serde_json::json!("local"));
+/// Registers a set of OpenRouter models with OpenClaw (with real limits and pricing from
+/// the catalog when it can be reached) and assigns them: a default with fallbacks, an
+/// image model, and per-agent or per-channel overrides.
+#[tauri::command]
+pub async fn openclaw_configure_models(
+    app: AppHandle,
+    setup: ModelSetup,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    if setup.primary.primary.trim().is_empty() {
+        return Err("A primary model is required".to_string());
+    }
+    let api_key = settings.openrouter_key(&vault)?;
+
+    // Real context length, output limit, pricing and modalities, so OpenClaw budgets
+    // context and reports cost correctly.
+    let mut wanted: Vec<&String> = setup
+        .primary
+        .ids()
+        .chain(setup.image_model.iter().flat_map(|m| m.ids()))
+        .chain(setup.models.iter())
+        .chain(setup.agents.values().flat_map(|m| m.ids()))
+        .chain(setup.channels.values().flat_map(|m| m.ids()))
+        .collect();
+    wanted.sort();
+    wanted.dedup();
+    // Without the catalog (offline), models keep the definition registered earlier or get
+    // a conservative one, and ids can't be checked.
+    let catalog = openrouter::fetch_catalog().await.ok();
+    let mut definitions = Vec::new();
+    let mut unknown = Vec::new();
+    for id in wanted {
+        match catalog.as_ref().map(|c| c.iter().find(|m| &m.id == id)) {
+            Some(Some(model)) => definitions.push(model.to_definition()),
+            Some(None) => unknown.push(id.as_str()),
+            None => definitions.push(openrouter::offline_definition(id)),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Not in the OpenRouter catalog: {}",
+            unknown.join(", ")
+        ));
+    }
+
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    let config = change.config_mut()?;
+
+    // Ensure gateway.mode is set so the gateway can start (required by OpenClaw)
+    let gateway = config.gateway_mut();
+    if gateway.mode.as_deref() != Some("remote") {
+        gateway.mode = Some("local".to_string());
+    }
 
     // Remove legacy root-level keys that OpenClaw no longer accepts
-    obj.remove("provider");
-    obj.remove("model");
-    obj.remove("contextWindow");
-    obj.remove("maxTokens");
+    config.remove_legacy_keys();
 
-    // Register OpenRouter as a provider with explicit model definition.
+    // Register OpenRouter as a provider with the models' catalog definitions, keeping
+    // models registered earlier so existing references stay valid.
     // OpenRouter uses standard openai-completions API.
-    let model_ref = format!("openrouter/{}", model);
-    let models_section = obj
-        .entry("models")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("models must be an object")?;
-    models_section.insert("mode".to_string(), serde_json::json!("merge"));
-    let providers = models_section
-        .entry("providers")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("models.providers must be an object")?;
-    providers.insert(
-        "openrouter".to_string(),
-        serde_json::json!({
-            "baseUrl": "https://openrouter.ai/api/v1",
-            "apiKey": "openrouter",
-            "api": "openai-completions",
-            "models": [{
-                "id": model,
-                "name": "Daemon (OpenRouter)",
-                "reasoning": false,
-                "input": ["text"],
-                "cost": { "input": 0, "output": 0, "cacheRead": 0, "cacheWrite": 0 },
-                "contextWindow": 16384,
-                "maxTokens": 8192
-            }]
-        }),
-    );
+    config.models_mut().mode = Some("merge".to_string());
+    let provider = config
+        .providers_mut()
+        .entry("openrouter".to_string())
+        .or_insert_with(ProviderConfig::default);
+    provider.base_url = Some("https://openrouter.ai/api/v1".to_string());
+    provider.api_key = Some("openrouter".to_string());
+    provider.api = Some("openai-completions".to_string());
+    let registered = provider.models.get_or_insert_with(Vec::new);
+    for definition in definitions {
+        match registered.iter_mut().find(|m| m.id == definition.id) {
+            Some(existing) if catalog.is_some() => *existing = definition,
+            Some(_) => {}
+            None => registered.push(definition),
+        }
+    }
 
-    // Set OpenRouter model as the default under agents.defaults
-    let agents = obj
-        .entry("agents")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("agents must be an object")?;
-    let defaults = agents
-        .entry("defaults")
-        .or_insert_with(|| serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("agents.defaults must be an object")?;
-    defaults.insert(
-        "model".to_string(),
-        serde_json::json!({ "primary": model_ref }),
-    );
+    // Defaults under agents.defaults
+    let defaults = config.agent_defaults_mut();
+    setup.primary.apply(&mut defaults.model);
+    if let Some(image) = &setup.image_model {
+        image.apply(&mut defaults.image_model);
+    }
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    std::fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
-
-    // Write auth-profiles.json for the OpenRouter provider so OpenClaw authenticates correctly.
-    let agent_dir = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("~"))
-        .join(".openclaw")
-        .join("agents")
-        .join("main")
-        .join("agent");
-    std::fs::create_dir_all(&agent_dir)
-        .map_err(|e| format!("Failed to create agent dir: {}", e))?;
-    let auth_path = agent_dir.join("auth-profiles.json");
-
-    // Read existing auth profiles or start fresh
-    let mut auth: serde_json::Value = if auth_path.exists() {
-        let content = std::fs::read_to_string(&auth_path)
-            .map_err(|e| format!("Failed to read auth profiles: {}", e))?;
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    let auth_obj = auth.as_object_mut().ok_or("Auth profiles must be a JSON object")?;
-    auth_obj.insert(
-        "openrouter:local".to_string(),
+    for (agent_id, choice) in &setup.agents {
+        let agent = config
+            .agent_mut(agent_id)
+            .ok_or_else(|| format!("Unknown agent: {}", agent_id))?;
+        choice.apply(&mut agent.model);
+    }
+    let dedicated = setup.dedicated_channel_agents;
+    for (channel, choice) in &setup.channels {
+        let agent_id = channel_agent(&mut change, &paths, channel, dedicated)?;
+        if let Some(agent) = change.config_mut()?.agent_mut(&agent_id) {
+            choice.apply(&mut agent.model);
+        }
+    }
+
+    // Write auth-profiles.json for the OpenRouter provider so OpenClaw authenticates correctly,
+    // under the registry's profile id so the API key list sees it. Earlier versions wrote
+    // "openrouter:local", which would otherwise be left behind holding the old key.
+    let openrouter = providers::find_provider(&settings.get(), "openrouter")?;
+    let auth = change.auth_mut()?;
+    openclaw_config::remove_auth_profile(auth, "openrouter:local");
+    openclaw_config::auth_profile_map_mut(auth).insert(
+        openrouter.profile_key,
         serde_json::json!({
-            "provider": "openrouter",
+            "provider": openrouter.openclaw_provider,
             "mode": "api_key",
             "key": api_key.trim()
         }),
     );
-    let auth_content = serde_json::to_string_pretty(&auth)
-        .map_err(|e| format!("Failed to serialize auth profiles: {}", e))?;
-    std::fs::write(&auth_path, auth_content)
-        .map_err(|e| format!("Failed to write auth profiles: {}", e))?;
 
-    Ok(())
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
 }
 
 #[tauri::command]
-pub async fn openclaw_gateway_restart(app: AppHandle) -> Result<(), String> {
-    spawn_and_stream(&app, "openclaw", &["gateway", "restart"]).await
+pub async fn openclaw_gateway_restart(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+) -> Result<(), String> {
+    let _claim = operations::claim(&app, "Restarting the gateway", &[ExclusionGroup::Gateway])?;
+    gateway::restart(&app, &settings.paths()).await?;
+    app.state::<GatewaySupervisor>().check_now();
+    Ok(())
 }
 
 // --- API Keys (OpenClaw cloud providers) ---
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApiKeyStatus {
+    /// Registry id (see `providers::registry`).
+    pub provider: String,
+    pub label: String,
+    pub profile_key: String,
+    pub custom: bool,
+    pub get_key_url: Option<String>,
     pub configured: bool,
     pub masked: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ApiKeysStatus {
-    pub gemini: ApiKeyStatus,
-    pub openai: ApiKeyStatus,
-    pub anthropic: ApiKeyStatus,
-}
-
-/// Map our provider id to OpenClaw profile key (e.g. "gemini" -> "google:default").
-fn openclaw_profile_key(provider: &str) -> Option<&'static str> {
-    match provider.to_lowercase().as_str() {
-        "gemini" => Some("google:default"),
-        "openai" => Some("openai:default"),
-        "anthropic" => Some("anthropic:default"),
-        _ => None,
-    }
-}
-
-/// OpenClaw provider name for auth profile (google, openai, anthropic).
-fn openclaw_provider_name(provider: &str) -> Option<&'static str> {
-    match provider.to_lowercase().as_str() {
-        "gemini" => Some("google"),
-        "openai" => Some("openai"),
-        "anthropic" => Some("anthropic"),
-        _ => None,
-    }
-}
-
-fn mask_key(key: &str) -> String {
-    if key.len() <= 4 {
-        return "••••".to_string();
-    }
-    format!("••••••••{}", &key[key.len().saturating_sub(4)..])
-}
-
 #[tauri::command]
-pub fn openclaw_get_api_keys() -> Result<ApiKeysStatus, String> {
-    let auth_path = openclaw_agent_auth_path();
-    let mut gemini = ApiKeyStatus {
-        configured: false,
-        masked: None,
-    };
-    let mut openai = ApiKeyStatus {
-        configured: false,
-        masked: None,
-    };
-    let mut anthropic = ApiKeyStatus {
-        configured: false,
-        masked: None,
+pub fn openclaw_get_api_keys(
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<ApiKeyStatus>, String> {
+    let auth = openclaw_config::load_auth_profiles(&settings.paths().auth_profiles_path())?;
+    let key_of = |value: &serde_json::Value| {
+        value
+            .get("key")
+            .and_then(|v| v.as_str())
+            .filter(|k| !k.is_empty())
+            .map(mask_key)
     };
 
-    if auth_path.exists() {
-        let content = std::fs::read_to_string(&auth_path)
-            .map_err(|e| format!("Failed to read auth profiles: {}", e))?;
-        let auth: serde_json::Value =
-            serde_json::from_str(&content).unwrap_or(serde_json::json!({}));
-        let obj = auth.as_object().ok_or("Auth profiles must be a JSON object")?;
-
-        for (profile_key, value) in obj {
-            let key_str = value
-                .get("key")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            if key_str.is_empty() {
-                continue;
+    Ok(providers::registry(&settings.get())
+        .into_iter()
+        .map(|provider| {
+            let masked = openclaw_config::auth_profile_map(&auth)
+                .get(&provider.profile_key)
+                .and_then(key_of);
+            ApiKeyStatus {
+                configured: masked.is_some(),
+                masked,
+                provider: provider.id,
+                label: provider.label,
+                profile_key: provider.profile_key,
+                custom: provider.custom,
+                get_key_url: provider.get_key_url,
             }
-            let masked = mask_key(key_str);
-            match profile_key.as_str() {
-                "google:default" => {
-                    gemini.configured = true;
-                    gemini.masked = Some(masked);
-                }
-                "openai:default" => {
-                    openai.configured = true;
-                    openai.masked = Some(masked);
-                }
-                "anthropic:default" => {
-                    anthropic.configured = true;
-                    anthropic.masked = Some(masked);
-                }
-                _ => {}
-            }
-        }
-    }
-
-    Ok(ApiKeysStatus {
-        gemini,
-        openai,
-        anthropic,
-    })
+        })
+        .collect())
 }
 
+/// Saves a vault key as the provider's OpenClaw auth profile. The key is checked against
+/// the provider first; `force` saves it even if the check fails.
 #[tauri::command]
-pub fn openclaw_set_api_key(provider: String, key: String) -> Result<(), String> {
-    let profile_key = openclaw_profile_key(&provider)
-        .ok_or_else(|| format!("Unknown provider: {}", provider))?;
-    let openclaw_provider = openclaw_provider_name(&provider)
-        .ok_or_else(|| format!("Unknown provider: {}", provider))?;
-
-    let auth_path = openclaw_agent_auth_path();
-    if let Some(parent) = auth_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create agent dir: {}", e))?;
+#[allow(clippy::too_many_arguments)]
+pub async fn openclaw_set_api_key(
+    app: AppHandle,
+    provider: String,
+    key_ref: String,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let key = vault.resolve(&key_ref)?;
+    let provider = providers::find_provider(&settings.get(), &provider)?;
+    if !force.unwrap_or(false) {
+        providers::check_key(&provider, &key).await.into_result()?;
     }
 
-    let mut auth: serde_json::Value = if auth_path.exists() {
-        let content = std::fs::read_to_string(&auth_path)
-            .map_err(|e| format!("Failed to read auth profiles: {}", e))?;
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-
-    let auth_obj = auth
-        .as_object_mut()
-        .ok_or("Auth profiles must be a JSON object")?;
-    auth_obj.insert(
-        profile_key.to_string(),
+    let mut change = ConfigChange::begin(&settings.paths());
+    if provider.custom {
+        provider.register(change.config_mut()?);
+    }
+    openclaw_config::auth_profile_map_mut(change.auth_mut()?).insert(
+        provider.profile_key,
         serde_json::json!({
-            "provider": openclaw_provider,
+            "provider": provider.openclaw_provider,
             "mode": "api_key",
             "key": key.trim()
         }),
     );
-
-    let content = serde_json::to_string_pretty(&auth)
-        .map_err(|e| format!("Failed to serialize auth profiles: {}", e))?;
-    std::fs::write(&auth_path, content)
-        .map_err(|e| format!("Failed to write auth profiles: {}", e))?;
-    Ok(())
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
 }
 
+/// Removes every key OpenClaw has for the provider, matching `openclaw_get_api_keys`, which
+/// reports the provider as configured while any of them is left.
 #[tauri::command]
-pub fn openclaw_remove_api_key(provider: String) -> Result<(), String> {
-    let profile_key = openclaw_profile_key(&provider)
-        .ok_or_else(|| format!("Unknown provider: {}", provider))?;
-
-    let auth_path = openclaw_agent_auth_path();
-    if !auth_path.exists() {
-        return Ok(());
+pub fn openclaw_remove_api_key(
+    app: AppHandle,
+    provider: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let provider = providers::find_provider(&settings.get(), &provider)?;
+
+    let mut change = ConfigChange::begin(&settings.paths());
+    let auth = change.auth_mut()?;
+    let ids: Vec<String> = openclaw_config::provider_profiles(auth, &provider.openclaw_provider)
+        .map(|(id, _)| id.clone())
+        .collect();
+    for id in &ids {
+        openclaw_config::remove_auth_profile(auth, id);
     }
-
-    let content = std::fs::read_to_string(&auth_path)
-        .map_err(|e| format!("Failed to read auth profiles: {}", e))?;
-    let mut auth: serde_json::Value =
-        serde_json::from_str(&content).unwrap_or(serde_json::json!({}));
-    let auth_obj = auth
-        .as_object_mut()
-        .ok_or("Auth profiles must be a JSON object")?;
-    auth_obj.remove(profile_key);
-
-    let content = serde_json::to_string_pretty(&auth)
-        .map_err(|e| format!("Failed to serialize auth profiles: {}", e))?;
-    std::fs::write(&auth_path, content)
-        .map_err(|e| format!("Failed to write auth profiles: {}", e))?;
-    Ok(())
+    provider_keys::save_order(&mut change, &provider.openclaw_provider, Vec::new())?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
 }
@@ -0,0 +1,910 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use super::config_files;
+use super::config_preview::{apply_or_preview, ConfigChange, ConfigPreview, PendingPreviews};
+pub use super::openclaw_paths::is_valid_agent_id;
+use super::openclaw_paths::{OpenClawPaths, DEFAULT_AGENT_ID};
+use super::settings::SettingsState;
+
+pub type Extra = serde_json::Map<String, serde_json::Value>;
+
+/// Root-level keys older OpenClaw versions accepted but current ones reject.
+pub const LEGACY_ROOT_KEYS: &[&str] = &["provider", "model", "contextWindow", "maxTokens"];
+
+/// Typed model of `~/.openclaw/openclaw.json`. Only the sections this app edits are
+/// modelled; every struct keeps a flattened `extra` map so keys we don't know about
+/// (newer OpenClaw options, plugin settings) survive a load/save round trip. Known keys
+/// keep their shape too: lists and maps are `Option`s so an explicit `[]` stays, and a
+/// shorthand model ref is written back as a string.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpenClawConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<GatewayConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models: Option<ModelsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agents: Option<AgentsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<ChannelsConfig>,
+    /// Routes inbound messages to agents by channel/account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bindings: Option<Vec<AgentBinding>>,
+    /// `Some(None)` is an explicit `"auth": null`.
+    #[serde(skip_serializing_if = "Option::is_none", deserialize_with = "nullable")]
+    pub auth: Option<Option<AuthConfig>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuthConfig {
+    /// Auth profile ids per provider, in the order OpenClaw tries them on failover.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GatewayConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// An explicit `"auth": null` is kept as `Some(Value::Null)`.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "keep_null"
+    )]
+    pub auth: Option<serde_json::Value>,
+    /// Used when `mode` is "remote": the gateway the CLI and this app talk to instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteGatewayConfig>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteGatewayConfig {
+    /// WebSocket URL, e.g. "ws://beelink.local:18789".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// SSH destination of the gateway host, e.g. "me@beelink.local".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh_target: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub providers: Option<BTreeMap<String, ProviderConfig>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<ModelDefinition>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+impl ProviderConfig {
+    pub fn model_list(&self) -> &[ModelDefinition] {
+        self.models.as_deref().unwrap_or(&[])
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelDefinition {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<ModelCost>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelCost {
+    /// USD per million tokens. Kept as written, so `3` doesn't come back as `3.0`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<serde_json::Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<serde_json::Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<serde_json::Number>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<AgentDefaults>,
+    /// Explicit agents. When empty OpenClaw runs a single implicit "main" agent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list: Option<Vec<AgentEntry>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentEntry {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_dir: Option<String>,
+    /// Overrides `agents.defaults.model` for this agent.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "write_model_selection",
+        deserialize_with = "model_selection"
+    )]
+    pub model: Option<AgentModelConfig>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentBinding {
+    pub agent_id: String,
+    #[serde(rename = "match")]
+    pub match_rule: BindingMatch,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BindingMatch {
+    pub channel: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentDefaults {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "write_model_selection",
+        deserialize_with = "model_selection"
+    )]
+    pub model: Option<AgentModelConfig>,
+    /// Used instead of `model` for messages with images when that model can't read them.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "write_model_selection",
+        deserialize_with = "model_selection"
+    )]
+    pub image_model: Option<AgentModelConfig>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentModelConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallbacks: Option<Vec<String>>,
+    /// Read from the shorthand `"model": "provider/id"`, and written back that way while
+    /// `primary` is all it holds.
+    #[serde(skip)]
+    pub shorthand: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+impl AgentModelConfig {
+    pub fn fallback_list(&self) -> &[String] {
+        self.fallbacks.as_deref().unwrap_or(&[])
+    }
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing key (`None`, via
+/// `#[serde(default)]`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn keep_null<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    serde_json::Value::deserialize(deserializer).map(Some)
+}
+
+fn write_model_selection<S>(
+    model: &Option<AgentModelConfig>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match model {
+        Some(AgentModelConfig {
+            primary: Some(primary),
+            fallbacks: None,
+            shorthand: true,
+            extra,
+        }) if extra.is_empty() => serializer.serialize_str(primary),
+        _ => model.serialize(serializer),
+    }
+}
+
+/// OpenClaw also accepts the shorthand `"model": "provider/id"`; read it as `primary`.
+fn model_selection<'de, D>(deserializer: D) -> Result<Option<AgentModelConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Selection {
+        Ref(String),
+        Detailed(AgentModelConfig),
+    }
+    Ok(match Option::<Selection>::deserialize(deserializer)? {
+        Some(Selection::Ref(primary)) => Some(AgentModelConfig {
+            primary: Some(primary),
+            shorthand: true,
+            ..Default::default()
+        }),
+        Some(Selection::Detailed(model)) => Some(model),
+        None => None,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub whatsapp: Option<WhatsAppChannelConfig>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WhatsAppChannelConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_from: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub severity: String, // "error" or "warning"
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            severity: "error".to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            severity: "warning".to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl OpenClawConfig {
+    pub fn gateway_mut(&mut self) -> &mut GatewayConfig {
+        self.gateway.get_or_insert_with(Default::default)
+    }
+
+    pub fn models_mut(&mut self) -> &mut ModelsConfig {
+        self.models.get_or_insert_with(Default::default)
+    }
+
+    pub fn providers_mut(&mut self) -> &mut BTreeMap<String, ProviderConfig> {
+        self.models_mut()
+            .providers
+            .get_or_insert_with(Default::default)
+    }
+
+    pub fn agent_defaults_mut(&mut self) -> &mut AgentDefaults {
+        self.agents
+            .get_or_insert_with(Default::default)
+            .defaults
+            .get_or_insert_with(Default::default)
+    }
+
+    /// Explicit agents, or empty if the config relies on the implicit "main" agent.
+    pub fn agent_list(&self) -> &[AgentEntry] {
+        self.agents
+            .as_ref()
+            .and_then(|a| a.list.as_deref())
+            .unwrap_or(&[])
+    }
+
+    /// The agent list, made explicit first: an empty list becomes `[main (default)]` so
+    /// adding another agent doesn't change which one handles unbound messages.
+    pub fn agent_list_mut(&mut self) -> &mut Vec<AgentEntry> {
+        let list = self
+            .agents
+            .get_or_insert_with(Default::default)
+            .list
+            .get_or_insert_with(Default::default);
+        if list.is_empty() {
+            list.push(AgentEntry {
+                id: DEFAULT_AGENT_ID.to_string(),
+                default: Some(true),
+                ..Default::default()
+            });
+        }
+        list
+    }
+
+    pub fn agent_mut(&mut self, id: &str) -> Option<&mut AgentEntry> {
+        self.agent_list_mut().iter_mut().find(|a| a.id == id)
+    }
+
+    /// Id of the agent that handles messages no binding matches.
+    pub fn default_agent_id(&self) -> String {
+        let list = self.agent_list();
+        list.iter()
+            .find(|a| a.default == Some(true))
+            .or(list.first())
+            .map(|a| a.id.clone())
+            .unwrap_or_else(|| DEFAULT_AGENT_ID.to_string())
+    }
+
+    pub fn bindings(&self) -> &[AgentBinding] {
+        self.bindings.as_deref().unwrap_or(&[])
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut Vec<AgentBinding> {
+        self.bindings.get_or_insert_with(Default::default)
+    }
+
+    pub fn auth(&self) -> Option<&AuthConfig> {
+        self.auth.as_ref()?.as_ref()
+    }
+
+    pub fn auth_order_mut(&mut self) -> &mut BTreeMap<String, Vec<String>> {
+        self.auth
+            .get_or_insert_with(Default::default)
+            .get_or_insert_with(Default::default)
+            .order
+            .get_or_insert_with(Default::default)
+    }
+
+    pub fn whatsapp_mut(&mut self) -> &mut WhatsAppChannelConfig {
+        self.channels
+            .get_or_insert_with(Default::default)
+            .whatsapp
+            .get_or_insert_with(Default::default)
+    }
+
+    /// Drops root-level keys that current OpenClaw versions refuse to start with.
+    pub fn remove_legacy_keys(&mut self) {
+        for key in LEGACY_ROOT_KEYS {
+            self.extra.remove(*key);
+        }
+    }
+
+    /// Checks the parts of the config this app understands. Errors mean OpenClaw will
+    /// refuse the file; warnings are likely mistakes that still load.
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        for key in LEGACY_ROOT_KEYS {
+            if self.extra.contains_key(*key) {
+                issues.push(ConfigIssue::error(
+                    key,
+                    "Legacy root-level key is no longer accepted by OpenClaw",
+                ));
+            }
+        }
+
+        if let Some(mode) = self.gateway.as_ref().and_then(|g| g.mode.as_deref()) {
+            if mode != "local" && mode != "remote" {
+                issues.push(ConfigIssue::error(
+                    "gateway.mode",
+                    format!("Unknown gateway mode \"{}\" (expected local or remote)", mode),
+                ));
+            }
+            let remote_url = self
+                .gateway
+                .as_ref()
+                .and_then(|g| g.remote.as_ref())
+                .and_then(|r| r.url.as_deref())
+                .filter(|u| !u.trim().is_empty());
+            if mode == "remote" && remote_url.is_none() {
+                issues.push(ConfigIssue::error(
+                    "gateway.remote.url",
+                    "Remote gateway mode needs gateway.remote.url",
+                ));
+            }
+        }
+
+        if let Some(models) = &self.models {
+            for (name, provider) in models.providers.iter().flatten() {
+                let path = format!("models.providers.{}", name);
+                if provider.base_url.as_deref().unwrap_or("").is_empty() {
+                    issues.push(ConfigIssue::error(
+                        &format!("{}.baseUrl", path),
+                        "Provider has no baseUrl",
+                    ));
+                }
+                let mut seen = std::collections::HashSet::new();
+                for (i, model) in provider.model_list().iter().enumerate() {
+                    if model.id.trim().is_empty() {
+                        issues.push(ConfigIssue::error(
+                            &format!("{}.models[{}].id", path, i),
+                            "Model id is empty",
+                        ));
+                    } else if !seen.insert(model.id.as_str()) {
+                        issues.push(ConfigIssue::warning(
+                            &format!("{}.models[{}].id", path, i),
+                            format!("Model \"{}\" is defined more than once", model.id),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(defaults) = self.agents.as_ref().and_then(|a| a.defaults.as_ref()) {
+            if let Some(model) = &defaults.model {
+                self.check_selection("agents.defaults.model", model, &mut issues);
+            }
+            if let Some(model) = &defaults.image_model {
+                self.check_selection("agents.defaults.imageModel", model, &mut issues);
+            }
+        }
+
+        let agents = self.agent_list();
+        let mut agent_ids = std::collections::HashSet::new();
+        for (i, agent) in agents.iter().enumerate() {
+            let path = format!("agents.list[{}]", i);
+            if !is_valid_agent_id(&agent.id) {
+                issues.push(ConfigIssue::error(
+                    &format!("{}.id", path),
+                    format!(
+                        "Agent id \"{}\" must be lowercase letters, digits, - or _",
+                        agent.id
+                    ),
+                ));
+            } else if !agent_ids.insert(agent.id.as_str()) {
+                issues.push(ConfigIssue::error(
+                    &format!("{}.id", path),
+                    format!("Agent \"{}\" is defined more than once", agent.id),
+                ));
+            }
+            if let Some(model) = &agent.model {
+                self.check_selection(&format!("{}.model", path), model, &mut issues);
+            }
+        }
+        if agents.iter().filter(|a| a.default == Some(true)).count() > 1 {
+            issues.push(ConfigIssue::warning(
+                "agents.list",
+                "More than one agent is marked default; the first one wins",
+            ));
+        }
+
+        for (i, binding) in self.bindings().iter().enumerate() {
+            let path = format!("bindings[{}]", i);
+            let known = if agents.is_empty() {
+                binding.agent_id == DEFAULT_AGENT_ID
+            } else {
+                agent_ids.contains(binding.agent_id.as_str())
+            };
+            if !known {
+                issues.push(ConfigIssue::error(
+                    &format!("{}.agentId", path),
+                    format!("Binding refers to unknown agent \"{}\"", binding.agent_id),
+                ));
+            }
+            if binding.match_rule.channel.trim().is_empty() {
+                issues.push(ConfigIssue::error(
+                    &format!("{}.match.channel", path),
+                    "Binding has no channel",
+                ));
+            }
+        }
+
+        if let Some(whatsapp) = self.channels.as_ref().and_then(|c| c.whatsapp.as_ref()) {
+            if let Some(policy) = whatsapp.dm_policy.as_deref() {
+                if !["pairing", "allowlist", "open", "disabled"].contains(&policy) {
+                    issues.push(ConfigIssue::error(
+                        "channels.whatsapp.dmPolicy",
+                        format!("Unknown dmPolicy \"{}\"", policy),
+                    ));
+                }
+                if policy == "allowlist"
+                    && whatsapp.allow_from.as_ref().is_none_or(|a| a.is_empty())
+                {
+                    issues.push(ConfigIssue::warning(
+                        "channels.whatsapp.allowFrom",
+                        "dmPolicy is allowlist but allowFrom is empty, so every DM is ignored",
+                    ));
+                }
+            }
+        }
+
+        issues
+    }
+
+    fn check_selection(&self, path: &str, model: &AgentModelConfig, issues: &mut Vec<ConfigIssue>) {
+        let refs = model
+            .primary
+            .iter()
+            .map(|r| (format!("{}.primary", path), r))
+            .chain(
+                model
+                    .fallback_list()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| (format!("{}.fallbacks[{}]", path, i), r)),
+            );
+        for (path, model_ref) in refs {
+            if let Some(issue) = self.check_model_ref(&path, model_ref) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    /// A model ref is `provider/model-id`. When the provider is one we define explicitly,
+    /// the model must be listed under it.
+    fn check_model_ref(&self, path: &str, model_ref: &str) -> Option<ConfigIssue> {
+        let Some((provider, model_id)) = model_ref.split_once('/') else {
+            return Some(ConfigIssue::error(
+                path,
+                format!("Model \"{}\" must be written as provider/model", model_ref),
+            ));
+        };
+        let defined = self.models.as_ref()?.providers.as_ref()?.get(provider)?;
+        let listed = defined.model_list();
+        if !listed.is_empty() && !listed.iter().any(|m| m.id == model_id) {
+            return Some(ConfigIssue::warning(
+                path,
+                format!(
+                    "Model \"{}\" is not defined under models.providers.{}",
+                    model_id, provider
+                ),
+            ));
+        }
+        None
+    }
+}
+
+pub fn has_errors(issues: &[ConfigIssue]) -> bool {
+    issues.iter().any(|i| i.severity == "error")
+}
+
+/// Loads the config, treating a missing file as empty. Unlike a fallback to `{}`, a file
+/// that exists but can't be parsed is an error so it never gets overwritten.
+pub fn load_config(paths: &OpenClawPaths) -> Result<OpenClawConfig, String> {
+    let path = paths.config_path();
+    if !path.exists() {
+        return Ok(OpenClawConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {}", e))?;
+    parse_config(&content)
+}
+
+pub fn parse_config(content: &str) -> Result<OpenClawConfig, String> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("openclaw.json is not valid JSON: {}", e))?;
+    if !value.is_object() {
+        return Err("openclaw.json must contain a JSON object".to_string());
+    }
+    serde_json::from_value(value).map_err(|e| format!("openclaw.json has an invalid structure: {}", e))
+}
+
+/// Writes the config atomically, keeping a backup of the previous version. If the written
+/// file doesn't read back as the same config, the previous version is restored.
+pub fn save_config(paths: &OpenClawPaths, config: &OpenClawConfig) -> Result<(), String> {
+    let value = serde_json::to_value(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    config_files::write_json_with_rollback(paths, &paths.config_path(), &value)
+}
+
+/// Loads the config, applies `edit`, and saves the result.
+pub fn update_config<F>(paths: &OpenClawPaths, edit: F) -> Result<OpenClawConfig, String>
+where
+    F: FnOnce(&mut OpenClawConfig) -> Result<(), String>,
+{
+    let mut config = load_config(paths)?;
+    edit(&mut config)?;
+    save_config(paths, &config)?;
+    Ok(config)
+}
+
+// --- Auth profiles ---
+
+pub type AuthProfiles = serde_json::Map<String, serde_json::Value>;
+
+/// The map of profile id -> credential inside auth-profiles.json. Newer OpenClaw versions
+/// nest it under `profiles` next to `usageStats`; older files are the map itself.
+pub fn auth_profile_map(auth: &AuthProfiles) -> &AuthProfiles {
+    match auth.get("profiles") {
+        Some(serde_json::Value::Object(profiles)) => profiles,
+        _ => auth,
+    }
+}
+
+pub fn auth_profile_map_mut(auth: &mut AuthProfiles) -> &mut AuthProfiles {
+    if matches!(auth.get("profiles"), Some(serde_json::Value::Object(_))) {
+        return auth
+            .get_mut("profiles")
+            .and_then(|p| p.as_object_mut())
+            .expect("profiles is an object");
+    }
+    auth
+}
+
+/// Removes a profile and OpenClaw's bookkeeping about it in auth-profiles.json (`order`,
+/// `lastGood`, `usageStats`), so nothing is left pointing at a key that's gone. Returns
+/// whether the profile existed.
+pub fn remove_auth_profile(auth: &mut AuthProfiles, profile_id: &str) -> bool {
+    use serde_json::Value;
+
+    let removed = auth_profile_map_mut(auth).remove(profile_id).is_some();
+    if let Some(Value::Object(stats)) = auth.get_mut("usageStats") {
+        stats.remove(profile_id);
+    }
+    if let Some(Value::Object(order)) = auth.get_mut("order") {
+        for ids in order.values_mut() {
+            if let Value::Array(ids) = ids {
+                ids.retain(|id| id != profile_id);
+            }
+        }
+        order.retain(|_, ids| ids.as_array().is_none_or(|ids| !ids.is_empty()));
+    }
+    if let Some(Value::Object(last_good)) = auth.get_mut("lastGood") {
+        last_good.retain(|_, id| id != profile_id);
+    }
+    removed
+}
+
+/// Profiles (id, credential) for an OpenClaw provider, in file order.
+pub fn provider_profiles<'a>(
+    auth: &'a AuthProfiles,
+    provider: &'a str,
+) -> impl Iterator<Item = (&'a String, &'a serde_json::Value)> {
+    auth_profile_map(auth).iter().filter(move |(id, value)| {
+        is_auth_profile(id, value)
+            && (value.get("provider").and_then(|p| p.as_str()) == Some(provider)
+                || id.split_once(':').map(|(p, _)| p) == Some(provider))
+    })
+}
+
+/// Tells credentials ("provider:name" entries) apart from bookkeeping keys such as
+/// `usageStats` in flat auth-profiles.json files.
+pub fn is_auth_profile(id: &str, value: &serde_json::Value) -> bool {
+    value.is_object() && (id.contains(':') || value.get("provider").is_some())
+}
+
+/// OpenClaw's per-profile usage bookkeeping (`usageStats`), if it has recorded any.
+pub fn auth_usage_stats<'a>(auth: &'a AuthProfiles, profile_id: &str) -> Option<&'a serde_json::Value> {
+    auth.get("usageStats")?.get(profile_id)
+}
+
+/// Loads auth profiles, treating a missing file as empty. A file that can't be parsed is an
+/// error rather than `{}`, so saving never wipes keys we couldn't read.
+pub fn load_auth_profiles(path: &Path) -> Result<AuthProfiles, String> {
+    if !path.exists() {
+        return Ok(AuthProfiles::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read auth profiles: {}", e))?;
+    match serde_json::from_str(&content)
+        .map_err(|e| format!("auth-profiles.json is not valid JSON: {}", e))?
+    {
+        serde_json::Value::Object(profiles) => Ok(profiles),
+        _ => Err("Auth profiles must be a JSON object".to_string()),
+    }
+}
+
+/// Applies an RFC 7396 JSON merge patch: objects merge recursively, `null` deletes.
+pub fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch_obj) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::json!({});
+    }
+    if let serde_json::Value::Object(target_obj) = target {
+        for (key, value) in patch_obj {
+            if value.is_null() {
+                target_obj.remove(key);
+            } else {
+                merge_patch(
+                    target_obj.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenClawConfigSnapshot {
+    pub path: String,
+    pub exists: bool,
+    pub config: serde_json::Value,
+    pub issues: Vec<ConfigIssue>,
+    /// Set for dry runs, in which case `config` is what would be written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<ConfigPreview>,
+}
+
+fn snapshot(paths: &OpenClawPaths, config: &OpenClawConfig) -> Result<OpenClawConfigSnapshot, String> {
+    let path = paths.config_path();
+    Ok(OpenClawConfigSnapshot {
+        path: path.to_string_lossy().to_string(),
+        exists: path.exists(),
+        config: serde_json::to_value(config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?,
+        issues: config.validate(),
+        preview: None,
+    })
+}
+
+pub fn current_snapshot(paths: &OpenClawPaths) -> Result<OpenClawConfigSnapshot, String> {
+    snapshot(paths, &load_config(paths)?)
+}
+
+#[tauri::command]
+pub fn openclaw_config_get(
+    settings: State<'_, SettingsState>,
+) -> Result<OpenClawConfigSnapshot, String> {
+    current_snapshot(&settings.paths())
+}
+
+/// Applies a JSON merge patch to openclaw.json. The result must parse and pass validation
+/// without errors, otherwise nothing is written. With `dry_run`, nothing is written and the
+/// snapshot carries a preview of the change.
+#[tauri::command]
+pub fn openclaw_config_update(
+    app: AppHandle,
+    patch: serde_json::Value,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<OpenClawConfigSnapshot, String> {
+    let paths = settings.paths();
+    let mut change = ConfigChange::begin(&paths);
+    let mut value = serde_json::to_value(&*change.config_mut()?)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    merge_patch(&mut value, &patch);
+    let next: OpenClawConfig = serde_json::from_value(value)
+        .map_err(|e| format!("Patched config has an invalid structure: {}", e))?;
+
+    let issues = next.validate();
+    if has_errors(&issues) {
+        let messages: Vec<String> = issues
+            .iter()
+            .filter(|i| i.severity == "error")
+            .map(|i| format!("{}: {}", i.path, i.message))
+            .collect();
+        return Err(format!("Config not saved: {}", messages.join("; ")));
+    }
+
+    *change.config_mut()? = next.clone();
+    let preview = apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)?;
+    Ok(OpenClawConfigSnapshot {
+        preview,
+        ..snapshot(&paths, &next)?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &[(&str, &str)] = &[
+        (
+            "openclaw-onboarded.json",
+            include_str!("../../tests/fixtures/openclaw-onboarded.json"),
+        ),
+        (
+            "openclaw-multi-agent.json",
+            include_str!("../../tests/fixtures/openclaw-multi-agent.json"),
+        ),
+    ];
+
+    #[test]
+    fn round_trip_keeps_the_file_as_written() {
+        for (name, content) in FIXTURES {
+            let original: serde_json::Value = serde_json::from_str(content).unwrap();
+            let config = parse_config(content).unwrap();
+            let written = serde_json::to_value(&config).unwrap();
+            assert_eq!(
+                written, original,
+                "{} changed on a load/save round trip",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn shorthand_model_becomes_an_object_once_it_has_fallbacks() {
+        let mut config = parse_config(FIXTURES[0].1).unwrap();
+        let model = config.agent_defaults_mut().model.as_mut().unwrap();
+        model.primary = Some("openrouter/anthropic/claude-sonnet-4.5".to_string());
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            written["agents"]["defaults"]["model"],
+            "openrouter/anthropic/claude-sonnet-4.5"
+        );
+
+        let model = config.agent_defaults_mut().model.as_mut().unwrap();
+        model.fallbacks = Some(vec!["openrouter/google/gemini-2.5-flash".to_string()]);
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            written["agents"]["defaults"]["model"],
+            serde_json::json!({
+                "primary": "openrouter/anthropic/claude-sonnet-4.5",
+                "fallbacks": ["openrouter/google/gemini-2.5-flash"]
+            })
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+use super::settings::{SettingsPatch, SettingsState};
+use super::vault::Vault;
+
+/// OpenClaw's own override for its state directory (default `~/.openclaw`).
+pub const STATE_DIR_ENV: &str = "OPENCLAW_STATE_DIR";
+/// OpenClaw's override for the gateway port.
+const GATEWAY_PORT_ENV: &str = "OPENCLAW_GATEWAY_PORT";
+const DEFAULT_GATEWAY_PORT: u16 = 18789;
+pub const DEFAULT_AGENT_ID: &str = "main";
+/// Name shown for the implicit profile used when none is selected.
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// A named OpenClaw installation. Unset fields fall back to OpenClaw's defaults, so a
+/// profile only needs to record what differs.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpenClawProfile {
+    pub name: String,
+    /// State directory, e.g. "~/.openclaw-work".
+    pub home: Option<String>,
+    /// Gateway base URL, e.g. "http://127.0.0.1:19789".
+    pub gateway_url: Option<String>,
+    /// Agent whose auth profiles and model this app manages.
+    pub agent_id: Option<String>,
+}
+
+/// Where an OpenClaw installation keeps its files and where its gateway listens.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenClawPaths {
+    pub profile: String,
+    pub home: PathBuf,
+    pub gateway_url: String,
+    pub agent_id: String,
+    /// Whether `home` differs from what the `openclaw` CLI would pick on its own, in which
+    /// case spawned commands need `OPENCLAW_STATE_DIR` set.
+    #[serde(skip)]
+    pub custom_home: bool,
+}
+
+impl Default for OpenClawPaths {
+    fn default() -> Self {
+        Self::resolve(None)
+    }
+}
+
+impl OpenClawPaths {
+    /// Resolves a profile against the environment and OpenClaw's defaults.
+    pub fn resolve(profile: Option<&OpenClawProfile>) -> Self {
+        let env_home = std::env::var(STATE_DIR_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| expand_home(&v));
+        let default_home = env_home.unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("~"))
+                .join(".openclaw")
+        });
+        let home = profile
+            .and_then(|p| p.home.as_deref())
+            .filter(|h| !h.trim().is_empty())
+            .map(expand_home)
+            .unwrap_or_else(|| default_home.clone());
+
+        let port = std::env::var(GATEWAY_PORT_ENV)
+            .ok()
+            .and_then(|p| p.trim().parse::<u16>().ok())
+            .unwrap_or(DEFAULT_GATEWAY_PORT);
+        let gateway_url = profile
+            .and_then(|p| p.gateway_url.as_deref())
+            .filter(|u| !u.trim().is_empty())
+            .map(|u| u.trim().trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}", port));
+
+        // An id that isn't a plain directory name (e.g. from a hand-edited store) is ignored.
+        let agent_id = profile
+            .and_then(|p| p.agent_id.as_deref())
+            .map(str::trim)
+            .filter(|a| is_valid_agent_id(a))
+            .unwrap_or(DEFAULT_AGENT_ID)
+            .to_string();
+
+        Self {
+            profile: profile
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string()),
+            custom_home: home != default_home,
+            home,
+            gateway_url,
+            agent_id,
+        }
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.home.join("openclaw.json")
+    }
+
+    pub fn agent_dir(&self, agent_id: &str) -> PathBuf {
+        self.home.join("agents").join(agent_id).join("agent")
+    }
+
+    /// auth-profiles.json for the profile's agent, which holds the keys OpenClaw uses.
+    pub fn auth_profiles_path(&self) -> PathBuf {
+        self.agent_dir(&self.agent_id).join("auth-profiles.json")
+    }
+
+    pub fn backups_dir(&self) -> PathBuf {
+        self.home.join("backups")
+    }
+
+    /// Points a spawned `openclaw` command at this installation.
+    pub fn apply_env(&self, cmd: &mut tokio::process::Command) {
+        if self.custom_home {
+            cmd.env(STATE_DIR_ENV, &self.home);
+        }
+    }
+}
+
+/// Expands a leading "~" or "~/" to the user's home directory. Other users' homes
+/// ("~name") are left as they are.
+pub fn expand_home(path: &str) -> PathBuf {
+    let path = path.trim();
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with(['/', '\\']) => rest.trim_start_matches(['/', '\\']),
+        _ => return PathBuf::from(path),
+    };
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("~"))
+        .join(rest)
+}
+
+/// OpenClaw agent ids double as directory names under `agents/`.
+pub fn is_valid_agent_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Checks a profile before it is stored and returns it with its name and agent trimmed.
+pub fn validate_profile(profile: OpenClawProfile) -> Result<OpenClawProfile, String> {
+    let name = profile.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    if name == DEFAULT_PROFILE_NAME {
+        return Err(format!("\"{}\" is reserved for the built-in profile", name));
+    }
+    if let Some(url) = profile.gateway_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid gateway URL: {}", e))?;
+    }
+    let agent_id = profile
+        .agent_id
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+    if let Some(agent_id) = &agent_id {
+        if !is_valid_agent_id(agent_id) {
+            return Err(format!(
+                "Agent id \"{}\" must be lowercase letters, digits, - or _",
+                agent_id
+            ));
+        }
+    }
+    Ok(OpenClawProfile {
+        name,
+        agent_id,
+        ..profile
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenClawProfilesSnapshot {
+    /// Name of the active profile, or "default" when none is selected.
+    pub active: String,
+    pub active_paths: OpenClawPaths,
+    pub profiles: Vec<OpenClawProfile>,
+}
+
+fn profiles_snapshot(settings: &SettingsState) -> OpenClawProfilesSnapshot {
+    let paths = settings.paths();
+    OpenClawProfilesSnapshot {
+        active: paths.profile.clone(),
+        active_paths: paths,
+        profiles: settings.get().openclaw_profiles,
+    }
+}
+
+#[tauri::command]
+pub fn openclaw_profiles_list(
+    settings: State<'_, SettingsState>,
+) -> Result<OpenClawProfilesSnapshot, String> {
+    Ok(profiles_snapshot(&settings))
+}
+
+/// Adds a profile, or replaces the one with the same name.
+#[tauri::command]
+pub fn openclaw_profile_save(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    profile: OpenClawProfile,
+) -> Result<OpenClawProfilesSnapshot, String> {
+    let profile = validate_profile(profile)?;
+    let mut profiles = settings.get().openclaw_profiles;
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            openclaw_profiles: Some(profiles),
+            ..Default::default()
+        },
+    )?;
+    Ok(profiles_snapshot(&settings))
+}
+
+/// Deletes a profile. Deleting the active profile switches back to the default one. The
+/// profile's OpenClaw files are left alone.
+#[tauri::command]
+pub fn openclaw_profile_delete(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    name: String,
+) -> Result<OpenClawProfilesSnapshot, String> {
+    let current = settings.get();
+    let profiles: Vec<OpenClawProfile> = current
+        .openclaw_profiles
+        .into_iter()
+        .filter(|p| p.name != name)
+        .collect();
+    let was_active = current.active_openclaw_profile.as_deref() == Some(name.as_str());
+    settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            openclaw_profiles: Some(profiles),
+            active_openclaw_profile: was_active.then_some(None),
+            ..Default::default()
+        },
+    )?;
+    Ok(profiles_snapshot(&settings))
+}
+
+/// Makes `name` the active profile; `None` (or "default") selects the built-in one.
+#[tauri::command]
+pub fn openclaw_profile_switch(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    name: Option<String>,
+) -> Result<OpenClawProfilesSnapshot, String> {
+    let name = name.filter(|n| n != DEFAULT_PROFILE_NAME);
+    settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            active_openclaw_profile: Some(name),
+            ..Default::default()
+        },
+    )?;
+    Ok(profiles_snapshot(&settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(agent_id: &str) -> OpenClawProfile {
+        OpenClawProfile {
+            name: "work".to_string(),
+            home: Some("/tmp/openclaw-work".to_string()),
+            agent_id: Some(agent_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn expands_only_the_current_users_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home(" ~/.openclaw "), home.join(".openclaw"));
+        assert_eq!(expand_home("~\\.openclaw"), home.join(".openclaw"));
+        assert_eq!(expand_home("~foo/.openclaw"), PathBuf::from("~foo/.openclaw"));
+        assert_eq!(expand_home("/srv/openclaw"), PathBuf::from("/srv/openclaw"));
+    }
+
+    #[test]
+    fn resolves_agent_dirs_only_for_valid_ids() {
+        let paths = OpenClawPaths::resolve(Some(&profile(" ops ")));
+        assert_eq!(
+            paths.auth_profiles_path(),
+            PathBuf::from("/tmp/openclaw-work/agents/ops/agent/auth-profiles.json")
+        );
+        for bad in ["../../etc", "a/b", "", "Ops"] {
+            let paths = OpenClawPaths::resolve(Some(&profile(bad)));
+            assert_eq!(paths.agent_id, DEFAULT_AGENT_ID, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn validates_profiles_before_saving() {
+        let saved = validate_profile(OpenClawProfile {
+            name: " work ".to_string(),
+            agent_id: Some(" ops ".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(saved.name, "work");
+        assert_eq!(saved.agent_id.as_deref(), Some("ops"));
+
+        let blank = validate_profile(profile("  ")).unwrap();
+        assert_eq!(blank.agent_id, None);
+
+        assert!(validate_profile(profile("../../etc")).is_err());
+        assert!(validate_profile(OpenClawProfile::default()).is_err());
+        assert!(validate_profile(OpenClawProfile {
+            name: DEFAULT_PROFILE_NAME.to_string(),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(validate_profile(OpenClawProfile {
+            gateway_url: Some("not a url".to_string()),
+            ..profile("ops")
+        })
+        .is_err());
+    }
+}
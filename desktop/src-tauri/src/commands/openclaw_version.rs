@@ -0,0 +1,608 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use super::diagnostics::resolve_login_shell_path;
+use super::gateway::{self, GatewayTarget, ProbeResult};
+use super::installer::{self, InstallPhase, InstallProgress, InstallSource, PINNED_VERSION};
+use super::openclaw::{find_openclaw, spawn_and_stream};
+use super::openclaw_paths::OpenClawPaths;
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+
+/// How long `openclaw --version` may take (Node startup included).
+const VERSION_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a restarted gateway gets to answer after a version change.
+const GATEWAY_START_TIMEOUT: Duration = Duration::from_secs(30);
+const GATEWAY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?").unwrap());
+
+// --- Versions ---
+
+/// An OpenClaw release number, e.g. "2026.2.17" or "2026.2.17-beta.1".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub pre: Option<String>,
+}
+
+impl Version {
+    /// Finds the first version number in `text`, e.g. `openclaw --version` output.
+    pub fn parse(text: &str) -> Option<Version> {
+        let caps = VERSION_RE.captures(text)?;
+        Some(Version {
+            major: caps[1].parse().ok()?,
+            minor: caps[2].parse().ok()?,
+            patch: caps[3].parse().ok()?,
+            pre: caps.get(4).map(|m| m.as_str().to_string()),
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // A pre-release comes before its release.
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+/// Orders pre-release tags as semver does: dot-separated identifiers left to right,
+/// numeric ones by value ("beta.2" < "beta.10") and before alphanumeric ones; a tag that
+/// runs out first comes first.
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+// --- Compatibility ---
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Support {
+    /// Config handling has been tested against this release.
+    Supported,
+    /// Newer than any tested release; probably fine.
+    Untested,
+    /// This app writes config this release doesn't understand, or the reverse.
+    Unsupported,
+}
+
+struct Compat {
+    from: &'static str,
+    support: Support,
+    note: &'static str,
+}
+
+/// Sorted by `from`; a release takes the last entry at or below it. Releases newer than
+/// `PINNED_VERSION` are untested.
+const COMPATIBILITY: &[Compat] = &[
+    Compat {
+        from: "0.0.0",
+        support: Support::Unsupported,
+        note: "Releases before the OpenClaw rename use ~/.clawdbot and older config keys",
+    },
+    Compat {
+        from: "2026.1.29",
+        support: Support::Supported,
+        note: "",
+    },
+];
+
+/// SRI integrity of tested releases, from `npm view openclaw@<version> dist.integrity`. The
+/// registry's own checksum has to match, so a compromised registry or mirror can't swap the
+/// tarball. Until `PINNED_VERSION` is listed, the default install runs OpenClaw's script.
+pub const KNOWN_RELEASES: &[(&str, &str)] = &[];
+
+/// The checksum `KNOWN_RELEASES` lists for `version`.
+pub fn known_integrity(version: &str) -> Option<&'static str> {
+    KNOWN_RELEASES
+        .iter()
+        .find(|(v, _)| *v == version)
+        .map(|(_, integrity)| *integrity)
+}
+
+/// Oldest release this app supports.
+pub fn min_supported() -> &'static str {
+    COMPATIBILITY
+        .iter()
+        .find(|c| c.support == Support::Supported)
+        .map(|c| c.from)
+        .unwrap_or(PINNED_VERSION)
+}
+
+/// How well this app works with `version`, and why.
+pub fn support(version: &Version) -> (Support, String) {
+    let pinned = Version::parse(PINNED_VERSION).expect("PINNED_VERSION parses");
+    let entry = COMPATIBILITY
+        .iter()
+        .rfind(|c| Version::parse(c.from).is_some_and(|from| &from <= version));
+    match entry {
+        Some(c) if c.support != Support::Supported => (c.support, c.note.to_string()),
+        None => (
+            Support::Unsupported,
+            format!("Older than {}", min_supported()),
+        ),
+        Some(_) if version > &pinned => (
+            Support::Untested,
+            format!("Newer than the latest tested release ({})", PINNED_VERSION),
+        ),
+        Some(_) => (Support::Supported, String::new()),
+    }
+}
+
+// --- Detection ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenClawVersion {
+    pub installed: bool,
+    pub path: Option<String>,
+    /// Output of `openclaw --version`.
+    pub raw: Option<String>,
+    pub version: Option<String>,
+    pub support: Option<Support>,
+    pub message: String,
+    /// Release the installer installs by default.
+    pub pinned: String,
+    pub min_supported: String,
+}
+
+/// Runs `openclaw --version` and returns its trimmed output.
+async fn run_version(paths: &OpenClawPaths, program: &str) -> Result<String, String> {
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.arg("--version").kill_on_drop(true);
+    let base = if cfg!(target_os = "macos") {
+        resolve_login_shell_path()
+    } else {
+        None
+    };
+    cmd.env("PATH", installer::search_path(base));
+    paths.apply_env(&mut cmd);
+
+    let output = tokio::time::timeout(VERSION_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "openclaw --version timed out".to_string())?
+        .map_err(|e| format!("Failed to run openclaw --version: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "openclaw --version exited with code {:?}",
+            output.status.code()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The installed OpenClaw's version and how well this app supports it.
+pub async fn check(paths: &OpenClawPaths) -> OpenClawVersion {
+    let mut result = OpenClawVersion {
+        installed: false,
+        path: find_openclaw(),
+        raw: None,
+        version: None,
+        support: None,
+        message: "OpenClaw is not installed".to_string(),
+        pinned: PINNED_VERSION.to_string(),
+        min_supported: min_supported().to_string(),
+    };
+    let Some(path) = result.path.clone() else {
+        return result;
+    };
+    result.installed = true;
+
+    let raw = match run_version(paths, &path).await {
+        Ok(raw) => raw,
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    };
+    result.raw = Some(raw.clone());
+    let Some(version) = Version::parse(&raw) else {
+        result.message = format!("Couldn't read a version from \"{}\"", raw);
+        return result;
+    };
+
+    let (support, note) = support(&version);
+    result.message = match support {
+        Support::Supported => format!("OpenClaw {}", version),
+        _ => format!("OpenClaw {}: {}", version, note),
+    };
+    result.version = Some(version.to_string());
+    result.support = Some(support);
+    result
+}
+
+// --- Upgrade / downgrade ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersionChange {
+    pub previous: Option<String>,
+    pub current: String,
+}
+
+fn emit_progress(app: &AppHandle, phase: InstallPhase, message: String) {
+    let _ = app.emit(
+        "openclaw-install-progress",
+        InstallProgress {
+            phase,
+            message,
+            bytes: None,
+            total: None,
+        },
+    );
+}
+
+/// Waits for the local gateway to answer its health endpoint and, with `expected`, to
+/// report that version, which shows the gateway runs the newly installed release rather
+/// than one still shutting down. A gateway that reports no version is taken to run the
+/// release `openclaw --version` reports once it answers.
+pub async fn wait_for_gateway(
+    paths: &OpenClawPaths,
+    expected: Option<&Version>,
+) -> Result<(), String> {
+    let installed = match expected {
+        Some(_) => check(paths).await.version.and_then(|v| Version::parse(&v)),
+        None => None,
+    };
+    wait_for(
+        &gateway::target(paths),
+        expected,
+        installed.as_ref(),
+        GATEWAY_START_TIMEOUT,
+    )
+    .await
+}
+
+/// `installed` is what `openclaw --version` reports, the fallback when the gateway
+/// doesn't report its version.
+async fn wait_for(
+    target: &GatewayTarget,
+    expected: Option<&Version>,
+    installed: Option<&Version>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let probe = gateway::probe(&target.url, target.token.as_deref()).await;
+        let running = probe.version.as_deref().and_then(Version::parse);
+        let message = match (probe.result, expected) {
+            (ProbeResult::Running, None) => return Ok(()),
+            (ProbeResult::Running, Some(expected)) if running.as_ref() == Some(expected) => {
+                return Ok(())
+            }
+            (ProbeResult::Running, Some(expected)) => match (&probe.version, installed) {
+                (Some(version), _) => format!("it runs {}, not {}", version, expected),
+                (None, Some(installed)) if installed == expected => return Ok(()),
+                (None, Some(installed)) => format!(
+                    "it doesn't report a version and openclaw --version reports {}, not {}",
+                    installed, expected
+                ),
+                (None, None) => format!(
+                    "it doesn't report a version and openclaw --version doesn't either, \
+                    so {} is unconfirmed",
+                    expected
+                ),
+            },
+            _ => probe.message,
+        };
+        if tokio::time::Instant::now() >= deadline {
+            return Err(format!(
+                "The gateway didn't come back up within {}s: {}",
+                timeout.as_secs(),
+                message
+            ));
+        }
+        tokio::time::sleep(GATEWAY_POLL_INTERVAL).await;
+    }
+}
+
+/// Installs `version` and checks that it runs: `openclaw --version` must report it and,
+/// if the gateway was running, the gateway must come back up on it.
+#[allow(clippy::too_many_arguments)]
+async fn install_version(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    version: &Version,
+    registry: Option<String>,
+    integrity: Option<String>,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+    restart_gateway: bool,
+) -> Result<(), String> {
+    let source = InstallSource::Registry {
+        version: Some(version.to_string()),
+        registry,
+        integrity,
+    };
+    installer::install(app, paths, source, op_id, timeout_secs).await?;
+
+    let reported = check(paths).await;
+    if reported.version.as_deref() != Some(version.to_string().as_str()) {
+        return Err(format!(
+            "Installed {}, but {}",
+            version,
+            reported.raw.map_or(reported.message, |raw| format!(
+                "openclaw --version reports \"{}\"",
+                raw
+            ))
+        ));
+    }
+
+    if restart_gateway {
+        spawn_and_stream(app, paths, "openclaw", &["gateway", "start"]).await?;
+        wait_for_gateway(paths, Some(version)).await?;
+    }
+    Ok(())
+}
+
+/// Switches the installed OpenClaw to `version` through the verified installer. The local
+/// gateway is stopped for the switch and restarted if it was running. If the new version
+/// fails to install, to report its version, or to bring the gateway back, the previous
+/// version is reinstalled. Versions older than this app supports need `force`. The tarball
+/// is checked against `integrity` if given, and against `KNOWN_RELEASES` if listed there.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn openclaw_set_version(
+    app: AppHandle,
+    version: String,
+    registry: Option<String>,
+    integrity: Option<String>,
+    force: Option<bool>,
+    op_id: Option<String>,
+    timeout_secs: Option<u64>,
+    settings: State<'_, SettingsState>,
+) -> Result<VersionChange, String> {
+    let target = Version::parse(&version).ok_or(format!("Invalid version: {}", version))?;
+    let (support, note) = support(&target);
+    if support == Support::Unsupported && !force.unwrap_or(false) {
+        return Err(format!(
+            "OpenClaw {} isn't supported by this app ({}). Pass force to install it anyway.",
+            target, note
+        ));
+    }
+
+    let _claim = operations::claim(
+        &app,
+        "Changing the OpenClaw version",
+        &[ExclusionGroup::Install, ExclusionGroup::Gateway],
+    )?;
+    let paths = settings.paths();
+    let previous = check(&paths).await.version.and_then(|v| Version::parse(&v));
+    if previous.as_ref() == Some(&target) {
+        return Ok(VersionChange {
+            previous: previous.map(|v| v.to_string()),
+            current: target.to_string(),
+        });
+    }
+
+    // A remote gateway runs its own copy of OpenClaw; only a local one uses this binary.
+    let gateway_target = gateway::target(&paths);
+    let restart_gateway = !gateway_target.remote
+        && gateway::probe(&gateway_target.url, gateway_target.token.as_deref())
+            .await
+            .result
+            == ProbeResult::Running;
+    if restart_gateway {
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["gateway", "stop"]).await;
+    }
+
+    let Err(error) = install_version(
+        &app,
+        &paths,
+        &target,
+        registry.clone(),
+        integrity,
+        op_id,
+        timeout_secs,
+        restart_gateway,
+    )
+    .await
+    else {
+        return Ok(VersionChange {
+            previous: previous.map(|v| v.to_string()),
+            current: target.to_string(),
+        });
+    };
+
+    let Some(previous) = previous else {
+        return Err(format!(
+            "Switching to OpenClaw {} failed: {}. There was no earlier version to roll back to.",
+            target, error
+        ));
+    };
+    emit_progress(
+        &app,
+        InstallPhase::RollingBack,
+        format!("Rolling back to OpenClaw {}", previous),
+    );
+    if restart_gateway {
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["gateway", "stop"]).await;
+    }
+    match install_version(
+        &app,
+        &paths,
+        &previous,
+        registry,
+        None,
+        None,
+        timeout_secs,
+        restart_gateway,
+    )
+    .await
+    {
+        Ok(()) => Err(format!(
+            "Switching to OpenClaw {} failed: {}. Rolled back to {}.",
+            target, error, previous
+        )),
+        Err(rollback_error) => Err(format!(
+            "Switching to OpenClaw {} failed: {}. Rolling back to {} also failed: {}",
+            target, error, previous, rollback_error
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn openclaw_version(
+    settings: State<'_, SettingsState>,
+) -> Result<OpenClawVersion, String> {
+    Ok(check(&settings.paths()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{closed_addr, MockRequest, MockResponse, MockServer};
+
+    fn v(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn orders_releases_and_pre_releases() {
+        let ordered = [
+            "2026.1.29",
+            "2026.2.9",
+            "2026.2.17-alpha",
+            "2026.2.17-alpha.1",
+            "2026.2.17-beta",
+            "2026.2.17-beta.2",
+            "2026.2.17-beta.10",
+            "2026.2.17-beta.10.1",
+            "2026.2.17-rc.1",
+            "2026.2.17",
+            "2026.10.1",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(v("openclaw 2026.2.17-beta.1 (abc)"), v("2026.2.17-beta.1"));
+        assert_eq!(v("2026.2.17-1").cmp(&v("2026.2.17-beta")), Ordering::Less);
+    }
+
+    fn gateway_reporting(version: &'static str) -> impl Fn(&MockRequest) -> MockResponse {
+        move |_| {
+            MockResponse::json(
+                200,
+                serde_json::json!({ "version": version, "uptimeMs": 1000 }),
+            )
+        }
+    }
+
+    fn target(url: String) -> GatewayTarget {
+        GatewayTarget {
+            url,
+            remote: false,
+            token: None,
+            ssh_target: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_for_the_expected_version() {
+        let server = MockServer::start(gateway_reporting("2026.2.17")).await;
+        let timeout = Duration::from_secs(1);
+        assert!(wait_for(&target(server.url("")), None, None, timeout)
+            .await
+            .is_ok());
+        let expected = v("2026.2.17");
+        assert!(
+            wait_for(&target(server.url("")), Some(&expected), None, timeout)
+                .await
+                .is_ok()
+        );
+
+        let newer = v("2026.3.1");
+        let err = wait_for(&target(server.url("")), Some(&newer), None, timeout)
+            .await
+            .unwrap_err();
+        assert!(err.contains("it runs 2026.2.17, not 2026.3.1"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_cli_version_when_the_gateway_reports_none() {
+        let server =
+            MockServer::start(|_| MockResponse::json(200, serde_json::json!({ "uptimeMs": 1000 })))
+                .await;
+        let timeout = Duration::from_secs(1);
+        let expected = v("2026.2.17");
+        assert!(wait_for(
+            &target(server.url("")),
+            Some(&expected),
+            Some(&expected),
+            timeout
+        )
+        .await
+        .is_ok());
+
+        let older = v("2026.2.9");
+        let err = wait_for(
+            &target(server.url("")),
+            Some(&expected),
+            Some(&older),
+            timeout,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("reports 2026.2.9, not 2026.2.17"), "{}", err);
+
+        let err = wait_for(&target(server.url("")), Some(&expected), None, timeout)
+            .await
+            .unwrap_err();
+        assert!(err.contains("is unconfirmed"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_a_gateway_that_doesnt_answer() {
+        let url = format!("http://{}", closed_addr().await);
+        let err = wait_for(&target(url), None, None, Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert!(err.contains("didn't come back up"), "{}", err);
+    }
+}
@@ -1,7 +1,11 @@
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
+
+use super::openclaw_config::{ModelCost, ModelDefinition};
+use super::settings::SettingsState;
+use super::vault::Vault;
 
 pub const OPENROUTER_BASE: &str = "https://openrouter.ai/api/v1/chat/completions";
 const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
@@ -30,6 +34,142 @@ pub async fn openrouter_test_key(api_key: String) -> Result<(), String> {
     }
 }
 
+// --- Model catalog ---
+
+/// One entry of OpenRouter's `/models` catalog (only the fields we use).
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CatalogModel {
+    pub id: String,
+    pub name: Option<String>,
+    pub context_length: Option<u64>,
+    pub pricing: CatalogPricing,
+    pub architecture: CatalogArchitecture,
+    pub top_provider: CatalogTopProvider,
+    pub supported_parameters: Vec<String>,
+}
+
+/// USD per token, as decimal strings. "-1" marks variable pricing (e.g. openrouter/auto).
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CatalogPricing {
+    pub prompt: Option<String>,
+    pub completion: Option<String>,
+    pub input_cache_read: Option<String>,
+    pub input_cache_write: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CatalogArchitecture {
+    pub input_modalities: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CatalogTopProvider {
+    pub context_length: Option<u64>,
+    pub max_completion_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogResponse {
+    data: Vec<CatalogModel>,
+}
+
+/// Output budget when the catalog doesn't state one.
+const FALLBACK_MAX_TOKENS: u64 = 8192;
+/// Context window assumed for a model when the catalog can't be reached.
+const FALLBACK_CONTEXT_WINDOW: u64 = 128_000;
+
+fn per_million(price: &Option<String>) -> serde_json::Number {
+    price
+        .as_deref()
+        .and_then(|p| p.trim().parse::<f64>().ok())
+        .filter(|p| *p > 0.0)
+        .and_then(|p| serde_json::Number::from_f64(p * 1_000_000.0))
+        .unwrap_or_else(|| 0.into())
+}
+
+impl CatalogModel {
+    /// The `models.providers.openrouter.models[]` entry OpenClaw needs to budget context
+    /// and report cost for this model.
+    pub fn to_definition(&self) -> ModelDefinition {
+        let context_window = self.top_provider.context_length.or(self.context_length);
+        let max_tokens = self
+            .top_provider
+            .max_completion_tokens
+            .unwrap_or(FALLBACK_MAX_TOKENS);
+        // OpenClaw understands text and image input; other modalities (audio, file) aren't
+        // passed through to the model.
+        let mut input: Vec<String> = self
+            .architecture
+            .input_modalities
+            .iter()
+            .filter(|m| matches!(m.as_str(), "text" | "image"))
+            .cloned()
+            .collect();
+        if !input.iter().any(|m| m == "text") {
+            input.insert(0, "text".to_string());
+        }
+        ModelDefinition {
+            id: self.id.clone(),
+            name: Some(self.name.clone().unwrap_or_else(|| self.id.clone())),
+            reasoning: Some(
+                self.supported_parameters
+                    .iter()
+                    .any(|p| p == "reasoning" || p == "include_reasoning"),
+            ),
+            input: Some(input),
+            cost: Some(ModelCost {
+                input: Some(per_million(&self.pricing.prompt)),
+                output: Some(per_million(&self.pricing.completion)),
+                cache_read: Some(per_million(&self.pricing.input_cache_read)),
+                cache_write: Some(per_million(&self.pricing.input_cache_write)),
+                ..Default::default()
+            }),
+            max_tokens: Some(context_window.map_or(max_tokens, |c| max_tokens.min(c))),
+            context_window,
+            ..Default::default()
+        }
+    }
+}
+
+/// A conservative definition for when the catalog can't be fetched (e.g. offline): text
+/// input, a common context window and no pricing. Configuring again online replaces it.
+pub fn offline_definition(model_id: &str) -> ModelDefinition {
+    ModelDefinition {
+        id: model_id.to_string(),
+        name: Some(model_id.to_string()),
+        input: Some(vec!["text".to_string()]),
+        context_window: Some(FALLBACK_CONTEXT_WINDOW),
+        max_tokens: Some(FALLBACK_MAX_TOKENS),
+        ..Default::default()
+    }
+}
+
+pub async fn fetch_catalog() -> Result<Vec<CatalogModel>, String> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .get(OPENROUTER_MODELS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch OpenRouter model catalog: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Failed to fetch OpenRouter model catalog: HTTP {}",
+            resp.status()
+        ));
+    }
+    resp.json::<CatalogResponse>()
+        .await
+        .map(|r| r.data)
+        .map_err(|e| format!("Failed to parse OpenRouter model catalog: {}", e))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Message {
     pub role: String,
@@ -73,9 +213,11 @@ pub async fn openrouter_chat(
     app: AppHandle,
     model: String,
     messages: Vec<Message>,
-    api_key: String,
     stream: bool,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
 ) -> Result<ChatResponse, String> {
+    let api_key = settings.openrouter_key(&vault)?;
     let client = Client::new();
 
     let body = serde_json::json!({
@@ -99,6 +241,20 @@ pub async fn openrouter_chat(
         return Err(format!("API request failed: {}", err_text));
     }
 
+    read_chat_response(resp, stream, |event| {
+        let _ = app.emit("chat-token", event);
+    })
+    .await
+}
+
+/// Reads an OpenAI-style chat completion. With `stream`, parses the SSE body, passing
+/// each delta to `on_token` (callers emit it as a `chat-token` event), and returns the
+/// assembled message.
+pub async fn read_chat_response(
+    resp: reqwest::Response,
+    stream: bool,
+    on_token: impl Fn(ChatTokenEvent),
+) -> Result<ChatResponse, String> {
     if !stream {
         let chat_resp: OpenRouterResponse = resp.json().await.map_err(|e| e.to_string())?;
         
@@ -153,19 +309,14 @@ pub async fn openrouter_chat(
                 continue;
             }
 
-            if line.starts_with("data: ") {
-                let data = &line[6..];
-                
+            if let Some(data) = line.strip_prefix("data: ") {
                 if data == "[DONE]" {
                     final_response.done = true;
                     // Send final event to indicate completion
-                    let _ = app.emit(
-                        "chat-token",
-                        ChatTokenEvent {
-                            content: String::new(),
-                            done: true,
-                        },
-                    );
+                    on_token(ChatTokenEvent {
+                        content: String::new(),
+                        done: true,
+                    });
                     break;
                 }
 
@@ -174,13 +325,10 @@ pub async fn openrouter_chat(
                         if let Some(delta) = &choice.delta {
                             if let Some(content_chunk) = &delta.content {
                                 full_content.push_str(content_chunk);
-                                let _ = app.emit(
-                                    "chat-token",
-                                    ChatTokenEvent {
-                                        content: content_chunk.clone(),
-                                        done: false,
-                                    },
-                                );
+                                on_token(ChatTokenEvent {
+                                    content: content_chunk.clone(),
+                                    done: false,
+                                });
                             }
                         }
                     }
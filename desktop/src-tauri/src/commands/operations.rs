@@ -0,0 +1,858 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, Command};
+use tokio::sync::Notify;
+
+use super::diagnostics::resolve_login_shell_path;
+use super::installer;
+use super::openclaw::OpenClawLogEvent;
+use super::openclaw_paths::OpenClawPaths;
+use super::vault::{now_millis, random_id};
+
+/// Timeout for operations that don't pick their own.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How long a cancelled process tree gets to exit after SIGTERM before it is killed.
+const KILL_GRACE: Duration = Duration::from_secs(3);
+
+// --- Types ---
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+    TimedOut,
+    /// A task ended; its outcome is what the command that ran it returned.
+    Finished,
+}
+
+/// A task is a command holding exclusion groups; a process is a spawned program.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Task,
+    Process,
+}
+
+/// Resources a task needs to itself. Two tasks sharing a group can't run at once.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExclusionGroup {
+    /// Installs or removes the OpenClaw CLI.
+    Install,
+    /// Writes openclaw.json or auth-profiles.json, in one step or over several.
+    Config,
+    /// Stops, starts or restarts the gateway.
+    Gateway,
+}
+
+/// Returned by `operations_list` and emitted as `openclaw-operation` when an operation
+/// starts, moves to another step, or ends.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OperationStatus {
+    pub id: String,
+    pub label: String,
+    pub kind: OperationKind,
+    pub groups: Vec<ExclusionGroup>,
+    pub state: OperationState,
+    /// What a task is doing now, e.g. "Restarting the gateway".
+    pub step: Option<String>,
+    pub pid: Option<u32>,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub message: Option<String>,
+}
+
+/// A process to run as an operation.
+pub struct OperationSpec {
+    /// Caller-chosen id, so the UI can cancel or answer prompts before the command returns.
+    /// Generated when absent.
+    pub id: Option<String>,
+    /// Shown in events and errors, e.g. "openclaw onboard".
+    pub label: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub timeout: Duration,
+}
+
+impl OperationSpec {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        let label = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            id: None,
+            label,
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn id(mut self, id: Option<String>) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// `secs` overrides `default` when set, e.g. from a command argument.
+    pub fn timeout(mut self, secs: Option<u64>, default: Duration) -> Self {
+        self.timeout = secs.map(Duration::from_secs).unwrap_or(default);
+        self
+    }
+}
+
+/// Called with each stdout line before it is emitted as a log event, and with `None` once
+/// stdout closes.
+pub type LineHook = Box<dyn FnMut(Option<&str>) + Send>;
+
+/// Where operations report status changes and output: `openclaw-operation` and
+/// `openclaw-log` events in the app.
+pub trait OperationEvents: Send + Sync {
+    fn status(&self, status: OperationStatus);
+    fn log(&self, event: OpenClawLogEvent);
+}
+
+impl OperationEvents for AppHandle {
+    fn status(&self, status: OperationStatus) {
+        let _ = self.emit("openclaw-operation", status);
+    }
+
+    fn log(&self, event: OpenClawLogEvent) {
+        let _ = self.emit("openclaw-log", event);
+    }
+}
+
+struct Operation {
+    label: String,
+    pid: Option<u32>,
+    started_at: u64,
+    stdin: tokio::sync::Mutex<Option<ChildStdin>>,
+    cancel: Notify,
+}
+
+impl Operation {
+    fn status(&self, id: &str, state: OperationState, message: Option<String>) -> OperationStatus {
+        OperationStatus {
+            id: id.to_string(),
+            label: self.label.clone(),
+            kind: OperationKind::Process,
+            groups: Vec::new(),
+            state,
+            step: None,
+            pid: self.pid,
+            started_at: self.started_at,
+            finished_at: (state != OperationState::Running).then(now_millis),
+            message,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Task {
+    id: String,
+    label: String,
+    groups: Vec<ExclusionGroup>,
+    step: Option<String>,
+    started_at: u64,
+}
+
+impl Task {
+    fn status(&self, state: OperationState) -> OperationStatus {
+        OperationStatus {
+            id: self.id.clone(),
+            label: self.label.clone(),
+            kind: OperationKind::Task,
+            groups: self.groups.clone(),
+            state,
+            step: self.step.clone(),
+            pid: None,
+            started_at: self.started_at,
+            finished_at: (state != OperationState::Running).then(now_millis),
+            message: None,
+        }
+    }
+}
+
+/// Running processes by id, and the tasks holding exclusion groups.
+#[derive(Default)]
+pub struct OperationManager {
+    running: Mutex<HashMap<String, Arc<Operation>>>,
+    tasks: Arc<Mutex<Vec<Task>>>,
+}
+
+impl OperationManager {
+    fn get(&self, id: &str) -> Result<Arc<Operation>, String> {
+        if let Some(op) = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+        {
+            return Ok(op.clone());
+        }
+        match self
+            .tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|t| t.id == id)
+        {
+            Some(task) => Err(format!(
+                "{} can't be stopped directly; cancel the program it is running",
+                task.label
+            )),
+            None => Err(format!("No running operation with id {}", id)),
+        }
+    }
+
+    fn insert(&self, id: &str, op: Arc<Operation>) -> Result<(), String> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.contains_key(id) {
+            return Err(format!("Operation {} is already running", id));
+        }
+        running.insert(id.to_string(), op);
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id);
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        self.get(id)?.cancel.notify_one();
+        Ok(())
+    }
+
+    pub async fn write_stdin(&self, id: &str, line: &str) -> Result<(), String> {
+        let op = self.get(id)?;
+        let mut stdin = op.stdin.lock().await;
+        let stdin = stdin
+            .as_mut()
+            .ok_or_else(|| format!("{} has no stdin", op.label))?;
+        stdin
+            .write_all(format!("{}\n", line).as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to {}: {}", op.label, e))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write to {}: {}", op.label, e))
+    }
+
+    fn list(&self) -> Vec<OperationStatus> {
+        let mut list: Vec<OperationStatus> = self
+            .tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|t| t.status(OperationState::Running))
+            .collect();
+        list.extend(
+            self.running
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(id, op)| op.status(id, OperationState::Running, None)),
+        );
+        list.sort_by_key(|s| s.started_at);
+        list
+    }
+}
+
+/// Held by a task for as long as it runs; releases its groups when dropped.
+pub struct Claim {
+    events: Arc<dyn OperationEvents>,
+    tasks: Arc<Mutex<Vec<Task>>>,
+    id: String,
+    groups: Vec<ExclusionGroup>,
+}
+
+impl Claim {
+    pub fn holds(&self, group: ExclusionGroup) -> bool {
+        self.groups.contains(&group)
+    }
+
+    /// Records what the task is doing now and reports it to the UI.
+    pub fn step(&self, step: &str) {
+        let status = {
+            let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(task) = tasks.iter_mut().find(|t| t.id == self.id) else {
+                return;
+            };
+            task.step = Some(step.to_string());
+            task.status(OperationState::Running)
+        };
+        self.events.status(status);
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        let task = {
+            let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+            let index = tasks.iter().position(|t| t.id == self.id);
+            index.map(|i| tasks.remove(i))
+        };
+        if let Some(task) = task {
+            self.events.status(task.status(OperationState::Finished));
+        }
+    }
+}
+
+/// Registers a task holding `groups`, or fails with a busy error naming the task that
+/// holds one of them.
+pub fn claim(app: &AppHandle, label: &str, groups: &[ExclusionGroup]) -> Result<Claim, String> {
+    claim_with(
+        &app.state::<OperationManager>(),
+        Arc::new(app.clone()),
+        label,
+        groups,
+    )
+}
+
+/// `claim` registering with `manager` and reporting to `events`.
+pub fn claim_with(
+    manager: &OperationManager,
+    events: Arc<dyn OperationEvents>,
+    label: &str,
+    groups: &[ExclusionGroup],
+) -> Result<Claim, String> {
+    let task = {
+        let mut tasks = manager.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(busy) = tasks
+            .iter()
+            .find(|t| t.groups.iter().any(|g| groups.contains(g)))
+        {
+            return Err(format!(
+                "Busy: {} is in progress. Wait for it to finish, then try again.",
+                busy.label
+            ));
+        }
+        let task = Task {
+            id: random_id("task"),
+            label: label.to_string(),
+            groups: groups.to_vec(),
+            step: None,
+            started_at: now_millis(),
+        };
+        tasks.push(task.clone());
+        task
+    };
+    events.status(task.status(OperationState::Running));
+    Ok(Claim {
+        events,
+        tasks: manager.tasks.clone(),
+        id: task.id,
+        groups: task.groups,
+    })
+}
+
+fn is_valid_op_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// --- Running ---
+
+enum Outcome {
+    Exited(ExitStatus),
+    Cancelled,
+    TimedOut,
+}
+
+/// Runs `spec` as an operation: stdout/stderr are streamed as `openclaw-log` events tagged
+/// with the operation id, stdin stays open for `operation_write_stdin`, and the process tree
+/// is killed on cancel or timeout. Errors on a non-zero exit, cancel or timeout.
+pub async fn run(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    spec: OperationSpec,
+    on_stdout: Option<LineHook>,
+) -> Result<(), String> {
+    let manager = app.state::<OperationManager>();
+    run_with(&manager, Arc::new(app.clone()), paths, spec, on_stdout).await
+}
+
+/// `run` registering with `manager` and reporting to `events`.
+pub async fn run_with(
+    manager: &OperationManager,
+    events: Arc<dyn OperationEvents>,
+    paths: &OpenClawPaths,
+    spec: OperationSpec,
+    mut on_stdout: Option<LineHook>,
+) -> Result<(), String> {
+    let id = spec.id.clone().unwrap_or_else(|| random_id("op"));
+    if !is_valid_op_id(&id) {
+        return Err(format!("Invalid operation id: {}", id));
+    }
+
+    let mut cmd = Command::new(&spec.program);
+    cmd.args(&spec.args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    // Own process group, so cancelling reaches whatever the process started.
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    // On macOS, GUI apps get a minimal PATH. Use the login shell PATH so we
+    // can find binaries like openclaw, ollama, etc. A user-local install from the
+    // installer is found either way.
+    let base = if cfg!(target_os = "macos") {
+        resolve_login_shell_path()
+    } else {
+        None
+    };
+    cmd.env("PATH", installer::search_path(base));
+    paths.apply_env(&mut cmd);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn {}: {}", spec.label, e))?;
+
+    let op = Arc::new(Operation {
+        label: spec.label.clone(),
+        pid: child.id(),
+        started_at: now_millis(),
+        stdin: tokio::sync::Mutex::new(child.stdin.take()),
+        cancel: Notify::new(),
+    });
+    if let Err(e) = manager.insert(&id, op.clone()) {
+        let _ = child.start_kill();
+        return Err(e);
+    }
+    events.status(op.status(&id, OperationState::Running, None));
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let events_out = events.clone();
+    let events_err = events.clone();
+    let id_out = id.clone();
+    let id_err = id.clone();
+
+    let stdout_handle = tokio::spawn(async move {
+        if let Some(stdout) = stdout {
+            let mut reader = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                if let Some(hook) = on_stdout.as_mut() {
+                    hook(Some(&line));
+                }
+                log_line(&*events_out, &id_out, line, "stdout");
+            }
+        }
+        if let Some(hook) = on_stdout.as_mut() {
+            hook(None);
+        }
+    });
+
+    let stderr_handle = tokio::spawn(async move {
+        if let Some(stderr) = stderr {
+            let mut reader = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                log_line(&*events_err, &id_err, line, "stderr");
+            }
+        }
+    });
+
+    let outcome = tokio::select! {
+        status = child.wait() => match status {
+            Ok(status) => Ok(Outcome::Exited(status)),
+            Err(e) => Err(format!("Failed to wait for process: {}", e)),
+        },
+        _ = op.cancel.notified() => Ok(Outcome::Cancelled),
+        _ = tokio::time::sleep(spec.timeout) => Ok(Outcome::TimedOut),
+    };
+    if !matches!(outcome, Ok(Outcome::Exited(_))) {
+        kill_tree(&mut child).await;
+    }
+
+    // The readers end once every process holding the pipes has exited.
+    let _ = stdout_handle.await;
+    let _ = stderr_handle.await;
+    manager.remove(&id);
+
+    let (state, result) = match outcome {
+        Ok(Outcome::Exited(status)) if status.success() => (OperationState::Succeeded, Ok(())),
+        Ok(Outcome::Exited(status)) => (
+            OperationState::Failed,
+            Err(format!(
+                "{} exited with code {:?}",
+                spec.label,
+                status.code()
+            )),
+        ),
+        Ok(Outcome::Cancelled) => (
+            OperationState::Cancelled,
+            Err(format!("{} was cancelled", spec.label)),
+        ),
+        Ok(Outcome::TimedOut) => (
+            OperationState::TimedOut,
+            Err(format!(
+                "{} timed out after {}s",
+                spec.label,
+                spec.timeout.as_secs()
+            )),
+        ),
+        Err(e) => (OperationState::Failed, Err(e)),
+    };
+    events.status(op.status(&id, state, result.as_ref().err().cloned()));
+    result
+}
+
+/// Stops the child and everything in its process group: SIGTERM first, SIGKILL after a
+/// grace period. On Windows the tree is killed with taskkill.
+async fn kill_tree(child: &mut tokio::process::Child) {
+    let Some(pid) = child.id() else {
+        return;
+    };
+
+    #[cfg(unix)]
+    {
+        let group = format!("-{}", pid);
+        let _ = Command::new("kill")
+            .args(["-TERM", "--", &group])
+            .status()
+            .await;
+        let _ = tokio::time::timeout(KILL_GRACE, child.wait()).await;
+        // Whatever is left: the leader if it ignored SIGTERM, or processes it started.
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &group])
+            .status()
+            .await;
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .status()
+            .await;
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    let _ = (pid, child.start_kill());
+
+    let _ = child.wait().await;
+}
+
+fn log_line(events: &dyn OperationEvents, id: &str, line: String, stream: &str) {
+    events.log(OpenClawLogEvent {
+        line,
+        stream: stream.to_string(),
+        op_id: Some(id.to_string()),
+    });
+}
+
+// --- Commands ---
+
+/// Running tasks and processes, oldest first.
+#[tauri::command]
+pub fn operations_list(
+    operations: State<'_, OperationManager>,
+) -> Result<Vec<OperationStatus>, String> {
+    Ok(operations.list())
+}
+
+/// Kills a running operation and everything it started. The command that started it
+/// returns a "was cancelled" error.
+#[tauri::command]
+pub fn operation_cancel(
+    op_id: String,
+    operations: State<'_, OperationManager>,
+) -> Result<(), String> {
+    operations.cancel(&op_id)
+}
+
+/// Writes `line` and a newline to an operation's stdin, e.g. to answer a prompt.
+#[tauri::command]
+pub async fn operation_write_stdin(
+    op_id: String,
+    line: String,
+    operations: State<'_, OperationManager>,
+) -> Result<(), String> {
+    operations.write_stdin(&op_id, &line).await
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Records what a run reports instead of emitting it.
+    #[derive(Default)]
+    struct Recorder {
+        statuses: Mutex<Vec<OperationStatus>>,
+        logs: Mutex<Vec<OpenClawLogEvent>>,
+    }
+
+    impl OperationEvents for Recorder {
+        fn status(&self, status: OperationStatus) {
+            self.statuses.lock().unwrap().push(status);
+        }
+
+        fn log(&self, event: OpenClawLogEvent) {
+            self.logs.lock().unwrap().push(event);
+        }
+    }
+
+    impl Recorder {
+        fn lines(&self) -> Vec<(String, String)> {
+            let logs = self.logs.lock().unwrap();
+            logs.iter()
+                .map(|l| (l.stream.clone(), l.line.clone()))
+                .collect()
+        }
+
+        fn states(&self) -> Vec<OperationState> {
+            let statuses = self.statuses.lock().unwrap();
+            statuses.iter().map(|s| s.state).collect()
+        }
+
+        async fn wait_for_line(&self, line: &str) {
+            for _ in 0..500 {
+                if self.lines().iter().any(|(_, l)| l == line) {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            panic!("no {:?} in {:?}", line, self.lines());
+        }
+    }
+
+    fn shell(id: &str, script: &str) -> OperationSpec {
+        OperationSpec::new("sh", &["-c", script]).id(Some(id.to_string()))
+    }
+
+    async fn wait_until_running(manager: &OperationManager, id: &str) {
+        for _ in 0..500 {
+            if manager.get(id).is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("{} never started", id);
+    }
+
+    #[tokio::test]
+    async fn tags_output_with_the_operation_id() {
+        let manager = OperationManager::default();
+        let events = Arc::new(Recorder::default());
+        let spec = shell("op-log", "echo out; echo err >&2");
+        run_with(
+            &manager,
+            events.clone(),
+            &OpenClawPaths::default(),
+            spec,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let mut lines = events.lines();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                ("stderr".to_string(), "err".to_string()),
+                ("stdout".to_string(), "out".to_string())
+            ]
+        );
+        let logs = events.logs.lock().unwrap();
+        assert!(logs.iter().all(|l| l.op_id.as_deref() == Some("op-log")));
+        assert_eq!(
+            events.states(),
+            [OperationState::Running, OperationState::Succeeded]
+        );
+        assert!(manager.get("op-log").is_err());
+    }
+
+    #[tokio::test]
+    async fn forwards_stdin_and_cancels_the_whole_tree() {
+        let manager = OperationManager::default();
+        let events = Arc::new(Recorder::default());
+        // The background sleep holds stdout open, so the run can only end once the whole
+        // process group is gone, not just the shell.
+        let spec = shell("op-stdin", "read x; echo \"got $x\"; sleep 100 & wait").label("prompt");
+        let paths = OpenClawPaths::default();
+        let running = run_with(&manager, events.clone(), &paths, spec, None);
+        let drive = async {
+            wait_until_running(&manager, "op-stdin").await;
+            manager.write_stdin("op-stdin", "hello").await.unwrap();
+            events.wait_for_line("got hello").await;
+            manager.cancel("op-stdin").unwrap();
+        };
+
+        let (result, ()) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(running, drive)
+        })
+        .await
+        .expect("the process tree outlived the cancel");
+        assert_eq!(result.unwrap_err(), "prompt was cancelled");
+        assert_eq!(
+            events.states(),
+            [OperationState::Running, OperationState::Cancelled]
+        );
+        assert!(manager.cancel("op-stdin").is_err());
+        assert!(manager.write_stdin("op-stdin", "again").await.is_err());
+    }
+
+    #[test]
+    fn claims_conflict_only_on_shared_groups() {
+        use ExclusionGroup::*;
+        let manager = OperationManager::default();
+        let events: Arc<dyn OperationEvents> = Arc::new(Recorder::default());
+
+        let setup = claim_with(&manager, events.clone(), "Setup", &[Config, Gateway]).unwrap();
+        assert!(setup.holds(Config) && !setup.holds(Install));
+        let busy = claim_with(&manager, events.clone(), "Restart", &[Gateway]).err();
+        assert_eq!(
+            busy.as_deref(),
+            Some("Busy: Setup is in progress. Wait for it to finish, then try again.")
+        );
+        let install = claim_with(&manager, events.clone(), "Install", &[Install]).unwrap();
+
+        drop(setup);
+        let restart = claim_with(&manager, events.clone(), "Restart", &[Gateway]).unwrap();
+        assert!(claim_with(&manager, events.clone(), "Reinstall", &[Install]).is_err());
+        drop((install, restart));
+        assert!(manager.list().is_empty());
+    }
+
+    #[test]
+    fn releases_a_claim_when_its_task_panics() {
+        let manager = OperationManager::default();
+        let events: Arc<dyn OperationEvents> = Arc::new(Recorder::default());
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _claim =
+                claim_with(&manager, events.clone(), "Setup", &[ExclusionGroup::Config]).unwrap();
+            panic!("task failed");
+        }));
+
+        assert!(panicked.is_err());
+        assert!(claim_with(&manager, events, "Setup", &[ExclusionGroup::Config]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn lists_tasks_and_processes() {
+        let manager = OperationManager::default();
+        let events = Arc::new(Recorder::default());
+        let claim = claim_with(
+            &manager,
+            events.clone(),
+            "Setup",
+            &[ExclusionGroup::Install],
+        )
+        .unwrap();
+        claim.step("Installing");
+        let paths = OpenClawPaths::default();
+        let running = run_with(
+            &manager,
+            events.clone(),
+            &paths,
+            shell("op-list", "sleep 100"),
+            None,
+        );
+        let check = async {
+            wait_until_running(&manager, "op-list").await;
+            let list = manager.list();
+            manager.cancel("op-list").unwrap();
+            list
+        };
+        let (result, list) = tokio::join!(running, check);
+        assert!(result.is_err());
+
+        let kinds: Vec<_> = list.iter().map(|s| (s.kind, s.label.as_str())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (OperationKind::Task, "Setup"),
+                (OperationKind::Process, "sh -c sleep 100")
+            ]
+        );
+        assert_eq!(list[0].step.as_deref(), Some("Installing"));
+        assert_eq!(list[0].groups, [ExclusionGroup::Install]);
+        assert!(list[1].pid.is_some());
+        assert!(manager
+            .cancel(&list[0].id)
+            .unwrap_err()
+            .contains("can't be stopped directly"));
+
+        drop(claim);
+        assert!(manager.list().is_empty());
+        let states: Vec<_> = events
+            .statuses
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| s.state)
+            .collect();
+        assert_eq!(states.last(), Some(&OperationState::Finished));
+    }
+
+    #[tokio::test]
+    async fn kills_a_run_that_times_out() {
+        let manager = OperationManager::default();
+        let events = Arc::new(Recorder::default());
+        let spec = OperationSpec::new("sh", &["-c", "sleep 100 & wait"])
+            .label("slow")
+            .timeout(None, Duration::from_millis(200));
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            run_with(
+                &manager,
+                events.clone(),
+                &OpenClawPaths::default(),
+                spec,
+                None,
+            ),
+        )
+        .await
+        .expect("the process tree outlived the timeout");
+
+        assert_eq!(result.unwrap_err(), "slow timed out after 0s");
+        assert_eq!(
+            events.states(),
+            [OperationState::Running, OperationState::TimedOut]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_a_failing_exit_code() {
+        let manager = OperationManager::default();
+        let events = Arc::new(Recorder::default());
+        let spec = OperationSpec::new("sh", &["-c", "exit 3"]).label("failing");
+        let result = run_with(
+            &manager,
+            events.clone(),
+            &OpenClawPaths::default(),
+            spec,
+            None,
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err(), "failing exited with code Some(3)");
+        assert_eq!(
+            events.states(),
+            [OperationState::Running, OperationState::Failed]
+        );
+    }
+}
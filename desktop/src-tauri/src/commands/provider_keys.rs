@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use super::config_preview::{apply_or_preview, ConfigChange, ConfigPreview, PendingPreviews};
+use super::openclaw_config::{self, is_valid_agent_id, AuthConfig, AuthProfiles, OpenClawConfig};
+use super::providers::{self, ProviderSpec};
+use super::settings::SettingsState;
+use super::vault::{mask_key, Vault};
+
+/// One named key for a provider, e.g. "openrouter:company".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderKeyStatus {
+    pub profile_id: String,
+    /// The part after "provider:".
+    pub name: String,
+    pub masked: Option<String>,
+    /// First in the failover order, so OpenClaw tries it first.
+    pub primary: bool,
+    /// From OpenClaw's `usageStats`, in milliseconds since the epoch.
+    pub last_used: Option<i64>,
+    pub last_failure_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub error_count: u64,
+    /// Set while OpenClaw is skipping the key after repeated failures.
+    pub cooldown_until: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderKeys {
+    /// Registry id (see `providers::registry`).
+    pub provider: String,
+    pub label: String,
+    pub openclaw_provider: String,
+    /// Keys in failover order.
+    pub keys: Vec<ProviderKeyStatus>,
+}
+
+/// The `order` OpenClaw keeps in auth-profiles.json, which it consults before `auth.order`.
+fn stored_order(auth: &AuthProfiles, provider: &str) -> Option<Vec<String>> {
+    let ids = auth.get("order")?.get(provider)?.as_array()?;
+    Some(
+        ids.iter()
+            .filter_map(|id| id.as_str())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// The provider's profile ids in the order OpenClaw will try them: the order in
+/// auth-profiles.json, else `auth.order`, then any profiles neither mentions, in file order.
+fn effective_order(config: &OpenClawConfig, auth: &AuthProfiles, provider: &str) -> Vec<String> {
+    let existing: Vec<String> = openclaw_config::provider_profiles(auth, provider)
+        .map(|(id, _)| id.clone())
+        .collect();
+    let mut order: Vec<String> = stored_order(auth, provider)
+        .or_else(|| {
+            config
+                .auth()
+                .and_then(|a| a.order.as_ref()?.get(provider))
+                .cloned()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| existing.contains(id))
+        .collect();
+    for id in existing {
+        if !order.contains(&id) {
+            order.push(id);
+        }
+    }
+    order
+}
+
+/// Writes the order to both places OpenClaw reads it from, so neither overrides the other:
+/// `auth.order` in openclaw.json and `order` in auth-profiles.json. An empty order removes
+/// the provider's entry.
+pub fn save_order(
+    change: &mut ConfigChange,
+    provider: &str,
+    order: Vec<String>,
+) -> Result<(), String> {
+    set_stored_order(change.auth_mut()?, provider, &order);
+    set_order(change.config_mut()?, provider, order);
+    Ok(())
+}
+
+/// Sets `order` in auth-profiles.json. Files in the older flat layout, which have no
+/// `profiles` map, only get one if they already have an order.
+fn set_stored_order(auth: &mut AuthProfiles, provider: &str, order: &[String]) {
+    use serde_json::Value;
+
+    if !auth.contains_key("order") && (order.is_empty() || !auth.contains_key("profiles")) {
+        return;
+    }
+    let orders = auth
+        .entry("order")
+        .or_insert_with(|| Value::Object(Default::default()));
+    let Value::Object(orders) = orders else {
+        return;
+    };
+    if order.is_empty() {
+        orders.remove(provider);
+    } else {
+        orders.insert(provider.to_string(), serde_json::json!(order));
+    }
+    if orders.is_empty() {
+        auth.remove("order");
+    }
+}
+
+fn set_order(config: &mut OpenClawConfig, provider: &str, order: Vec<String>) {
+    if !order.is_empty() {
+        config.auth_order_mut().insert(provider.to_string(), order);
+        return;
+    }
+    let Some(Some(auth)) = &mut config.auth else {
+        return;
+    };
+    let Some(orders) = &mut auth.order else {
+        return;
+    };
+    if orders.remove(provider).is_none() {
+        return;
+    }
+    if orders.is_empty() {
+        auth.order = None;
+    }
+    if *auth == AuthConfig::default() {
+        config.auth = None;
+    }
+}
+
+fn key_status(auth: &AuthProfiles, profile_id: &str, primary: bool) -> ProviderKeyStatus {
+    let stats = openclaw_config::auth_usage_stats(auth, profile_id);
+    let stat_i64 = |key: &str| stats.and_then(|s| s.get(key)).and_then(|v| v.as_i64());
+    let stat_str = |key: &str| {
+        stats
+            .and_then(|s| s.get(key))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    ProviderKeyStatus {
+        profile_id: profile_id.to_string(),
+        name: profile_id
+            .split_once(':')
+            .map(|(_, name)| name)
+            .unwrap_or(profile_id)
+            .to_string(),
+        masked: openclaw_config::auth_profile_map(auth)
+            .get(profile_id)
+            .and_then(|p| p.get("key"))
+            .and_then(|k| k.as_str())
+            .filter(|k| !k.is_empty())
+            .map(mask_key),
+        primary,
+        last_used: stat_i64("lastUsed"),
+        last_failure_at: stat_i64("lastFailureAt"),
+        last_error: stat_str("lastError").or_else(|| stat_str("disabledReason")),
+        error_count: stats
+            .and_then(|s| s.get("errorCount"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        cooldown_until: stat_i64("cooldownUntil").or_else(|| stat_i64("disabledUntil")),
+    }
+}
+
+fn provider_keys(config: &OpenClawConfig, auth: &AuthProfiles, spec: ProviderSpec) -> ProviderKeys {
+    let keys = effective_order(config, auth, &spec.openclaw_provider)
+        .iter()
+        .enumerate()
+        .map(|(i, id)| key_status(auth, id, i == 0))
+        .collect();
+    ProviderKeys {
+        provider: spec.id,
+        label: spec.label,
+        openclaw_provider: spec.openclaw_provider,
+        keys,
+    }
+}
+
+fn profile_id(spec: &ProviderSpec, name: &str) -> Result<String, String> {
+    if !is_valid_agent_id(name) {
+        return Err(format!(
+            "Key name \"{}\" must be lowercase letters, digits, - or _",
+            name
+        ));
+    }
+    Ok(format!("{}:{}", spec.openclaw_provider, name))
+}
+
+#[tauri::command]
+pub fn openclaw_provider_keys_list(
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<ProviderKeys>, String> {
+    let paths = settings.paths();
+    let config = openclaw_config::load_config(&paths)?;
+    let auth = openclaw_config::load_auth_profiles(&paths.auth_profiles_path())?;
+    Ok(providers::registry(&settings.get())
+        .into_iter()
+        .map(|spec| provider_keys(&config, &auth, spec))
+        .collect())
+}
+
+/// Stores a vault key as `<provider>:<name>`, replacing a key of the same name. New keys go
+/// last in the failover order unless `primary` is set. The key is checked against the
+/// provider first unless `force` is set.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn openclaw_provider_key_add(
+    app: AppHandle,
+    provider: String,
+    name: String,
+    key_ref: String,
+    primary: Option<bool>,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let key = vault.resolve(&key_ref)?;
+    let spec = providers::find_provider(&settings.get(), &provider)?;
+    let id = profile_id(&spec, name.trim())?;
+    if !force.unwrap_or(false) {
+        providers::check_key(&spec, &key).await.into_result()?;
+    }
+
+    let mut change = ConfigChange::begin(&settings.paths());
+    openclaw_config::auth_profile_map_mut(change.auth_mut()?).insert(
+        id.clone(),
+        serde_json::json!({
+            "provider": spec.openclaw_provider,
+            "mode": "api_key",
+            "key": key.trim()
+        }),
+    );
+    let auth = change.auth_mut()?.clone();
+    let config = change.config_mut()?;
+    spec.register(config);
+    let mut order = effective_order(config, &auth, &spec.openclaw_provider);
+    if primary.unwrap_or(false) {
+        order.retain(|p| p != &id);
+        order.insert(0, id);
+    }
+    save_order(&mut change, &spec.openclaw_provider, order)?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[tauri::command]
+pub fn openclaw_provider_key_remove(
+    app: AppHandle,
+    provider: String,
+    name: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let spec = providers::find_provider(&settings.get(), &provider)?;
+    let id = profile_id(&spec, &name)?;
+
+    let mut change = ConfigChange::begin(&settings.paths());
+    let auth = change.auth_mut()?;
+    if !openclaw_config::remove_auth_profile(auth, &id) {
+        return Err(format!("No key named \"{}\" for {}", name, spec.label));
+    }
+    let auth = auth.clone();
+    // effective_order only keeps ids that still exist, so this also prunes `id`.
+    let order = effective_order(change.config_mut()?, &auth, &spec.openclaw_provider);
+    // Drop the order entirely once a single key is left; there is nothing to fail over to.
+    save_order(
+        &mut change,
+        &spec.openclaw_provider,
+        if order.len() > 1 { order } else { Vec::new() },
+    )?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Moves a key to the front of the failover order.
+#[tauri::command]
+pub fn openclaw_provider_key_set_primary(
+    app: AppHandle,
+    provider: String,
+    name: String,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let spec = providers::find_provider(&settings.get(), &provider)?;
+    let id = profile_id(&spec, &name)?;
+
+    let mut change = ConfigChange::begin(&settings.paths());
+    let auth = change.auth_mut()?.clone();
+    let config = change.config_mut()?;
+    let mut order = effective_order(config, &auth, &spec.openclaw_provider);
+    if !order.contains(&id) {
+        return Err(format!("No key named \"{}\" for {}", name, spec.label));
+    }
+    order.retain(|p| p != &id);
+    order.insert(0, id);
+    save_order(&mut change, &spec.openclaw_provider, order)?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+/// Sets the full failover order. `profile_ids` must list every key of the provider once.
+#[tauri::command]
+pub fn openclaw_provider_keys_reorder(
+    app: AppHandle,
+    provider: String,
+    profile_ids: Vec<String>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+    previews: State<'_, PendingPreviews>,
+) -> Result<Option<ConfigPreview>, String> {
+    let spec = providers::find_provider(&settings.get(), &provider)?;
+
+    let mut change = ConfigChange::begin(&settings.paths());
+    let auth = change.auth_mut()?.clone();
+    let config = change.config_mut()?;
+    let mut current = effective_order(config, &auth, &spec.openclaw_provider);
+    let mut requested = profile_ids.clone();
+    current.sort();
+    requested.sort();
+    if current != requested {
+        return Err(format!(
+            "The new order must list each {} key exactly once",
+            spec.label
+        ));
+    }
+    save_order(&mut change, &spec.openclaw_provider, profile_ids)?;
+    apply_or_preview(&app, change, dry_run.unwrap_or(false), &previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_a_key_prunes_it_from_every_order() {
+        let mut config: OpenClawConfig = serde_json::from_value(serde_json::json!({
+            "auth": {
+                "order": {
+                    "anthropic": ["anthropic:work", "anthropic:default", "anthropic:ci"]
+                }
+            }
+        }))
+        .unwrap();
+        let mut auth: AuthProfiles = serde_json::from_value(serde_json::json!({
+            "profiles": {
+                "anthropic:default": { "provider": "anthropic", "key": "sk-ant-1" },
+                "anthropic:work": { "provider": "anthropic", "key": "sk-ant-2" },
+                "anthropic:ci": { "provider": "anthropic", "key": "sk-ant-3" }
+            },
+            "order": { "anthropic": ["anthropic:work", "anthropic:default"] },
+            "lastGood": { "anthropic": "anthropic:work" },
+            "usageStats": { "anthropic:work": { "lastUsed": 1 } }
+        }))
+        .unwrap();
+
+        // The order in auth-profiles.json wins over `auth.order`, as in OpenClaw.
+        assert_eq!(
+            effective_order(&config, &auth, "anthropic"),
+            ["anthropic:work", "anthropic:default", "anthropic:ci"]
+        );
+
+        assert!(openclaw_config::remove_auth_profile(&mut auth, "anthropic:work"));
+        let order = effective_order(&config, &auth, "anthropic");
+        set_stored_order(&mut auth, "anthropic", &order);
+        set_order(&mut config, "anthropic", order);
+
+        let expected = ["anthropic:default", "anthropic:ci"];
+        assert_eq!(
+            config.auth().and_then(|a| a.order.as_ref()).unwrap()["anthropic"],
+            expected
+        );
+        assert_eq!(auth["order"]["anthropic"], serde_json::json!(expected));
+        assert_eq!(auth["lastGood"], serde_json::json!({}));
+        assert_eq!(auth["usageStats"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn keeps_both_orders_in_sync() {
+        let mut config = OpenClawConfig::default();
+        let mut auth: AuthProfiles = serde_json::from_value(serde_json::json!({
+            "profiles": {
+                "openai:a": { "provider": "openai", "key": "sk-1" },
+                "openai:b": { "provider": "openai", "key": "sk-2" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(effective_order(&config, &auth, "openai"), ["openai:a", "openai:b"]);
+
+        let order = vec!["openai:b".to_string(), "openai:a".to_string()];
+        set_stored_order(&mut auth, "openai", &order);
+        set_order(&mut config, "openai", order.clone());
+        assert_eq!(auth["order"]["openai"], serde_json::json!(order));
+        assert_eq!(effective_order(&config, &auth, "openai"), order);
+
+        set_stored_order(&mut auth, "openai", &[]);
+        set_order(&mut config, "openai", Vec::new());
+        assert!(!auth.contains_key("order"));
+        assert!(config.auth.is_none());
+
+        // A flat file without an order is left to `auth.order`.
+        let mut flat: AuthProfiles = serde_json::from_value(serde_json::json!({
+            "openai:a": { "provider": "openai", "key": "sk-1" }
+        }))
+        .unwrap();
+        set_stored_order(&mut flat, "openai", &order);
+        assert!(!flat.contains_key("order"));
+    }
+}
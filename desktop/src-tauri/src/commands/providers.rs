@@ -0,0 +1,590 @@
+use regex::Regex;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use super::openclaw_config::{is_valid_agent_id, OpenClawConfig};
+use super::settings::{Settings, SettingsPatch, SettingsState};
+use super::vault::Vault;
+
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a provider expects the API key on its validation request.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAuth {
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// `x-api-key: <key>` (Anthropic)
+    XApiKey,
+    /// `x-goog-api-key: <key>` (Gemini)
+    GoogApiKey,
+}
+
+/// A provider whose key can be stored in OpenClaw's auth profiles.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProviderSpec {
+    /// Our id, used by the frontend and in vault entries (e.g. "gemini").
+    pub id: String,
+    pub label: String,
+    /// Provider name OpenClaw uses in model refs and auth profiles (e.g. "google").
+    pub openclaw_provider: String,
+    /// auth-profiles.json entry holding the key (e.g. "google:default").
+    pub profile_key: String,
+    /// Regex a well-formed key matches; `None` accepts anything non-empty.
+    pub key_pattern: Option<String>,
+    /// Endpoint that lists models, used to check a key works.
+    pub models_url: Option<String>,
+    pub key_auth: KeyAuth,
+    /// OpenAI-compatible base URL; set for custom providers, which OpenClaw needs
+    /// registered under `models.providers`.
+    pub base_url: Option<String>,
+    pub get_key_url: Option<String>,
+    pub custom: bool,
+}
+
+/// An OpenAI-compatible provider added by the user (e.g. a local vLLM or LiteLLM proxy).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomProvider {
+    pub id: String,
+    pub label: Option<String>,
+    /// Base URL ending in the API version, e.g. "http://localhost:8000/v1".
+    pub base_url: String,
+    pub key_pattern: Option<String>,
+}
+
+struct Builtin {
+    id: &'static str,
+    label: &'static str,
+    openclaw_provider: &'static str,
+    key_pattern: &'static str,
+    models_url: &'static str,
+    key_auth: KeyAuth,
+    get_key_url: &'static str,
+}
+
+const BUILTIN: &[Builtin] = &[
+    Builtin {
+        id: "gemini",
+        label: "Gemini",
+        openclaw_provider: "google",
+        key_pattern: r"^AIza[0-9A-Za-z_\-]{35}$",
+        models_url: "https://generativelanguage.googleapis.com/v1beta/models",
+        key_auth: KeyAuth::GoogApiKey,
+        get_key_url: "https://aistudio.google.com/apikey",
+    },
+    Builtin {
+        id: "openai",
+        label: "OpenAI",
+        openclaw_provider: "openai",
+        key_pattern: r"^sk-[A-Za-z0-9_\-]{20,}$",
+        models_url: "https://api.openai.com/v1/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://platform.openai.com/api-keys",
+    },
+    Builtin {
+        id: "anthropic",
+        label: "Claude (Anthropic)",
+        openclaw_provider: "anthropic",
+        key_pattern: r"^sk-ant-[A-Za-z0-9_\-]{20,}$",
+        models_url: "https://api.anthropic.com/v1/models",
+        key_auth: KeyAuth::XApiKey,
+        get_key_url: "https://console.anthropic.com/settings/keys",
+    },
+    Builtin {
+        id: "openrouter",
+        label: "OpenRouter",
+        openclaw_provider: "openrouter",
+        key_pattern: r"^sk-or-[A-Za-z0-9_\-]{20,}$",
+        // The public model list doesn't need a key; this endpoint does.
+        models_url: "https://openrouter.ai/api/v1/key",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://openrouter.ai/keys",
+    },
+    Builtin {
+        id: "groq",
+        label: "Groq",
+        openclaw_provider: "groq",
+        key_pattern: r"^gsk_[A-Za-z0-9]{20,}$",
+        models_url: "https://api.groq.com/openai/v1/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://console.groq.com/keys",
+    },
+    Builtin {
+        id: "mistral",
+        label: "Mistral",
+        openclaw_provider: "mistral",
+        key_pattern: r"^[A-Za-z0-9]{32}$",
+        models_url: "https://api.mistral.ai/v1/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://console.mistral.ai/api-keys",
+    },
+    Builtin {
+        id: "deepseek",
+        label: "DeepSeek",
+        openclaw_provider: "deepseek",
+        key_pattern: r"^sk-[A-Za-z0-9]{20,}$",
+        models_url: "https://api.deepseek.com/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://platform.deepseek.com/api_keys",
+    },
+    Builtin {
+        id: "xai",
+        label: "xAI (Grok)",
+        openclaw_provider: "xai",
+        key_pattern: r"^xai-[A-Za-z0-9]{20,}$",
+        models_url: "https://api.x.ai/v1/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://console.x.ai",
+    },
+    Builtin {
+        id: "together",
+        label: "Together AI",
+        openclaw_provider: "together",
+        key_pattern: r"^(tgp_v1_[A-Za-z0-9_\-]{20,}|[a-f0-9]{64})$",
+        models_url: "https://api.together.xyz/v1/models",
+        key_auth: KeyAuth::Bearer,
+        get_key_url: "https://api.together.ai/settings/api-keys",
+    },
+];
+
+impl From<&Builtin> for ProviderSpec {
+    fn from(b: &Builtin) -> Self {
+        Self {
+            id: b.id.to_string(),
+            label: b.label.to_string(),
+            openclaw_provider: b.openclaw_provider.to_string(),
+            profile_key: format!("{}:default", b.openclaw_provider),
+            key_pattern: Some(b.key_pattern.to_string()),
+            models_url: Some(b.models_url.to_string()),
+            key_auth: b.key_auth,
+            base_url: None,
+            get_key_url: Some(b.get_key_url.to_string()),
+            custom: false,
+        }
+    }
+}
+
+impl From<&CustomProvider> for ProviderSpec {
+    fn from(c: &CustomProvider) -> Self {
+        let base_url = c.base_url.trim().trim_end_matches('/').to_string();
+        Self {
+            id: c.id.clone(),
+            label: c.label.clone().unwrap_or_else(|| c.id.clone()),
+            openclaw_provider: c.id.clone(),
+            profile_key: format!("{}:default", c.id),
+            key_pattern: c.key_pattern.clone().filter(|p| !p.trim().is_empty()),
+            models_url: Some(format!("{}/models", base_url)),
+            key_auth: KeyAuth::Bearer,
+            base_url: Some(base_url),
+            get_key_url: None,
+            custom: true,
+        }
+    }
+}
+
+impl ProviderSpec {
+    /// OpenClaw only knows custom providers registered under `models.providers`; adds or
+    /// updates that entry. Built-in providers need nothing.
+    pub fn register(&self, config: &mut OpenClawConfig) {
+        let Some(base_url) = &self.base_url else {
+            return;
+        };
+        config
+            .models_mut()
+            .mode
+            .get_or_insert_with(|| "merge".to_string());
+        let entry = config
+            .providers_mut()
+            .entry(self.openclaw_provider.clone())
+            .or_default();
+        entry.base_url = Some(base_url.clone());
+        entry
+            .api
+            .get_or_insert_with(|| "openai-completions".to_string());
+    }
+
+    /// Rejects keys that don't look like this provider's, which usually means a key was
+    /// pasted into the wrong field.
+    pub fn check_key_format(&self, key: &str) -> Result<(), String> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("{} key is empty", self.label));
+        }
+        let Some(pattern) = &self.key_pattern else {
+            return Ok(());
+        };
+        let re = Regex::new(pattern)
+            .map_err(|e| format!("Invalid key pattern for {}: {}", self.label, e))?;
+        if !re.is_match(key) {
+            return Err(format!("This doesn't look like a {} API key", self.label));
+        }
+        Ok(())
+    }
+}
+
+/// Built-in providers followed by the user's custom ones.
+pub fn registry(settings: &Settings) -> Vec<ProviderSpec> {
+    BUILTIN
+        .iter()
+        .map(ProviderSpec::from)
+        .chain(settings.custom_providers.iter().map(ProviderSpec::from))
+        .collect()
+}
+
+pub fn find_provider(settings: &Settings, id: &str) -> Result<ProviderSpec, String> {
+    let id = id.to_lowercase();
+    registry(settings)
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown provider: {}", id))
+}
+
+/// Checks a custom provider before it is stored and returns it with its id normalized.
+pub fn validate_custom(provider: CustomProvider) -> Result<CustomProvider, String> {
+    let provider = CustomProvider {
+        id: provider.id.trim().to_lowercase(),
+        label: provider.label.filter(|l| !l.trim().is_empty()),
+        ..provider
+    };
+    if !is_valid_agent_id(&provider.id) {
+        return Err(format!(
+            "Provider id \"{}\" must be lowercase letters, digits, - or _",
+            provider.id
+        ));
+    }
+    if BUILTIN
+        .iter()
+        .any(|b| b.id == provider.id || b.openclaw_provider == provider.id)
+    {
+        return Err(format!("\"{}\" is a built-in provider", provider.id));
+    }
+    let url = reqwest::Url::parse(provider.base_url.trim())
+        .map_err(|e| format!("Invalid base URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Base URL must be http or https".to_string());
+    }
+    if let Some(pattern) = provider
+        .key_pattern
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        Regex::new(pattern).map_err(|e| format!("Invalid key pattern: {}", e))?;
+    }
+    Ok(provider)
+}
+
+// --- Live key validation ---
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCheckStatus {
+    Valid,
+    /// Rejected by the provider (wrong key, revoked, wrong provider).
+    Invalid,
+    /// Accepted but out of credit or quota, so requests would still fail.
+    NoQuota,
+    /// The provider couldn't be reached or answered with a server error.
+    NetworkError,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyCheck {
+    pub provider: String,
+    pub status: KeyCheckStatus,
+    pub http_status: Option<u16>,
+    pub message: String,
+}
+
+impl KeyCheck {
+    /// Error for commands that refuse to save a key that didn't validate.
+    pub fn into_result(self) -> Result<(), String> {
+        match self.status {
+            KeyCheckStatus::Valid => Ok(()),
+            _ => Err(format!("{} (use force to save it anyway)", self.message)),
+        }
+    }
+}
+
+/// Checks a key with a cheap authenticated request to the provider's models endpoint.
+/// Never fails: problems are reported in the returned status.
+pub async fn check_key(provider: &ProviderSpec, key: &str) -> KeyCheck {
+    let result = |status, http_status: Option<StatusCode>, message: String| KeyCheck {
+        provider: provider.id.clone(),
+        status,
+        http_status: http_status.map(|s| s.as_u16()),
+        message,
+    };
+    if let Err(e) = provider.check_key_format(key) {
+        return result(KeyCheckStatus::Invalid, None, e);
+    }
+    let Some(url) = &provider.models_url else {
+        return result(
+            KeyCheckStatus::Valid,
+            None,
+            format!("{} has no endpoint to check keys against", provider.label),
+        );
+    };
+
+    let client = match Client::builder().timeout(VALIDATION_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return result(KeyCheckStatus::NetworkError, None, e.to_string()),
+    };
+    let key = key.trim();
+    let request = match provider.key_auth {
+        KeyAuth::Bearer => client.get(url).bearer_auth(key),
+        KeyAuth::XApiKey => client
+            .get(url)
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01"),
+        KeyAuth::GoogApiKey => client.get(url).header("x-goog-api-key", key),
+    };
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            return result(
+                KeyCheckStatus::NetworkError,
+                None,
+                format!("Failed to reach {}: {}", provider.label, e),
+            )
+        }
+    };
+
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    let lower = body.to_lowercase();
+    let mentions_quota = ["quota", "credit", "billing", "insufficient", "balance"]
+        .iter()
+        .any(|w| lower.contains(w));
+
+    let (check, message) = if status.is_success() {
+        // OpenRouter's key endpoint reports remaining credit on a successful call.
+        let remaining = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.pointer("/data/limit_remaining").and_then(|r| r.as_f64()));
+        match remaining {
+            Some(r) if r <= 0.0 => (
+                KeyCheckStatus::NoQuota,
+                "Key has no credit left".to_string(),
+            ),
+            _ => (
+                KeyCheckStatus::Valid,
+                format!("{} key is valid", provider.label),
+            ),
+        }
+    } else if status == StatusCode::PAYMENT_REQUIRED
+        || (status == StatusCode::TOO_MANY_REQUESTS && mentions_quota)
+        || (status == StatusCode::FORBIDDEN && mentions_quota)
+    {
+        (
+            KeyCheckStatus::NoQuota,
+            format!("{} key has no quota or credit left", provider.label),
+        )
+    } else if status == StatusCode::TOO_MANY_REQUESTS {
+        // Rate limited, which only happens to keys the provider accepted.
+        (
+            KeyCheckStatus::Valid,
+            format!("{} key is valid (rate limited)", provider.label),
+        )
+    } else if status.is_client_error() {
+        (
+            KeyCheckStatus::Invalid,
+            format!(
+                "{} rejected the key (HTTP {})",
+                provider.label,
+                status.as_u16()
+            ),
+        )
+    } else {
+        (
+            KeyCheckStatus::NetworkError,
+            format!("{} returned HTTP {}", provider.label, status.as_u16()),
+        )
+    };
+    result(check, Some(status), message)
+}
+
+/// Checks a vault key against a provider without saving it anywhere.
+#[tauri::command]
+pub async fn openclaw_validate_api_key(
+    provider: String,
+    key_ref: String,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+) -> Result<KeyCheck, String> {
+    let key = vault.resolve(&key_ref)?;
+    let provider = find_provider(&settings.get(), &provider)?;
+    Ok(check_key(&provider, &key).await)
+}
+
+#[tauri::command]
+pub fn openclaw_providers_list(
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<ProviderSpec>, String> {
+    Ok(registry(&settings.get()))
+}
+
+/// Adds a custom provider, or replaces the one with the same id.
+#[tauri::command]
+pub fn openclaw_custom_provider_save(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    provider: CustomProvider,
+) -> Result<Vec<ProviderSpec>, String> {
+    let provider = validate_custom(provider)?;
+    let mut providers = settings.get().custom_providers;
+    match providers.iter_mut().find(|p| p.id == provider.id) {
+        Some(existing) => *existing = provider,
+        None => providers.push(provider),
+    }
+    let next = settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            custom_providers: Some(providers),
+            ..Default::default()
+        },
+    )?;
+    Ok(registry(&next))
+}
+
+/// Forgets a custom provider. Its key and `models.providers` entry in OpenClaw are kept.
+#[tauri::command]
+pub fn openclaw_custom_provider_delete(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    id: String,
+) -> Result<Vec<ProviderSpec>, String> {
+    let providers = settings
+        .get()
+        .custom_providers
+        .into_iter()
+        .filter(|p| p.id != id)
+        .collect();
+    let next = settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            custom_providers: Some(providers),
+            ..Default::default()
+        },
+    )?;
+    Ok(registry(&next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{closed_addr, MockResponse, MockServer};
+
+    /// A built-in provider whose validation endpoint is `url`.
+    fn provider_at(id: &str, url: &str) -> ProviderSpec {
+        ProviderSpec {
+            models_url: Some(url.to_string()),
+            ..find_provider(&Settings::default(), id).unwrap()
+        }
+    }
+
+    #[test]
+    fn validates_custom_providers() {
+        let custom = |id: &str, base_url: &str| CustomProvider {
+            id: id.to_string(),
+            label: Some(" ".to_string()),
+            base_url: base_url.to_string(),
+            key_pattern: None,
+        };
+        let saved = validate_custom(custom(" Local-LLM ", "http://localhost:8000/v1")).unwrap();
+        assert_eq!(saved.id, "local-llm");
+        assert_eq!(saved.label, None);
+
+        assert!(validate_custom(custom("../x", "http://localhost:8000/v1")).is_err());
+        assert!(validate_custom(custom("openai", "http://localhost:8000/v1")).is_err());
+        assert!(validate_custom(custom("local", "file:///etc/passwd")).is_err());
+        assert!(validate_custom(CustomProvider {
+            key_pattern: Some("(".to_string()),
+            ..custom("local", "http://localhost:8000/v1")
+        })
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn accepts_a_working_key_and_rejects_a_wrong_one() {
+        let server = MockServer::start(|req| {
+            assert_eq!((req.method.as_str(), req.path.as_str()), ("GET", "/openai/v1/models"));
+            assert!(req.body.is_empty());
+            match req.header("authorization") {
+                Some("Bearer gsk_aaaaaaaaaaaaaaaaaaaaaaaa") => {
+                    MockResponse::json(200, serde_json::json!({ "data": [] }))
+                }
+                _ => MockResponse::json(401, serde_json::json!({ "error": "Invalid API Key" })),
+            }
+        })
+        .await;
+        let groq = provider_at("groq", &server.url("/openai/v1/models"));
+
+        let valid = check_key(&groq, "gsk_aaaaaaaaaaaaaaaaaaaaaaaa").await;
+        assert_eq!(valid.status, KeyCheckStatus::Valid);
+        assert_eq!(valid.http_status, Some(200));
+
+        let invalid = check_key(&groq, "gsk_bbbbbbbbbbbbbbbbbbbbbbbb").await;
+        assert_eq!(invalid.status, KeyCheckStatus::Invalid);
+        assert_eq!(invalid.http_status, Some(401));
+        assert!(invalid.into_result().is_err());
+    }
+
+    #[tokio::test]
+    async fn sends_anthropic_keys_as_x_api_key() {
+        let server = MockServer::start(|req| {
+            if req.header("x-api-key") == Some("sk-ant-REDACTED")
+                && req.header("anthropic-version").is_some()
+            {
+                MockResponse::json(200, serde_json::json!({ "data": [] }))
+            } else {
+                MockResponse::json(401, serde_json::json!({ "type": "error" }))
+            }
+        })
+        .await;
+        let anthropic = provider_at("anthropic", &server.url("/v1/models"));
+
+        let check = check_key(&anthropic, "sk-ant-REDACTED").await;
+        assert_eq!(check.status, KeyCheckStatus::Valid);
+    }
+
+    #[tokio::test]
+    async fn reports_keys_without_credit() {
+        let server = MockServer::start(|_| {
+            MockResponse::json(200, serde_json::json!({ "data": { "limit_remaining": 0 } }))
+        })
+        .await;
+        let openrouter = provider_at("openrouter", &server.url("/api/v1/key"));
+
+        let check = check_key(&openrouter, "sk-or-aaaaaaaaaaaaaaaaaaaaaaaa").await;
+        assert_eq!(check.status, KeyCheckStatus::NoQuota);
+    }
+
+    #[tokio::test]
+    async fn reports_an_unreachable_or_failing_provider_as_a_network_error() {
+        let xai = provider_at("xai", &format!("http://{}/v1/models", closed_addr().await));
+        let check = check_key(&xai, "xai-aaaaaaaaaaaaaaaaaaaaaaaa").await;
+        assert_eq!(check.status, KeyCheckStatus::NetworkError);
+        assert_eq!(check.http_status, None);
+
+        let server = MockServer::start(|_| MockResponse::new(503, "upstream down")).await;
+        let deepseek = provider_at("deepseek", &server.url("/models"));
+        let check = check_key(&deepseek, "sk-aaaaaaaaaaaaaaaaaaaaaaaa").await;
+        assert_eq!(check.status, KeyCheckStatus::NetworkError);
+        assert_eq!(check.http_status, Some(503));
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_keys_without_a_request() {
+        let groq = find_provider(&Settings::default(), "groq").unwrap();
+        let check = check_key(&groq, "not-a-groq-key").await;
+        assert_eq!(check.status, KeyCheckStatus::Invalid);
+        assert_eq!(check.http_status, None);
+    }
+}
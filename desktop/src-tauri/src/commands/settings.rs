@@ -0,0 +1,455 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_store::StoreExt;
+
+use super::openclaw_paths::{validate_profile, OpenClawPaths, OpenClawProfile};
+use super::providers::{validate_custom, CustomProvider};
+use super::vault::{now_millis, Vault};
+
+pub const SETTINGS_STORE: &str = "settings.json";
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+/// "simple" = quick/cheap, "standard" = balanced, "complex" = most capable
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskComplexity {
+    #[default]
+    Simple,
+    Standard,
+    Complex,
+}
+
+/// App settings persisted in the `settings.json` store. Field names match the store keys
+/// written by earlier frontend-only versions so existing files load unchanged.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub schema_version: u32,
+    pub theme: Theme,
+    /// `None` means "use the built-in prompt".
+    pub system_prompt: Option<String>,
+    pub task_complexity: TaskComplexity,
+    /// Vault reference for the OpenRouter key used by chat, diagnostics and OpenClaw setup.
+    pub openrouter_key_ref: Option<String>,
+    pub openclaw_profiles: Vec<OpenClawProfile>,
+    /// `None` uses the built-in profile (`OPENCLAW_STATE_DIR` or `~/.openclaw`).
+    pub active_openclaw_profile: Option<String>,
+    /// OpenAI-compatible providers added alongside the built-in registry.
+    pub custom_providers: Vec<CustomProvider>,
+    /// Restart the gateway automatically when it goes down. `None` means on.
+    pub gateway_auto_restart: Option<bool>,
+}
+
+/// Partial update for `settings_update`. Omitted fields are left unchanged; nullable fields
+/// are cleared by sending `null`.
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SettingsPatch {
+    pub theme: Option<Theme>,
+    #[serde(deserialize_with = "present")]
+    pub system_prompt: Option<Option<String>>,
+    pub task_complexity: Option<TaskComplexity>,
+    #[serde(deserialize_with = "present")]
+    pub openrouter_key_ref: Option<Option<String>>,
+    pub openclaw_profiles: Option<Vec<OpenClawProfile>>,
+    #[serde(deserialize_with = "present")]
+    pub active_openclaw_profile: Option<Option<String>>,
+    pub custom_providers: Option<Vec<CustomProvider>>,
+    pub gateway_auto_restart: Option<bool>,
+}
+
+/// Distinguishes "field sent as null" (`Some(None)`) from "field omitted" (`None`).
+fn present<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+/// A store migration upgrades the raw key/value map from one schema version to the next.
+/// `MIGRATIONS[n]` takes a store at version `n` to version `n + 1`.
+type Migration = fn(&mut serde_json::Map<String, serde_json::Value>, &Vault) -> Result<(), String>;
+
+const MIGRATIONS: &[Migration] = &[migrate_v0_plaintext_key];
+
+/// v0 -> v1: earlier versions kept the OpenRouter key in plaintext under `openrouterApiKey`.
+/// Move it into the vault and keep only its reference.
+fn migrate_v0_plaintext_key(
+    entries: &mut serde_json::Map<String, serde_json::Value>,
+    vault: &Vault,
+) -> Result<(), String> {
+    let Some(value) = entries.remove("openrouterApiKey") else {
+        return Ok(());
+    };
+    if let Some(key) = value.as_str().filter(|k| !k.trim().is_empty()) {
+        let summary = vault.put("openrouter", "default", key)?;
+        entries.insert(
+            "openrouterKeyRef".to_string(),
+            serde_json::json!(summary.key_ref),
+        );
+    }
+    Ok(())
+}
+
+/// Settings cached in managed state. Commands read from here rather than taking values
+/// from the UI on every call.
+#[derive(Default)]
+pub struct SettingsState {
+    settings: RwLock<Settings>,
+    /// Why the store couldn't be loaded. Until the first update backs it up, the store still
+    /// holds the user's settings while this state runs on defaults.
+    load_error: RwLock<Option<String>>,
+}
+
+impl SettingsState {
+    /// Loads settings from the store, running any pending schema migrations first.
+    pub fn load(app: &AppHandle, vault: &Vault) -> Result<Self, String> {
+        let store = app
+            .store(SETTINGS_STORE)
+            .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+        let mut entries: serde_json::Map<String, serde_json::Value> =
+            store.entries().into_iter().collect();
+        let before: Vec<String> = entries.keys().cloned().collect();
+        if migrate(&mut entries, vault)? {
+            for key in before.iter().filter(|k| !entries.contains_key(*k)) {
+                store.delete(key);
+            }
+            for (key, value) in &entries {
+                store.set(key.clone(), value.clone());
+            }
+            store
+                .save()
+                .map_err(|e| format!("Failed to save settings store: {}", e))?;
+        }
+
+        let settings = parse(entries)?;
+        Ok(Self {
+            settings: RwLock::new(settings),
+            load_error: RwLock::new(None),
+        })
+    }
+
+    /// Defaults, for when `load` fails. The error is kept for the UI.
+    pub fn failed(error: String) -> Self {
+        Self {
+            settings: RwLock::new(Settings::default()),
+            load_error: RwLock::new(Some(error)),
+        }
+    }
+
+    pub fn load_error(&self) -> Option<String> {
+        self.load_error
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Resolves the configured OpenRouter key from the vault.
+    pub fn openrouter_key(&self, vault: &Vault) -> Result<String, String> {
+        let key_ref = self
+            .get()
+            .openrouter_key_ref
+            .ok_or("No OpenRouter API key configured — add one in Settings")?;
+        vault.resolve(&key_ref)
+    }
+
+    /// Paths of the active OpenClaw profile.
+    pub fn paths(&self) -> OpenClawPaths {
+        let settings = self.get();
+        let profile = settings
+            .active_openclaw_profile
+            .as_ref()
+            .and_then(|name| settings.openclaw_profiles.iter().find(|p| &p.name == name));
+        OpenClawPaths::resolve(profile)
+    }
+
+    /// Applies `patch`, writes the result to the store and emits `settings-changed`.
+    pub fn update(
+        &self,
+        app: &AppHandle,
+        vault: &Vault,
+        patch: SettingsPatch,
+    ) -> Result<Settings, String> {
+        let mut next = self.get();
+        if let Some(theme) = patch.theme {
+            next.theme = theme;
+        }
+        if let Some(prompt) = patch.system_prompt {
+            next.system_prompt = prompt.filter(|p| !p.trim().is_empty());
+        }
+        if let Some(complexity) = patch.task_complexity {
+            next.task_complexity = complexity;
+        }
+        if let Some(key_ref) = patch.openrouter_key_ref {
+            if let Some(ref r) = key_ref {
+                vault.resolve(r)?;
+            }
+            next.openrouter_key_ref = key_ref;
+        }
+        if let Some(profiles) = patch.openclaw_profiles {
+            let profiles = profiles
+                .into_iter()
+                .map(validate_profile)
+                .collect::<Result<Vec<_>, _>>()?;
+            for (i, profile) in profiles.iter().enumerate() {
+                if profiles[..i].iter().any(|p| p.name == profile.name) {
+                    return Err(format!("Duplicate OpenClaw profile: {}", profile.name));
+                }
+            }
+            next.openclaw_profiles = profiles;
+        }
+        if let Some(active) = patch.active_openclaw_profile {
+            if let Some(ref name) = active {
+                if !next.openclaw_profiles.iter().any(|p| &p.name == name) {
+                    return Err(format!("Unknown OpenClaw profile: {}", name));
+                }
+            }
+            next.active_openclaw_profile = active;
+        }
+        if let Some(providers) = patch.custom_providers {
+            let providers = providers
+                .into_iter()
+                .map(validate_custom)
+                .collect::<Result<Vec<_>, _>>()?;
+            for (i, provider) in providers.iter().enumerate() {
+                if providers[..i].iter().any(|p| p.id == provider.id) {
+                    return Err(format!("Duplicate custom provider: {}", provider.id));
+                }
+            }
+            next.custom_providers = providers;
+        }
+        if let Some(auto_restart) = patch.gateway_auto_restart {
+            next.gateway_auto_restart = Some(auto_restart);
+        }
+        next.schema_version = CURRENT_SCHEMA_VERSION;
+
+        self.back_up_unloaded(&store_path(app)?)?;
+        let store = app
+            .store(SETTINGS_STORE)
+            .map_err(|e| format!("Failed to open settings store: {}", e))?;
+        let serde_json::Value::Object(entries) = serde_json::to_value(&next)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?
+        else {
+            return Err("Settings must serialize to an object".to_string());
+        };
+        for (key, value) in entries {
+            store.set(key, value);
+        }
+        store
+            .save()
+            .map_err(|e| format!("Failed to save settings store: {}", e))?;
+
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = next.clone();
+        let _ = app.emit("settings-changed", &next);
+        Ok(next)
+    }
+
+    /// Writing defaults over a store that failed to load would lose it, so the first update
+    /// after a failed load copies the store at `path` to `settings.json.unloaded-<millis>`.
+    fn back_up_unloaded(&self, path: &Path) -> Result<(), String> {
+        if self.load_error().is_none() {
+            return Ok(());
+        }
+        if path.exists() {
+            let mut backup = path.to_path_buf().into_os_string();
+            backup.push(format!(".unloaded-{}", now_millis()));
+            std::fs::copy(path, &backup)
+                .map_err(|e| format!("Failed to back up the settings store: {}", e))?;
+        }
+        *self.load_error.write().unwrap_or_else(|e| e.into_inner()) = None;
+        Ok(())
+    }
+}
+
+/// Runs the migrations a store at an older schema version needs. Returns whether any ran,
+/// in which case the entries need saving.
+fn migrate(
+    entries: &mut serde_json::Map<String, serde_json::Value>,
+    vault: &Vault,
+) -> Result<bool, String> {
+    let stored_version = entries
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if stored_version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Settings schema version {} is newer than this app supports",
+            stored_version
+        ));
+    }
+    if stored_version == CURRENT_SCHEMA_VERSION {
+        return Ok(false);
+    }
+    for migration in &MIGRATIONS[stored_version as usize..] {
+        migration(entries, vault)?;
+    }
+    entries.insert(
+        SCHEMA_VERSION_KEY.to_string(),
+        serde_json::json!(CURRENT_SCHEMA_VERSION),
+    );
+    Ok(true)
+}
+
+fn parse(entries: serde_json::Map<String, serde_json::Value>) -> Result<Settings, String> {
+    serde_json::from_value(serde_json::Value::Object(entries))
+        .map_err(|e| format!("Invalid settings: {}", e))
+}
+
+fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to find the app data directory: {}", e))?
+        .join(SETTINGS_STORE))
+}
+
+#[tauri::command]
+pub fn settings_get(settings: State<'_, SettingsState>) -> Result<Settings, String> {
+    Ok(settings.get())
+}
+
+/// Why settings couldn't be loaded at startup, if they couldn't. The app then runs on
+/// defaults, and the store is backed up before the first change is saved over it.
+#[tauri::command]
+pub fn settings_load_error(settings: State<'_, SettingsState>) -> Result<Option<String>, String> {
+    Ok(settings.load_error())
+}
+
+#[tauri::command]
+pub fn settings_update(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    patch: SettingsPatch,
+) -> Result<Settings, String> {
+    settings.update(&app, &vault, patch)
+}
+
+/// Stores a new OpenRouter key in the vault and points settings at it. This is the only
+/// point where the raw key crosses from the UI to the backend.
+#[tauri::command]
+pub fn settings_set_openrouter_key(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, Vault>,
+    key: String,
+) -> Result<Settings, String> {
+    let summary = vault.put("openrouter", "default", &key)?;
+    settings.update(
+        &app,
+        &vault,
+        SettingsPatch {
+            openrouter_key_ref: Some(Some(summary.key_ref)),
+            ..Default::default()
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::vault::random_id;
+
+    /// A directory for a test's vault and store, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(random_id("settings-test"));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn entries(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        match value {
+            serde_json::Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn moves_a_plaintext_key_into_the_vault() {
+        let dir = TempDir::new();
+        let vault = Vault::open(dir.0.join("vault.json"));
+        let mut store = entries(serde_json::json!({
+            "theme": "dark",
+            "openrouterApiKey": "sk-or-v1-abcd"
+        }));
+
+        assert!(migrate(&mut store, &vault).unwrap());
+        assert!(!store.contains_key("openrouterApiKey"));
+        assert!(!serde_json::to_string(&store).unwrap().contains("sk-or-v1"));
+        let settings = parse(store.clone()).unwrap();
+        assert_eq!(settings.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(settings.theme, Theme::Dark);
+        let key_ref = settings.openrouter_key_ref.unwrap();
+        assert_eq!(vault.resolve(&key_ref).unwrap(), "sk-or-v1-abcd");
+
+        // A migrated store is left alone.
+        assert!(!migrate(&mut store, &vault).unwrap());
+    }
+
+    #[test]
+    fn refuses_stores_from_newer_versions() {
+        let dir = TempDir::new();
+        let vault = Vault::open(dir.0.join("vault.json"));
+        let mut store = entries(serde_json::json!({ "schemaVersion": CURRENT_SCHEMA_VERSION + 1 }));
+        assert!(migrate(&mut store, &vault).is_err());
+        assert!(parse(entries(serde_json::json!({ "theme": "sepia" }))).is_err());
+    }
+
+    #[test]
+    fn backs_up_a_store_that_failed_to_load_once() {
+        let dir = TempDir::new();
+        let path = dir.0.join(SETTINGS_STORE);
+        std::fs::write(&path, r#"{ "theme": "sepia" }"#).unwrap();
+        let state = SettingsState::failed("Invalid settings".to_string());
+        let backups = || -> Vec<PathBuf> {
+            std::fs::read_dir(&dir.0)
+                .unwrap()
+                .map(|e| e.unwrap().path())
+                .filter(|p| p.to_string_lossy().contains(".unloaded-"))
+                .collect()
+        };
+
+        state.back_up_unloaded(&path).unwrap();
+        assert_eq!(state.load_error(), None);
+        let taken = backups();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&taken[0]).unwrap(),
+            r#"{ "theme": "sepia" }"#
+        );
+
+        state.back_up_unloaded(&path).unwrap();
+        assert_eq!(backups().len(), 1);
+        assert_eq!(state.get(), Settings::default());
+    }
+}
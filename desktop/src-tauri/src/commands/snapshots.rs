@@ -0,0 +1,1042 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents;
+use super::config_files;
+use super::gateway::{self, ProbeResult};
+use super::gateway_token;
+use super::openclaw::spawn_and_stream;
+use super::openclaw_config::{self, OpenClawConfig};
+use super::openclaw_paths::OpenClawPaths;
+use super::openclaw_version;
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+use super::vault::{derive_key, now_millis, random_bytes};
+
+/// First line of every snapshot file.
+const MAGIC: &[u8] = b"OPENCLAW-SNAPSHOT\n";
+const SNAPSHOT_VERSION: u32 = 1;
+const EXTENSION: &str = "ocsnap";
+/// Top-level directory of the archived state inside the tarball.
+const ROOT: &str = "openclaw";
+/// Plaintext bytes per encrypted chunk.
+const CHUNK: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+const NONCE_PREFIX_LEN: usize = 7;
+const MAX_HEADER_LEN: u64 = 64 * 1024;
+
+// --- Types ---
+
+/// Key derivation and nonce prefix of an encrypted snapshot.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Encryption {
+    salt: String,
+    nonce_prefix: String,
+}
+
+/// On-disk layout: `MAGIC`, this header as one line of JSON, then a gzipped tarball of the
+/// state directory, encrypted in chunks when `encryption` is set.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SnapshotHeader {
+    version: u32,
+    profile: String,
+    /// State directory the snapshot was taken from.
+    home: String,
+    created_at: u64,
+    label: Option<String>,
+    openclaw_version: Option<String>,
+    encryption: Option<Encryption>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub path: String,
+    pub profile: String,
+    pub home: String,
+    pub created_at: u64,
+    pub size: u64,
+    pub label: Option<String>,
+    pub openclaw_version: Option<String>,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotRestore {
+    pub restored: SnapshotInfo,
+    /// Snapshot of the state that was replaced.
+    pub previous: Option<SnapshotInfo>,
+    pub gateway_restarted: bool,
+}
+
+// --- Chunked encryption ---
+//
+// AES-256-GCM over fixed-size chunks. Each chunk's nonce is the random prefix, a counter and
+// a last-chunk flag, so reordered, dropped or truncated chunks fail to decrypt.
+
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+struct EncryptWriter<W: Write> {
+    inner: W,
+    cipher: Aes256Gcm,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> EncryptWriter<W> {
+    fn new(inner: W, key: &[u8; 32], prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            inner,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            prefix,
+            counter: 0,
+            buf: Vec::with_capacity(CHUNK),
+        }
+    }
+
+    fn seal(&mut self, len: usize, last: bool) -> io::Result<()> {
+        let nonce = chunk_nonce(&self.prefix, self.counter, last);
+        let chunk: Vec<u8> = self.buf.drain(..len).collect();
+        let sealed = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), chunk.as_ref())
+            .map_err(|_| io::Error::other("Failed to encrypt snapshot"))?;
+        self.inner.write_all(&sealed)?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("Snapshot is too large to encrypt"))?;
+        Ok(())
+    }
+
+    /// Seals the final chunk, which may be empty.
+    fn finish(mut self) -> io::Result<W> {
+        self.seal(self.buf.len(), true)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        // A full chunk is held back until more data shows it isn't the last one.
+        while self.buf.len() > CHUNK {
+            self.seal(CHUNK, false)?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct DecryptReader<R: Read> {
+    inner: R,
+    cipher: Aes256Gcm,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    pending: Vec<u8>,
+    plain: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> DecryptReader<R> {
+    fn new(inner: R, key: &[u8; 32], prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            inner,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            prefix,
+            counter: 0,
+            pending: Vec::new(),
+            plain: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let sealed_len = CHUNK + TAG_LEN;
+        // Reading one byte past a full chunk tells whether it is the last one.
+        let want = (sealed_len + 1).saturating_sub(self.pending.len());
+        self.inner
+            .by_ref()
+            .take(want as u64)
+            .read_to_end(&mut self.pending)?;
+        let last = self.pending.len() <= sealed_len;
+        let len = self.pending.len().min(sealed_len);
+        let chunk: Vec<u8> = self.pending.drain(..len).collect();
+        let nonce = chunk_nonce(&self.prefix, self.counter, last);
+        self.plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), chunk.as_ref())
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Failed to decrypt snapshot — wrong passphrase or damaged file",
+                )
+            })?;
+        self.pos = 0;
+        self.counter = self.counter.wrapping_add(1);
+        self.done = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plain.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = out.len().min(self.plain.len() - self.pos);
+        out[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+enum Sink {
+    Plain(File),
+    Encrypted(Box<EncryptWriter<File>>),
+}
+
+impl Sink {
+    fn finish(self) -> io::Result<File> {
+        match self {
+            Sink::Plain(file) => Ok(file),
+            Sink::Encrypted(writer) => writer.finish(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(file) => file.write(data),
+            Sink::Encrypted(writer) => writer.write(data),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(file) => file.flush(),
+            Sink::Encrypted(writer) => writer.flush(),
+        }
+    }
+}
+
+fn decode_prefix(encoded: &str) -> Result<[u8; NONCE_PREFIX_LEN], String> {
+    BASE64
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "Snapshot header is corrupted".to_string())
+}
+
+// --- Storage ---
+
+/// Directory snapshots are kept in, outside the state directory they capture.
+pub fn snapshots_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("snapshots"))
+        .map_err(|e| format!("Failed to find the app data directory: {}", e))
+}
+
+/// A new snapshot id such as "1760000000000-default".
+pub fn new_id(profile: &str, created_at: u64) -> String {
+    let profile: String = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}", created_at, profile)
+}
+
+pub fn snapshot_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+    Ok(dir.join(format!("{}.{}", id, EXTENSION)))
+}
+
+/// Opens a snapshot and reads its header, leaving the reader at the payload.
+fn open(path: &Path) -> Result<(SnapshotHeader, BufReader<File>), String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut magic = vec![0u8; MAGIC.len()];
+    if reader.read_exact(&mut magic).is_err() || magic != MAGIC {
+        return Err(format!("{} is not an OpenClaw snapshot", path.display()));
+    }
+    let mut line = String::new();
+    reader
+        .by_ref()
+        .take(MAX_HEADER_LEN)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let header: SnapshotHeader =
+        serde_json::from_str(&line).map_err(|e| format!("Snapshot header is corrupted: {}", e))?;
+    if header.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "This snapshot was made by a newer version of the app (format {})",
+            header.version
+        ));
+    }
+    Ok((header, reader))
+}
+
+fn info(path: &Path, header: &SnapshotHeader) -> SnapshotInfo {
+    SnapshotInfo {
+        id: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        profile: header.profile.clone(),
+        home: header.home.clone(),
+        created_at: header.created_at,
+        size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        label: header.label.clone(),
+        openclaw_version: header.openclaw_version.clone(),
+        encrypted: header.encryption.is_some(),
+    }
+}
+
+pub fn read_info(path: &Path) -> Result<SnapshotInfo, String> {
+    open(path).map(|(header, _)| info(path, &header))
+}
+
+/// Snapshots in `dir`, newest first. Unreadable files are skipped.
+pub fn list(dir: &Path) -> Result<Vec<SnapshotInfo>, String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == EXTENSION))
+        .filter_map(|path| read_info(&path).ok())
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    Ok(snapshots)
+}
+
+// --- Create ---
+
+/// Adds `dir` to the archive as `name`. Symlinks are stored as links; sockets and other
+/// special files (such as a running gateway's) are skipped.
+fn append_tree<W: Write>(tar: &mut tar::Builder<W>, dir: &Path, name: &Path) -> io::Result<()> {
+    tar.append_dir(name, dir)?;
+    let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let entry_name = name.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        let result = if file_type.is_dir() {
+            append_tree(tar, &path, &entry_name)
+        } else if file_type.is_file() || file_type.is_symlink() {
+            tar.append_path_with_name(&path, &entry_name)
+        } else {
+            Ok(())
+        };
+        match result {
+            // Removed while we were archiving, e.g. a temp file.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            other => other?,
+        }
+    }
+    Ok(())
+}
+
+fn write_snapshot(
+    dest: &Path,
+    paths: &OpenClawPaths,
+    mut header: SnapshotHeader,
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let mut file =
+        File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    // Snapshots hold credentials.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = file.set_permissions(std::fs::Permissions::from_mode(0o600));
+    }
+
+    let key = match passphrase {
+        Some(passphrase) => {
+            let salt = random_bytes(16);
+            let prefix: [u8; NONCE_PREFIX_LEN] = random_bytes(NONCE_PREFIX_LEN)
+                .try_into()
+                .map_err(|_| "Failed to generate a nonce".to_string())?;
+            header.encryption = Some(Encryption {
+                salt: BASE64.encode(&salt),
+                nonce_prefix: BASE64.encode(prefix),
+            });
+            Some((derive_key(passphrase.as_bytes(), &salt)?, prefix))
+        }
+        None => None,
+    };
+    let line = serde_json::to_string(&header)
+        .map_err(|e| format!("Failed to serialize snapshot header: {}", e))?;
+    file.write_all(MAGIC)
+        .and_then(|_| file.write_all(line.as_bytes()))
+        .and_then(|_| file.write_all(b"\n"))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+
+    let sink = match key {
+        Some((key, prefix)) => Sink::Encrypted(Box::new(EncryptWriter::new(file, &key, prefix))),
+        None => Sink::Plain(file),
+    };
+    let mut tar = tar::Builder::new(GzEncoder::new(sink, Compression::default()));
+    tar.follow_symlinks(false);
+    append_tree(&mut tar, &paths.home, Path::new(ROOT))
+        .map_err(|e| format!("Failed to archive {}: {}", paths.home.display(), e))?;
+    tar.into_inner()
+        .and_then(|gz| gz.finish())
+        .and_then(|sink| sink.finish())
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))
+}
+
+/// Archives the profile's state directory to `dest`. Blocks; run it off the async runtime.
+pub fn create(
+    dest: &Path,
+    paths: &OpenClawPaths,
+    label: Option<String>,
+    openclaw_version: Option<String>,
+) -> Result<SnapshotInfo, String> {
+    create_snapshot(dest, paths, label, None, openclaw_version)
+}
+
+/// Like `create`, with the archive encrypted under a key derived from `passphrase`.
+pub fn create_encrypted(
+    dest: &Path,
+    paths: &OpenClawPaths,
+    label: Option<String>,
+    passphrase: &str,
+    openclaw_version: Option<String>,
+) -> Result<SnapshotInfo, String> {
+    create_snapshot(dest, paths, label, Some(passphrase), openclaw_version)
+}
+
+fn create_snapshot(
+    dest: &Path,
+    paths: &OpenClawPaths,
+    label: Option<String>,
+    passphrase: Option<&str>,
+    openclaw_version: Option<String>,
+) -> Result<SnapshotInfo, String> {
+    if !paths.home.is_dir() {
+        return Err(format!(
+            "Nothing to snapshot: {} doesn't exist",
+            paths.home.display()
+        ));
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let header = SnapshotHeader {
+        version: SNAPSHOT_VERSION,
+        profile: paths.profile.clone(),
+        home: paths.home.to_string_lossy().to_string(),
+        created_at: now_millis(),
+        label: label.filter(|l| !l.trim().is_empty()),
+        openclaw_version,
+        encryption: None,
+    };
+
+    let tmp = dest.with_extension(format!("{}.tmp", EXTENSION));
+    if let Err(e) = write_snapshot(&tmp, paths, header, passphrase) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, dest)
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    read_info(dest)
+}
+
+// --- Restore ---
+
+/// A directory removed when dropped.
+struct StagingDir(PathBuf);
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A hidden sibling of the state directory, on the same filesystem so it can be renamed
+/// into place.
+fn sibling(home: &Path, tag: &str) -> PathBuf {
+    let name = home
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| ROOT.to_string());
+    home.with_file_name(format!(".{}.{}-{}", name, tag, now_millis()))
+}
+
+fn unpack(path: &Path, passphrase: Option<&str>, dest: &Path) -> Result<SnapshotHeader, String> {
+    let (header, reader) = open(path)?;
+    let payload: Box<dyn Read> = match &header.encryption {
+        Some(encryption) => {
+            let passphrase =
+                passphrase.ok_or("This snapshot is encrypted; enter its passphrase")?;
+            let salt = BASE64
+                .decode(&encryption.salt)
+                .map_err(|_| "Snapshot header is corrupted".to_string())?;
+            let key = derive_key(passphrase.as_bytes(), &salt)?;
+            let prefix = decode_prefix(&encryption.nonce_prefix)?;
+            let mut decrypted = DecryptReader::new(reader, &key, prefix);
+            // A wrong passphrase fails on the first chunk; check it before tar hides the cause.
+            decrypted.next_chunk().map_err(|e| e.to_string())?;
+            Box::new(decrypted)
+        }
+        None => Box::new(reader),
+    };
+    let mut archive = tar::Archive::new(GzDecoder::new(payload));
+    archive.set_preserve_permissions(true);
+    archive
+        .unpack(dest)
+        .map_err(|e| format!("Failed to unpack {}: {}", path.display(), e))?;
+    Ok(header)
+}
+
+/// Replaces a leading `from` in every path-like string under `value` with `to`. Whole
+/// components only, so "/a/.openclaw-work" isn't taken for a path under "/a/.openclaw".
+/// Returns whether anything changed.
+fn relocate_paths(value: &mut serde_json::Value, from: &Path, to: &Path) -> bool {
+    match value {
+        serde_json::Value::String(text) => match Path::new(text.as_str()).strip_prefix(from) {
+            Ok(rest) => {
+                *text = to.join(rest).to_string_lossy().to_string();
+                true
+            }
+            Err(_) => false,
+        },
+        serde_json::Value::Array(items) => {
+            let mut changed = false;
+            for item in items {
+                changed |= relocate_paths(item, from, to);
+            }
+            changed
+        }
+        serde_json::Value::Object(map) => {
+            let mut changed = false;
+            for item in map.values_mut() {
+                changed |= relocate_paths(item, from, to);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+/// Points absolute paths in the restored config and auth profiles (such as agent
+/// workspaces) at `paths.home` when the snapshot came from another state directory, e.g.
+/// on another machine. `staged` is the unpacked state directory; files are rewritten
+/// through the config layer, so each is backed up there and read back.
+fn relocate_config(staged: &Path, from: &str, paths: &OpenClawPaths) -> Result<(), String> {
+    let from = Path::new(from);
+    let staged_paths = OpenClawPaths {
+        home: staged.to_path_buf(),
+        ..paths.clone()
+    };
+    if from.as_os_str().is_empty() || from == paths.home || !staged_paths.config_path().is_file() {
+        return Ok(());
+    }
+
+    let config = openclaw_config::load_config(&staged_paths)?;
+    let mut value =
+        serde_json::to_value(&config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let config = if relocate_paths(&mut value, from, &paths.home) {
+        let relocated: OpenClawConfig = serde_json::from_value(value)
+            .map_err(|e| format!("Relocated config has an invalid structure: {}", e))?;
+        openclaw_config::save_config(&staged_paths, &relocated)?;
+        relocated
+    } else {
+        config
+    };
+
+    // Agent dirs are resolved where they will end up, then mapped into the staged copy;
+    // ones outside the state directory aren't part of the snapshot.
+    let agent_ids = config
+        .agent_list()
+        .iter()
+        .map(|a| a.id.clone())
+        .chain([config.default_agent_id()]);
+    let auth_files: BTreeSet<PathBuf> = agent_ids
+        .filter_map(|id| {
+            let dir = agents::agent_dir(paths, &config, &id);
+            let rest = dir.strip_prefix(&paths.home).ok()?;
+            Some(staged.join(rest).join("auth-profiles.json"))
+        })
+        .filter(|path| path.is_file())
+        .collect();
+    for path in auth_files {
+        let mut value = serde_json::Value::Object(openclaw_config::load_auth_profiles(&path)?);
+        if relocate_paths(&mut value, from, &paths.home) {
+            config_files::write_json_with_rollback(&staged_paths, &path, &value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the state directory with `staged`, putting the old one back if the move fails.
+fn swap_in(home: &Path, staged: &Path) -> Result<(), String> {
+    let aside = sibling(home, "replaced");
+    if home.exists() {
+        std::fs::rename(home, &aside)
+            .map_err(|e| format!("Failed to move {} aside: {}", home.display(), e))?;
+    }
+    if let Err(e) = std::fs::rename(staged, home) {
+        if aside.exists() {
+            let _ = std::fs::rename(&aside, home);
+        }
+        return Err(format!("Failed to restore {}: {}", home.display(), e));
+    }
+    let _ = std::fs::remove_dir_all(&aside);
+    Ok(())
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub fn openclaw_snapshots_list(app: AppHandle) -> Result<Vec<SnapshotInfo>, String> {
+    list(&snapshots_dir(&app)?)
+}
+
+/// Snapshots the active profile's state directory: config, auth profiles, agent
+/// workspaces and WhatsApp session credentials. With a passphrase the archive is
+/// encrypted and can't be restored without it.
+#[tauri::command]
+pub async fn openclaw_snapshot_create(
+    app: AppHandle,
+    label: Option<String>,
+    passphrase: Option<String>,
+    settings: State<'_, SettingsState>,
+) -> Result<SnapshotInfo, String> {
+    let paths = settings.paths();
+    let dir = snapshots_dir(&app)?;
+    let _claim = operations::claim(&app, "Creating a snapshot", &[ExclusionGroup::Config])?;
+
+    let version = openclaw_version::check(&paths).await.version;
+    let dest = snapshot_path(&dir, &new_id(&paths.profile, now_millis()))?;
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    tauri::async_runtime::spawn_blocking(move || match passphrase {
+        Some(passphrase) => create_encrypted(&dest, &paths, label, &passphrase, version),
+        None => create(&dest, &paths, label, version),
+    })
+    .await
+    .map_err(|e| format!("Snapshot failed: {}", e))?
+}
+
+/// Copies a snapshot file, e.g. one brought over from another machine, into the snapshot
+/// directory.
+#[tauri::command]
+pub fn openclaw_snapshot_import(app: AppHandle, path: String) -> Result<SnapshotInfo, String> {
+    let source = PathBuf::from(&path);
+    let (header, _) = open(&source)?;
+    let dest = snapshot_path(
+        &snapshots_dir(&app)?,
+        &new_id(&header.profile, header.created_at),
+    )?;
+    if dest.exists() {
+        return Err("This snapshot has already been imported".to_string());
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::copy(&source, &dest).map_err(|e| format!("Failed to import {}: {}", path, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(0o600));
+    }
+    read_info(&dest)
+}
+
+#[tauri::command]
+pub fn openclaw_snapshot_delete(app: AppHandle, id: String) -> Result<(), String> {
+    let path = snapshot_path(&snapshots_dir(&app)?, &id)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot {}: {}", id, e))
+}
+
+/// Replaces the active profile's state directory with a snapshot. The snapshot is
+/// unpacked first, then the local gateway is stopped, the current state is snapshotted
+/// and swapped out, the services are given the restored gateway token, and the gateway is
+/// started again if it was running.
+#[tauri::command]
+pub async fn openclaw_snapshot_restore(
+    app: AppHandle,
+    id: String,
+    passphrase: Option<String>,
+    settings: State<'_, SettingsState>,
+) -> Result<SnapshotRestore, String> {
+    let paths = settings.paths();
+    let dir = snapshots_dir(&app)?;
+    let path = snapshot_path(&dir, &id)?;
+    let restored = read_info(&path)?;
+    if restored.encrypted && passphrase.is_none() {
+        return Err("This snapshot is encrypted; enter its passphrase".to_string());
+    }
+    let claim = operations::claim(
+        &app,
+        "Restoring a snapshot",
+        &[ExclusionGroup::Config, ExclusionGroup::Gateway],
+    )?;
+
+    claim.step("Unpacking the snapshot");
+    let staging = StagingDir(sibling(&paths.home, "restoring"));
+    let (source, dest) = (path.clone(), staging.0.clone());
+    let header =
+        tauri::async_runtime::spawn_blocking(move || unpack(&source, passphrase.as_deref(), &dest))
+            .await
+            .map_err(|e| format!("Restore failed: {}", e))??;
+    let staged = staging.0.join(ROOT);
+    if !staged.is_dir() {
+        return Err("The snapshot doesn't contain an OpenClaw state directory".to_string());
+    }
+    relocate_config(&staged, &header.home, &paths)?;
+
+    // The gateway keeps the WhatsApp session and config open; it must not run during the swap.
+    let target = gateway::target(&paths);
+    let running = |target: gateway::GatewayTarget| async move {
+        gateway::probe(&target.url, target.token.as_deref())
+            .await
+            .result
+            == ProbeResult::Running
+    };
+    let gateway_was_running = !target.remote && running(target.clone()).await;
+    if gateway_was_running {
+        claim.step("Stopping the gateway");
+        let _ = spawn_and_stream(&app, &paths, "openclaw", &["gateway", "stop"]).await;
+        if running(target).await {
+            return Err("The gateway is still running; stop it and try again".to_string());
+        }
+    }
+
+    let swapped = async {
+        let mut previous = None;
+        if paths.home.is_dir() {
+            claim.step("Saving the current state");
+            let version = openclaw_version::check(&paths).await.version;
+            let dest = snapshot_path(&dir, &new_id(&paths.profile, now_millis()))?;
+            let label = Some(format!("Before restoring {}", id));
+            let current = paths.clone();
+            previous = Some(
+                tauri::async_runtime::spawn_blocking(move || {
+                    create(&dest, &current, label, version)
+                })
+                .await
+                .map_err(|e| format!("Snapshot failed: {}", e))??,
+            );
+        }
+        claim.step("Restoring the state directory");
+        swap_in(&paths.home, &staged)?;
+        // The services still pass the token of the state that was replaced.
+        claim.step("Syncing the gateway token");
+        let changed = gateway_token::sync(&paths).map_err(|e| {
+            format!(
+                "Snapshot restored, but the gateway token couldn't be synced: {}",
+                e
+            )
+        })?;
+        if changed {
+            gateway_token::reload_services(&paths).await;
+        }
+        Ok::<_, String>(previous)
+    }
+    .await;
+
+    // Started again even if the restore failed, on whichever state is now in place.
+    if gateway_was_running {
+        claim.step("Starting the gateway");
+        let started = match spawn_and_stream(&app, &paths, "openclaw", &["gateway", "start"]).await
+        {
+            Ok(()) => openclaw_version::wait_for_gateway(&paths, None).await,
+            Err(e) => Err(e),
+        };
+        if let (Ok(_), Err(e)) = (&swapped, started) {
+            return Err(format!(
+                "Snapshot restored, but the gateway didn't start: {}",
+                e
+            ));
+        }
+    }
+    let previous = swapped?;
+
+    Ok(SnapshotRestore {
+        restored,
+        previous,
+        gateway_restarted: gateway_was_running,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::TempHome;
+
+    const KEY: [u8; 32] = [7; 32];
+    const PREFIX: [u8; NONCE_PREFIX_LEN] = [1; NONCE_PREFIX_LEN];
+
+    fn encrypt(data: &[u8]) -> Vec<u8> {
+        let mut writer = EncryptWriter::new(Vec::new(), &KEY, PREFIX);
+        // Uneven writes, so chunks don't line up with them.
+        for part in data.chunks(1000) {
+            writer.write_all(part).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn decrypt(sealed: &[u8]) -> io::Result<Vec<u8>> {
+        let mut plain = Vec::new();
+        DecryptReader::new(sealed, &KEY, PREFIX).read_to_end(&mut plain)?;
+        Ok(plain)
+    }
+
+    #[test]
+    fn encrypts_in_chunks() {
+        let sealed_len = CHUNK + TAG_LEN;
+        for len in [0, 1, CHUNK - 1, CHUNK, CHUNK + 1, 2 * CHUNK, 2 * CHUNK + 5] {
+            let data = random_bytes(len);
+            let sealed = encrypt(&data);
+            assert_eq!(
+                sealed.len(),
+                len + len.div_ceil(CHUNK).max(1) * TAG_LEN,
+                "{}",
+                len
+            );
+            assert_eq!(decrypt(&sealed).unwrap(), data, "{}", len);
+        }
+
+        // Exactly one chunk is sealed as the last one, not followed by an empty chunk.
+        assert_eq!(encrypt(&random_bytes(CHUNK)).len(), sealed_len);
+    }
+
+    #[test]
+    fn refuses_damaged_ciphertext() {
+        let data = random_bytes(2 * CHUNK + 5);
+        let sealed = encrypt(&data);
+        let sealed_len = CHUNK + TAG_LEN;
+
+        let mut tampered = sealed.clone();
+        tampered[CHUNK + 3] ^= 1;
+        assert!(decrypt(&tampered).is_err());
+
+        // Cut at chunk boundaries, the rest still decrypts but isn't taken as the end.
+        for len in [0, sealed_len, 2 * sealed_len, sealed.len() - 1] {
+            let err = decrypt(&sealed[..len]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", len);
+        }
+
+        // Swapped chunks fail too.
+        let mut swapped = sealed[sealed_len..2 * sealed_len].to_vec();
+        swapped.extend_from_slice(&sealed[..sealed_len]);
+        swapped.extend_from_slice(&sealed[2 * sealed_len..]);
+        assert!(decrypt(&swapped).is_err());
+
+        let mut plain = Vec::new();
+        let wrong_key = DecryptReader::new(sealed.as_slice(), &[8; 32], PREFIX)
+            .read_to_end(&mut plain)
+            .unwrap_err();
+        assert!(wrong_key.to_string().contains("wrong passphrase"));
+    }
+
+    /// A state directory with an incompressible file spanning several chunks.
+    fn state() -> (TempHome, Vec<u8>) {
+        let home = TempHome::new();
+        home.write("openclaw.json", r#"{ "gateway": { "mode": "local" } }"#);
+        let blob = random_bytes(3 * CHUNK + 100);
+        std::fs::create_dir_all(home.paths.home.join("credentials")).unwrap();
+        std::fs::write(home.paths.home.join("credentials/blob"), &blob).unwrap();
+        (home, blob)
+    }
+
+    fn unpacked(path: &Path, passphrase: Option<&str>) -> Result<TempHome, String> {
+        let dest = TempHome::new();
+        unpack(path, passphrase, &dest.paths.home)?;
+        Ok(dest)
+    }
+
+    #[test]
+    fn round_trips_plain_and_encrypted_snapshots() {
+        let (home, blob) = state();
+        let out = TempHome::new();
+        let plain = out.paths.home.join("plain.ocsnap");
+        let encrypted = out.paths.home.join("encrypted.ocsnap");
+        assert!(!create(&plain, &home.paths, None, None).unwrap().encrypted);
+        let info = create_encrypted(&encrypted, &home.paths, None, "hunter2", None).unwrap();
+        assert!(info.encrypted);
+
+        for (path, passphrase) in [(&plain, None), (&encrypted, Some("hunter2"))] {
+            let dest = unpacked(path, passphrase).unwrap();
+            let root = dest.paths.home.join(ROOT);
+            assert_eq!(std::fs::read(root.join("credentials/blob")).unwrap(), blob);
+            assert!(root.join("openclaw.json").is_file());
+        }
+
+        let err = unpacked(&encrypted, None).unwrap_err();
+        assert!(err.contains("enter its passphrase"), "{}", err);
+        let err = unpacked(&encrypted, Some("hunter3")).unwrap_err();
+        assert!(err.contains("wrong passphrase"), "{}", err);
+    }
+
+    #[test]
+    fn refuses_damaged_snapshots() {
+        let (home, _) = state();
+        let out = TempHome::new();
+        let path = out.paths.home.join("encrypted.ocsnap");
+        create_encrypted(&path, &home.paths, None, "hunter2", None).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let header_end = MAGIC.len()
+            + bytes[MAGIC.len()..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .unwrap()
+            + 1;
+        let sealed_len = CHUNK + TAG_LEN;
+        // Dropping whole chunks leaves only chunks that decrypt but aren't the last one.
+        let boundary = header_end + (bytes.len() - header_end - 1) / sealed_len * sealed_len;
+        assert!(boundary > header_end);
+
+        let mut tampered = bytes.clone();
+        tampered[header_end + CHUNK / 2] ^= 1;
+        let truncated = &bytes[..bytes.len() - 10];
+        for damaged in [&tampered[..], truncated, &bytes[..boundary]] {
+            std::fs::write(&path, damaged).unwrap();
+            assert!(unpacked(&path, Some("hunter2")).is_err());
+        }
+    }
+
+    #[test]
+    fn relocates_paths_in_the_restored_config_and_auth_profiles() {
+        let staged = TempHome::new();
+        staged.write(
+            "openclaw.json",
+            r#"{
+                "agents": {
+                    "defaults": { "workspace": "/old/.openclaw/workspace" },
+                    "list": [
+                        { "id": "main" },
+                        { "id": "work", "workspace": "/old/.openclaw-work/workspace",
+                          "agentDir": "/old/.openclaw/agents/work/agent" }
+                    ]
+                },
+                "gateway": { "mode": "local" }
+            }"#,
+        );
+        staged.write(
+            "agents/work/agent/auth-profiles.json",
+            r#"{ "profiles": { "file:default": { "keyFile": "/old/.openclaw/keys/k" } } }"#,
+        );
+        staged.write(
+            "agents/main/agent/auth-profiles.json",
+            r#"{ "profiles": { "openrouter:default": { "key": "sk-or-1" } } }"#,
+        );
+        let main_auth = std::fs::read(
+            staged
+                .paths
+                .home
+                .join("agents/main/agent/auth-profiles.json"),
+        )
+        .unwrap();
+        let target = OpenClawPaths {
+            home: PathBuf::from("/new/.openclaw"),
+            ..OpenClawPaths::default()
+        };
+
+        relocate_config(&staged.paths.home, "/old/.openclaw", &target).unwrap();
+
+        let config = openclaw_config::load_config(&staged.paths).unwrap();
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            value["agents"]["defaults"]["workspace"],
+            "/new/.openclaw/workspace"
+        );
+        let work = &value["agents"]["list"][1];
+        // A sibling directory that merely shares the prefix is left alone.
+        assert_eq!(work["workspace"], "/old/.openclaw-work/workspace");
+        assert_eq!(work["agentDir"], "/new/.openclaw/agents/work/agent");
+        assert_eq!(value["gateway"]["mode"], "local");
+
+        let auth = openclaw_config::load_auth_profiles(
+            &staged
+                .paths
+                .home
+                .join("agents/work/agent/auth-profiles.json"),
+        )
+        .unwrap();
+        assert_eq!(
+            auth["profiles"]["file:default"]["keyFile"],
+            "/new/.openclaw/keys/k"
+        );
+        // Files without old paths aren't rewritten.
+        assert_eq!(
+            std::fs::read(
+                staged
+                    .paths
+                    .home
+                    .join("agents/main/agent/auth-profiles.json")
+            )
+            .unwrap(),
+            main_auth
+        );
+        // Rewritten files were backed up first.
+        assert_eq!(config_files::list_backups(&staged.paths).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn leaves_a_config_from_the_same_directory_alone() {
+        let staged = TempHome::new();
+        let config = r#"{ "agents": { "defaults": { "workspace": "/old/.openclaw/w" } } }"#;
+        staged.write("openclaw.json", config);
+        let target = OpenClawPaths {
+            home: PathBuf::from("/old/.openclaw"),
+            ..OpenClawPaths::default()
+        };
+        relocate_config(&staged.paths.home, "/old/.openclaw", &target).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(staged.paths.config_path()).unwrap(),
+            config
+        );
+    }
+}
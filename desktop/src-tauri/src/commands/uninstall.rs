@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use super::gateway;
+use super::gateway_token::{installed_services, ServiceKind};
+use super::installer;
+use super::openclaw::{find_openclaw, spawn_and_stream};
+use super::openclaw_paths::OpenClawPaths;
+use super::openclaw_version;
+use super::operations::{self, ExclusionGroup};
+use super::settings::SettingsState;
+use super::snapshots;
+use super::vault::now_millis;
+
+// --- Types ---
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallOptions {
+    /// Also delete the profile's state directory (config, credentials, workspaces).
+    #[serde(default)]
+    pub remove_state: bool,
+    /// Archive the state directory before deleting it. Defaults to true.
+    pub archive_state: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UninstallAction {
+    StopGateway,
+    RemoveService,
+    RemoveBinary,
+    ArchiveState,
+    RemoveState,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UninstallStep {
+    pub action: UninstallAction,
+    pub description: String,
+    /// File or directory the step touches.
+    pub path: Option<String>,
+    /// Size of `path` in bytes, for the state directory.
+    pub size: Option<u64>,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UninstallReport {
+    pub dry_run: bool,
+    pub steps: Vec<UninstallStep>,
+    /// Where the state directory was (or would be) archived.
+    pub archive: Option<String>,
+}
+
+impl UninstallStep {
+    fn new(action: UninstallAction, description: String, path: Option<&Path>) -> Self {
+        Self {
+            action,
+            description,
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            size: None,
+            done: false,
+            error: None,
+        }
+    }
+}
+
+// --- Helpers ---
+
+/// Total size of the files under `dir`, not following symlinks.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Refuses to delete a directory that isn't an OpenClaw state directory (no openclaw.json)
+/// or that is the home directory or one of its ancestors, which a mistyped profile home
+/// could point at.
+fn check_removable(home: &Path) -> Result<(), String> {
+    let user_home = dirs::home_dir().ok_or("Could not find the home directory")?;
+    if user_home.starts_with(home) {
+        return Err(format!(
+            "Refusing to delete {}: it contains your home directory",
+            home.display()
+        ));
+    }
+    if !home.join("openclaw.json").is_file() {
+        return Err(format!(
+            "Refusing to delete {}: it has no openclaw.json, so it isn't an OpenClaw state \
+             directory",
+            home.display()
+        ));
+    }
+    Ok(())
+}
+
+/// The name launchd or systemd knows a service definition by: the plist's file stem
+/// (its label, e.g. "ai.openclaw.gateway") or the unit file's name.
+fn service_name(kind: ServiceKind, path: &Path) -> Option<String> {
+    let name = match kind {
+        ServiceKind::Launchd => path.file_stem()?,
+        ServiceKind::Systemd => path.file_name()?,
+    };
+    Some(name.to_string_lossy().to_string())
+}
+
+async fn remove_service(kind: ServiceKind, path: &Path) -> Result<(), String> {
+    use tokio::process::Command;
+
+    let name = service_name(kind, path)
+        .ok_or_else(|| format!("Not a service definition: {}", path.display()))?;
+    match kind {
+        ServiceKind::Launchd => {
+            let _ = Command::new("launchctl")
+                .args(["unload", "-w"])
+                .arg(path)
+                .status()
+                .await;
+            let _ = Command::new("launchctl")
+                .args(["remove", name.as_str()])
+                .status()
+                .await;
+        }
+        ServiceKind::Systemd => {
+            let _ = Command::new("systemctl")
+                .args(["--user", "disable", "--now", name.as_str()])
+                .status()
+                .await;
+        }
+    }
+    std::fs::remove_file(path)
+        .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    if kind == ServiceKind::Systemd {
+        let _ = Command::new("systemctl")
+            .args(["--user", "daemon-reload"])
+            .status()
+            .await;
+    }
+    Ok(())
+}
+
+/// Uninstalls the npm package that provides `binary`: from the installer's prefix when
+/// it is there, from npm's global prefix otherwise.
+async fn remove_binary(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    binary: &Path,
+) -> Result<(), String> {
+    let prefix = installer::prefix();
+    let prefix_arg = prefix.to_string_lossy().to_string();
+    let mut args = vec!["uninstall", "--global"];
+    if binary.starts_with(&prefix) {
+        args.extend(["--prefix", prefix_arg.as_str()]);
+    }
+    args.push("openclaw");
+    spawn_and_stream(app, paths, "npm", &args).await?;
+    if binary.exists() {
+        return Err(format!(
+            "{} is still there; it wasn't installed with npm, so remove it the way it was \
+             installed",
+            binary.display()
+        ));
+    }
+    Ok(())
+}
+
+// --- Uninstall ---
+
+/// Lists what an uninstall touches, in the order it happens.
+fn plan(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    options: &UninstallOptions,
+) -> Result<(Vec<UninstallStep>, Option<PathBuf>), String> {
+    let mut steps = Vec::new();
+
+    let target = gateway::target(paths);
+    if !target.remote {
+        steps.push(UninstallStep::new(
+            UninstallAction::StopGateway,
+            format!("Stop the gateway at {}", target.url),
+            None,
+        ));
+    }
+    // The state is archived before anything is removed.
+    let remove_state = options.remove_state && paths.home.exists();
+    let size = if remove_state {
+        check_removable(&paths.home)?;
+        dir_size(&paths.home)
+    } else {
+        0
+    };
+    let mut archive = None;
+    if remove_state && options.archive_state.unwrap_or(true) {
+        let id = snapshots::new_id(&paths.profile, now_millis());
+        let dest = snapshots::snapshot_path(&snapshots::snapshots_dir(app)?, &id)?;
+        let mut step = UninstallStep::new(
+            UninstallAction::ArchiveState,
+            format!("Snapshot {} to {}", paths.home.display(), dest.display()),
+            Some(&paths.home),
+        );
+        step.size = Some(size);
+        steps.push(step);
+        archive = Some(dest);
+    }
+    for (kind, path) in installed_services(paths) {
+        let description = match kind {
+            ServiceKind::Launchd => "Unload and delete the gateway LaunchAgent",
+            ServiceKind::Systemd => "Disable and delete the gateway systemd unit",
+        };
+        steps.push(UninstallStep::new(
+            UninstallAction::RemoveService,
+            description.to_string(),
+            Some(&path),
+        ));
+    }
+    if let Some(binary) = find_openclaw() {
+        steps.push(UninstallStep::new(
+            UninstallAction::RemoveBinary,
+            "Uninstall the openclaw npm package".to_string(),
+            Some(Path::new(&binary)),
+        ));
+    }
+    if remove_state {
+        let mut step = UninstallStep::new(
+            UninstallAction::RemoveState,
+            format!(
+                "Delete {} (config, credentials, WhatsApp session, workspaces)",
+                paths.home.display()
+            ),
+            Some(&paths.home),
+        );
+        step.size = Some(size);
+        steps.push(step);
+    }
+    Ok((steps, archive))
+}
+
+async fn run_step(
+    app: &AppHandle,
+    paths: &OpenClawPaths,
+    step: &UninstallStep,
+    archive: Option<&Path>,
+) -> Result<(), String> {
+    let path = step.path.as_ref().map(PathBuf::from);
+    match (step.action, path) {
+        (UninstallAction::StopGateway, _) => {
+            spawn_and_stream(app, paths, "openclaw", &["gateway", "stop"]).await
+        }
+        (UninstallAction::RemoveService, Some(path)) => {
+            let kind = if path.extension().is_some_and(|e| e == "plist") {
+                ServiceKind::Launchd
+            } else {
+                ServiceKind::Systemd
+            };
+            remove_service(kind, &path).await
+        }
+        (UninstallAction::RemoveBinary, Some(path)) => remove_binary(app, paths, &path).await,
+        (UninstallAction::ArchiveState, Some(_)) => {
+            let dest = archive.ok_or("No archive destination")?.to_path_buf();
+            let version = openclaw_version::check(paths).await.version;
+            let paths = paths.clone();
+            let label = Some("Before uninstall".to_string());
+            tauri::async_runtime::spawn_blocking(move || {
+                snapshots::create(&dest, &paths, label, version).map(|_| ())
+            })
+            .await
+            .map_err(|e| format!("Archiving failed: {}", e))?
+        }
+        (UninstallAction::RemoveState, Some(path)) => {
+            // Checked again: the directory may have changed since the plan was made.
+            check_removable(&path)?;
+            std::fs::remove_dir_all(&path)
+                .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+        }
+        (_, None) => Ok(()),
+    }
+}
+
+/// Removes what setup installed: stops the local gateway, deletes its service definition,
+/// uninstalls the openclaw package and, with `remove_state`, archives then deletes the
+/// active profile's state directory. With `dry_run`, only lists the steps. Steps run in
+/// order; a failed archive stops before anything is deleted, other failures are recorded
+/// and the rest still run.
+#[tauri::command]
+pub async fn openclaw_uninstall(
+    app: AppHandle,
+    options: Option<UninstallOptions>,
+    dry_run: Option<bool>,
+    settings: State<'_, SettingsState>,
+) -> Result<UninstallReport, String> {
+    let options = options.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(false);
+    let paths = settings.paths();
+    let (mut steps, archive) = plan(&app, &paths, &options)?;
+    let report = |steps, dry_run| UninstallReport {
+        dry_run,
+        steps,
+        archive: archive.as_ref().map(|p| p.to_string_lossy().to_string()),
+    };
+    if dry_run {
+        return Ok(report(steps, true));
+    }
+
+    let claim = operations::claim(
+        &app,
+        "Uninstalling OpenClaw",
+        &[
+            ExclusionGroup::Install,
+            ExclusionGroup::Config,
+            ExclusionGroup::Gateway,
+        ],
+    )?;
+    for step in steps.iter_mut() {
+        claim.step(&step.description);
+        match run_step(&app, &paths, step, archive.as_deref()).await {
+            Ok(()) => step.done = true,
+            // Without an archive, deleting the state would lose it for good.
+            Err(e) if step.action == UninstallAction::ArchiveState => {
+                step.error = Some(e);
+                break;
+            }
+            Err(e) => step.error = Some(e),
+        }
+    }
+    Ok(report(steps, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::TempHome;
+
+    #[test]
+    fn only_removes_openclaw_state_directories() {
+        let home = TempHome::new();
+        let err = check_removable(&home.paths.home).unwrap_err();
+        assert!(err.contains("has no openclaw.json"), "{}", err);
+
+        home.write("openclaw.json", "{}");
+        assert!(check_removable(&home.paths.home).is_ok());
+
+        let user_home = dirs::home_dir().unwrap();
+        let err = check_removable(&user_home).unwrap_err();
+        assert!(err.contains("contains your home directory"), "{}", err);
+        assert!(check_removable(Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn names_services_after_their_files() {
+        let plist = Path::new("/Users/me/Library/LaunchAgents/ai.openclaw.work.gateway.plist");
+        assert_eq!(
+            service_name(ServiceKind::Launchd, plist).as_deref(),
+            Some("ai.openclaw.work.gateway")
+        );
+        let unit = Path::new("/home/me/.config/systemd/user/openclaw-gateway-work.service");
+        assert_eq!(
+            service_name(ServiceKind::Systemd, unit).as_deref(),
+            Some("openclaw-gateway-work.service")
+        );
+    }
+}
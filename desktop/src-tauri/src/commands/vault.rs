@@ -0,0 +1,651 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::config_files;
+
+const VAULT_VERSION: u32 = 1;
+
+/// How the vault encryption key is derived.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultMode {
+    /// Key derived from this machine's identity; unlocks automatically on start.
+    Machine,
+    /// Key derived from a user passphrase; stays locked until `vault_unlock`.
+    Passphrase,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultStatus {
+    pub mode: VaultMode,
+    pub locked: bool,
+    pub key_count: usize,
+    pub path: String,
+    /// Why the vault couldn't be opened or unlocked; cleared once it is.
+    pub error: Option<String>,
+}
+
+/// What the frontend sees for a stored key. The secret itself never leaves the backend.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultKeySummary {
+    pub key_ref: String,
+    pub provider: String,
+    pub label: String,
+    pub masked: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// On-disk layout: only the mode and KDF salt are in the clear.
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    mode: VaultMode,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct VaultEntry {
+    provider: String,
+    label: String,
+    secret: String,
+    created_at: u64,
+    updated_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct VaultPayload {
+    entries: BTreeMap<String, VaultEntry>,
+}
+
+struct VaultInner {
+    mode: VaultMode,
+    salt: Vec<u8>,
+    key: Option<[u8; 32]>,
+    payload: VaultPayload,
+    error: Option<String>,
+}
+
+/// Encrypted credential store and source of truth for every API key the app manages.
+/// Commands receive a `key_ref` and resolve it here instead of taking raw keys from the UI.
+pub struct Vault {
+    path: PathBuf,
+    inner: Mutex<VaultInner>,
+}
+
+impl Vault {
+    /// Opens the vault at `path`, creating an empty machine-keyed vault if none exists.
+    /// Machine-keyed vaults are unlocked immediately; passphrase vaults start locked. A vault
+    /// that can't be read or decrypted (corrupted, or from another machine or user) also
+    /// starts locked, with the reason in `VaultStatus::error`.
+    pub fn open(path: PathBuf) -> Self {
+        let inner = Self::load(&path).unwrap_or_else(|e| VaultInner {
+            mode: VaultMode::Machine,
+            salt: Vec::new(),
+            key: None,
+            payload: VaultPayload::default(),
+            error: Some(e),
+        });
+        Self {
+            path,
+            inner: Mutex::new(inner),
+        }
+    }
+
+    fn load(path: &Path) -> Result<VaultInner, String> {
+        if !path.exists() {
+            let salt = random_bytes(16);
+            let key = derive_key(machine_secret().as_bytes(), &salt)?;
+            return Ok(VaultInner {
+                mode: VaultMode::Machine,
+                salt,
+                key: Some(key),
+                payload: VaultPayload::default(),
+                error: None,
+            });
+        }
+
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read vault: {}", e))?;
+        let file: VaultFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Vault file is corrupted: {}", e))?;
+        if file.version > VAULT_VERSION {
+            return Err(format!(
+                "Vault version {} is newer than this app supports",
+                file.version
+            ));
+        }
+        let salt = BASE64
+            .decode(&file.salt)
+            .map_err(|e| format!("Vault salt is corrupted: {}", e))?;
+        let mut inner = VaultInner {
+            mode: file.mode,
+            salt,
+            key: None,
+            payload: VaultPayload::default(),
+            error: None,
+        };
+        if file.mode == VaultMode::Machine {
+            let key = derive_key(machine_secret().as_bytes(), &inner.salt)?;
+            match decrypt_payload(&file, &key) {
+                Ok(payload) => {
+                    inner.payload = payload;
+                    inner.key = Some(key);
+                }
+                Err(e) => inner.error = Some(e),
+            }
+        }
+        Ok(inner)
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        let inner = self.lock_inner();
+        VaultStatus {
+            mode: inner.mode,
+            locked: inner.key.is_none(),
+            key_count: inner.payload.entries.len(),
+            path: self.path.to_string_lossy().to_string(),
+            error: inner.error.clone(),
+        }
+    }
+
+    /// Unlocks the vault. Passphrase vaults require `passphrase`; machine vaults ignore it.
+    pub fn unlock(&self, passphrase: Option<&str>) -> Result<(), String> {
+        let mut inner = self.lock_inner();
+        if inner.key.is_some() {
+            return Ok(());
+        }
+        // The file couldn't be read at all; only a reset helps.
+        if inner.salt.is_empty() {
+            return Err(inner.error.clone().unwrap_or_else(|| "Vault is unreadable".to_string()));
+        }
+        let secret = match inner.mode {
+            VaultMode::Machine => machine_secret(),
+            VaultMode::Passphrase => passphrase
+                .filter(|p| !p.is_empty())
+                .ok_or("A passphrase is required to unlock the vault")?
+                .to_string(),
+        };
+        let key = derive_key(secret.as_bytes(), &inner.salt)?;
+        inner.payload = if self.path.exists() {
+            let content = std::fs::read_to_string(&self.path)
+                .map_err(|e| format!("Failed to read vault: {}", e))?;
+            let file: VaultFile = serde_json::from_str(&content)
+                .map_err(|e| format!("Vault file is corrupted: {}", e))?;
+            decrypt_payload(&file, &key)?
+        } else {
+            VaultPayload::default()
+        };
+        inner.key = Some(key);
+        inner.error = None;
+        Ok(())
+    }
+
+    /// Starts over with an empty machine-keyed vault, for one that can't be unlocked. The old
+    /// file is kept next to it as `vault.json.unreadable-<millis>`.
+    pub fn reset(&self) -> Result<(), String> {
+        let mut inner = self.lock_inner();
+        if inner.key.is_some() {
+            return Err("The vault is unlocked; there is nothing to reset".to_string());
+        }
+        if self.path.exists() {
+            let mut aside = self.path.clone().into_os_string();
+            aside.push(format!(".unreadable-{}", now_millis()));
+            std::fs::rename(&self.path, &aside)
+                .map_err(|e| format!("Failed to move the old vault aside: {}", e))?;
+        }
+        let salt = random_bytes(16);
+        *inner = VaultInner {
+            mode: VaultMode::Machine,
+            key: Some(derive_key(machine_secret().as_bytes(), &salt)?),
+            salt,
+            payload: VaultPayload::default(),
+            error: None,
+        };
+        Ok(())
+    }
+
+    /// Drops the decrypted contents and key from memory.
+    pub fn lock(&self) {
+        let mut inner = self.lock_inner();
+        inner.key = None;
+        inner.payload = VaultPayload::default();
+    }
+
+    /// Re-encrypts the vault under a new passphrase, or under the machine key when `None`.
+    pub fn set_passphrase(&self, passphrase: Option<&str>) -> Result<(), String> {
+        let mut inner = self.lock_inner();
+        if inner.key.is_none() {
+            return Err("Vault is locked".to_string());
+        }
+        let (mode, secret) = match passphrase.filter(|p| !p.is_empty()) {
+            Some(p) => (VaultMode::Passphrase, p.to_string()),
+            None => (VaultMode::Machine, machine_secret()),
+        };
+        let salt = random_bytes(16);
+        inner.key = Some(derive_key(secret.as_bytes(), &salt)?);
+        inner.salt = salt;
+        inner.mode = mode;
+        self.persist(&inner)
+    }
+
+    pub fn list(&self) -> Result<Vec<VaultKeySummary>, String> {
+        let inner = self.unlocked()?;
+        Ok(inner
+            .payload
+            .entries
+            .iter()
+            .map(|(id, entry)| summarize(id, entry))
+            .collect())
+    }
+
+    /// Stores a secret under `provider`/`label`, replacing any existing entry with the
+    /// same pair (its reference is kept so existing settings stay valid).
+    pub fn put(&self, provider: &str, label: &str, secret: &str) -> Result<VaultKeySummary, String> {
+        let secret = secret.trim();
+        if secret.is_empty() {
+            return Err("Key cannot be empty".to_string());
+        }
+        let mut inner = self.unlocked()?;
+        let now = now_millis();
+        let existing = inner
+            .payload
+            .entries
+            .iter()
+            .find(|(_, e)| e.provider == provider && e.label == label)
+            .map(|(id, _)| id.clone());
+        let id = existing.unwrap_or_else(new_key_ref);
+        let created_at = inner
+            .payload
+            .entries
+            .get(&id)
+            .map(|e| e.created_at)
+            .unwrap_or(now);
+        let entry = VaultEntry {
+            provider: provider.to_string(),
+            label: label.to_string(),
+            secret: secret.to_string(),
+            created_at,
+            updated_at: now,
+        };
+        let summary = summarize(&id, &entry);
+        inner.payload.entries.insert(id, entry);
+        self.persist(&inner)?;
+        Ok(summary)
+    }
+
+    pub fn delete(&self, key_ref: &str) -> Result<(), String> {
+        let mut inner = self.unlocked()?;
+        if inner.payload.entries.remove(key_ref).is_some() {
+            self.persist(&inner)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the plaintext secret for `key_ref`. Only backend code calls this.
+    pub fn resolve(&self, key_ref: &str) -> Result<String, String> {
+        let inner = self.unlocked()?;
+        inner
+            .payload
+            .entries
+            .get(key_ref)
+            .map(|e| e.secret.clone())
+            .ok_or_else(|| format!("Unknown key reference: {}", key_ref))
+    }
+
+    fn lock_inner(&self) -> std::sync::MutexGuard<'_, VaultInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn unlocked(&self) -> Result<std::sync::MutexGuard<'_, VaultInner>, String> {
+        let inner = self.lock_inner();
+        if inner.key.is_none() {
+            return Err("Vault is locked — unlock it in Settings first".to_string());
+        }
+        Ok(inner)
+    }
+
+    fn persist(&self, inner: &VaultInner) -> Result<(), String> {
+        let key = inner.key.ok_or("Vault is locked")?;
+        let plaintext = serde_json::to_vec(&inner.payload)
+            .map_err(|e| format!("Failed to serialize vault: {}", e))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| "Failed to encrypt vault".to_string())?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            mode: inner.mode,
+            salt: BASE64.encode(&inner.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize vault: {}", e))?;
+
+        config_files::write_atomic(&self.path, content.as_bytes())?;
+        restrict_permissions(&self.path);
+        Ok(())
+    }
+}
+
+fn decrypt_payload(file: &VaultFile, key: &[u8; 32]) -> Result<VaultPayload, String> {
+    let nonce = BASE64
+        .decode(&file.nonce)
+        .map_err(|e| format!("Vault nonce is corrupted: {}", e))?;
+    let ciphertext = BASE64
+        .decode(&file.ciphertext)
+        .map_err(|e| format!("Vault contents are corrupted: {}", e))?;
+    if nonce.len() != 12 {
+        return Err("Vault nonce is corrupted".to_string());
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Failed to decrypt vault — wrong passphrase or different machine".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Vault contents are corrupted: {}", e))
+}
+
+pub fn derive_key(secret: &[u8], salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| format!("Failed to derive encryption key: {}", e))?;
+    Ok(key)
+}
+
+pub fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// A random identifier such as "key_1f2e3d4c5b6a7988".
+pub fn random_id(prefix: &str) -> String {
+    let hex: String = random_bytes(8).iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}_{}", prefix, hex)
+}
+
+fn new_key_ref() -> String {
+    random_id("key")
+}
+
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn summarize(id: &str, entry: &VaultEntry) -> VaultKeySummary {
+    VaultKeySummary {
+        key_ref: id.to_string(),
+        provider: entry.provider.clone(),
+        label: entry.label.clone(),
+        masked: mask_key(&entry.secret),
+        created_at: entry.created_at,
+        updated_at: entry.updated_at,
+    }
+}
+
+pub fn mask_key(key: &str) -> String {
+    let len = key.chars().count();
+    if len <= 4 {
+        return "••••".to_string();
+    }
+    let tail: String = key.chars().skip(len - 4).collect();
+    format!("••••••••{}", tail)
+}
+
+/// Secret material for machine-keyed vaults: the OS machine id plus the current user.
+/// This keeps the file useless if copied elsewhere, but is not a substitute for a passphrase.
+fn machine_secret() -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let id = machine_id()
+        .or_else(sysinfo::System::host_name)
+        .unwrap_or_default();
+    format!("daemon-vault:{}:{}", id, user)
+}
+
+fn machine_id() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        let output = std::process::Command::new("ioreg")
+            .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|l| l.contains("IOPlatformUUID"))
+            .and_then(|l| l.split('"').nth(3))
+            .map(|s| s.to_string())
+    } else if cfg!(target_os = "windows") {
+        let output = std::process::Command::new("reg")
+            .args([
+                "query",
+                r"HKLM\SOFTWARE\Microsoft\Cryptography",
+                "/v",
+                "MachineGuid",
+            ])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|l| l.contains("MachineGuid"))
+            .and_then(|l| l.split_whitespace().last())
+            .map(|s| s.to_string())
+    } else {
+        ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .find_map(|p| std::fs::read_to_string(p).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+// --- Commands ---
+
+#[tauri::command]
+pub fn vault_status(vault: State<'_, Vault>) -> Result<VaultStatus, String> {
+    Ok(vault.status())
+}
+
+#[tauri::command]
+pub fn vault_unlock(vault: State<'_, Vault>, passphrase: Option<String>) -> Result<VaultStatus, String> {
+    vault.unlock(passphrase.as_deref())?;
+    Ok(vault.status())
+}
+
+/// Discards a vault that can't be unlocked; its keys have to be entered again.
+#[tauri::command]
+pub fn vault_reset(vault: State<'_, Vault>) -> Result<VaultStatus, String> {
+    vault.reset()?;
+    Ok(vault.status())
+}
+
+#[tauri::command]
+pub fn vault_lock(vault: State<'_, Vault>) -> Result<VaultStatus, String> {
+    vault.lock();
+    Ok(vault.status())
+}
+
+/// Switches to passphrase protection, or back to the machine key when `passphrase` is empty.
+#[tauri::command]
+pub fn vault_set_passphrase(
+    vault: State<'_, Vault>,
+    passphrase: Option<String>,
+) -> Result<VaultStatus, String> {
+    vault.set_passphrase(passphrase.as_deref())?;
+    Ok(vault.status())
+}
+
+#[tauri::command]
+pub fn vault_list_keys(vault: State<'_, Vault>) -> Result<Vec<VaultKeySummary>, String> {
+    vault.list()
+}
+
+#[tauri::command]
+pub fn vault_store_key(
+    vault: State<'_, Vault>,
+    provider: String,
+    label: String,
+    secret: String,
+) -> Result<VaultKeySummary, String> {
+    vault.put(&provider, &label, &secret)
+}
+
+#[tauri::command]
+pub fn vault_delete_key(vault: State<'_, Vault>, key_ref: String) -> Result<(), String> {
+    vault.delete(&key_ref)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory for a test vault, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(random_id("vault-test"));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn vault(&self) -> PathBuf {
+            self.0.join("vault.json")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn masks_all_but_the_last_four_characters() {
+        assert_eq!(mask_key("sk-or-v1-abcd"), "••••••••abcd");
+        assert_eq!(mask_key("abcd"), "••••");
+        assert_eq!(mask_key(""), "••••");
+        assert_eq!(mask_key("clé-ünïcødé"), "••••••••cødé");
+        assert_eq!(mask_key("🔑🔑🔑🔑🔑"), "••••••••🔑🔑🔑🔑");
+    }
+
+    #[test]
+    fn derives_keys_from_secret_and_salt() {
+        let salt = [1u8; 16];
+        let key = derive_key(b"secret", &salt).unwrap();
+        assert_eq!(key, derive_key(b"secret", &salt).unwrap());
+        assert_ne!(key, derive_key(b"Secret", &salt).unwrap());
+        assert_ne!(key, derive_key(b"secret", &[2u8; 16]).unwrap());
+        assert!(derive_key(b"secret", b"short").is_err());
+    }
+
+    #[test]
+    fn stores_keys_encrypted_across_restarts() {
+        let dir = TempDir::new();
+        let path = dir.vault();
+        let vault = Vault::open(path.clone());
+        assert!(!vault.status().locked);
+        let summary = vault.put("openrouter", "default", " sk-or-v1-abcd ").unwrap();
+        assert_eq!(summary.masked, "••••••••abcd");
+        // Same provider and label keep their reference.
+        let again = vault.put("openrouter", "default", "sk-or-v1-efgh").unwrap();
+        assert_eq!(again.key_ref, summary.key_ref);
+        vault.put("anthropic", "work", "sk-ant-1").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-or-v1"), "{}", content);
+
+        let vault = Vault::open(path);
+        assert_eq!(vault.status().mode, VaultMode::Machine);
+        assert_eq!(vault.resolve(&summary.key_ref).unwrap(), "sk-or-v1-efgh");
+        assert_eq!(vault.list().unwrap().len(), 2);
+        vault.delete(&summary.key_ref).unwrap();
+        assert!(vault.resolve(&summary.key_ref).is_err());
+    }
+
+    #[test]
+    fn locks_and_unlocks_with_a_passphrase() {
+        let dir = TempDir::new();
+        let path = dir.vault();
+        let vault = Vault::open(path.clone());
+        let key_ref = vault.put("openrouter", "default", "sk-or-1").unwrap().key_ref;
+        vault.set_passphrase(Some("correct horse")).unwrap();
+
+        vault.lock();
+        assert!(vault.status().locked);
+        assert!(vault.resolve(&key_ref).unwrap_err().contains("locked"));
+        assert!(vault.set_passphrase(None).is_err());
+        vault.unlock(Some("correct horse")).unwrap();
+        assert_eq!(vault.resolve(&key_ref).unwrap(), "sk-or-1");
+
+        // Reopened, a passphrase vault starts locked.
+        let vault = Vault::open(path.clone());
+        let status = vault.status();
+        assert!(status.locked && status.error.is_none());
+        assert_eq!(status.mode, VaultMode::Passphrase);
+        assert!(vault.unlock(None).is_err());
+        let err = vault.unlock(Some("wrong horse")).unwrap_err();
+        assert!(err.contains("wrong passphrase"), "{}", err);
+        assert!(vault.status().locked);
+        vault.unlock(Some("correct horse")).unwrap();
+        assert_eq!(vault.resolve(&key_ref).unwrap(), "sk-or-1");
+
+        // A new passphrase replaces the old one; none goes back to the machine key.
+        vault.set_passphrase(Some("battery staple")).unwrap();
+        let vault = Vault::open(path.clone());
+        assert!(vault.unlock(Some("correct horse")).is_err());
+        vault.unlock(Some("battery staple")).unwrap();
+        vault.set_passphrase(None).unwrap();
+        let vault = Vault::open(path);
+        assert!(!vault.status().locked);
+        assert_eq!(vault.resolve(&key_ref).unwrap(), "sk-or-1");
+    }
+
+    #[test]
+    fn resets_an_unreadable_vault() {
+        let dir = TempDir::new();
+        let path = dir.vault();
+        std::fs::write(&path, "not json").unwrap();
+        let vault = Vault::open(path.clone());
+        let status = vault.status();
+        assert!(status.locked);
+        assert!(status.error.unwrap().contains("corrupted"));
+        assert!(vault.unlock(None).is_err());
+
+        vault.reset().unwrap();
+        assert!(!vault.status().locked);
+        assert!(vault.reset().is_err());
+        let aside = std::fs::read_dir(&dir.0)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .any(|e| e.file_name().to_string_lossy().starts_with("vault.json.unreadable-"));
+        assert!(aside);
+    }
+}
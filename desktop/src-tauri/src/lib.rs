@@ -1,9 +1,30 @@
 mod commands;
 
+use commands::agents;
+use commands::config_files;
+use commands::config_preview;
+use commands::config_watcher;
 use commands::diagnostics;
+use commands::gateway;
+use commands::gateway_chat;
+use commands::gateway_supervisor;
+use commands::gateway_token;
+use commands::gateway_ws;
+use commands::installer;
 use commands::openclaw;
+use commands::openclaw_config;
+use commands::openclaw_paths;
+use commands::openclaw_version;
 use commands::openrouter;
+use commands::operations;
+use commands::provider_keys;
+use commands::providers;
+use commands::settings;
 use commands::setup;
+use commands::snapshots;
+use commands::uninstall;
+use commands::vault;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -11,6 +32,21 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .setup(|app| {
+            let vault = vault::Vault::open(app.path().app_data_dir()?.join("vault.json"));
+            let settings = settings::SettingsState::load(app.handle(), &vault)
+                .unwrap_or_else(settings::SettingsState::failed);
+            app.manage(vault);
+            app.manage(settings);
+            app.manage(config_preview::PendingPreviews::default());
+            app.manage(gateway_supervisor::GatewaySupervisor::default());
+            app.manage(gateway_ws::GatewayClient::default());
+            app.manage(operations::OperationManager::default());
+            config_watcher::start(app.handle().clone());
+            gateway_supervisor::start(app.handle().clone());
+            gateway_ws::start(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             openrouter::openrouter_chat,
             openrouter::openrouter_test_key,
@@ -18,14 +54,81 @@ pub fn run() {
             diagnostics::diagnostics_full,
             diagnostics::system_info,
             openclaw::openclaw_check,
-            openclaw::openclaw_install,
+            installer::openclaw_install,
+            openclaw_version::openclaw_version,
+            openclaw_version::openclaw_set_version,
+            uninstall::openclaw_uninstall,
+            snapshots::openclaw_snapshots_list,
+            snapshots::openclaw_snapshot_create,
+            snapshots::openclaw_snapshot_import,
+            snapshots::openclaw_snapshot_delete,
+            snapshots::openclaw_snapshot_restore,
             openclaw::openclaw_onboard,
             openclaw::openclaw_connect_whatsapp,
+            operations::operations_list,
+            operations::operation_cancel,
+            operations::operation_write_stdin,
             openclaw::openclaw_configure_model,
+            openclaw::openclaw_configure_models,
+            gateway::openclaw_gateway_target,
+            gateway::openclaw_gateway_verify,
+            gateway::openclaw_gateway_set_remote,
+            gateway::openclaw_gateway_set_local,
+            gateway_supervisor::openclaw_gateway_status,
+            gateway_supervisor::openclaw_gateway_check_now,
+            gateway_ws::openclaw_gateway_connection,
+            gateway_ws::openclaw_gateway_reconnect,
+            gateway_ws::openclaw_gateway_call,
+            gateway_chat::openclaw_gateway_chat,
+            gateway_chat::openclaw_gateway_chat_enable,
+            gateway_token::openclaw_gateway_token_status,
+            gateway_token::openclaw_gateway_token_sync,
+            gateway_token::openclaw_gateway_token_rotate,
             openclaw::openclaw_gateway_restart,
             openclaw::openclaw_get_api_keys,
             openclaw::openclaw_set_api_key,
             openclaw::openclaw_remove_api_key,
+            openclaw_config::openclaw_config_get,
+            openclaw_config::openclaw_config_update,
+            config_files::openclaw_config_backups_list,
+            config_files::openclaw_config_restore,
+            config_preview::openclaw_config_apply_preview,
+            config_preview::openclaw_config_discard_preview,
+            openclaw_paths::openclaw_profiles_list,
+            openclaw_paths::openclaw_profile_save,
+            openclaw_paths::openclaw_profile_delete,
+            openclaw_paths::openclaw_profile_switch,
+            agents::openclaw_agents_list,
+            agents::openclaw_agent_create,
+            agents::openclaw_agent_clone,
+            agents::openclaw_agent_delete,
+            agents::openclaw_agent_set_default,
+            agents::openclaw_agent_set_model,
+            agents::openclaw_agent_set_auth,
+            agents::openclaw_agent_remove_auth,
+            agents::openclaw_agent_bind,
+            agents::openclaw_agent_unbind,
+            providers::openclaw_providers_list,
+            providers::openclaw_validate_api_key,
+            providers::openclaw_custom_provider_save,
+            providers::openclaw_custom_provider_delete,
+            provider_keys::openclaw_provider_keys_list,
+            provider_keys::openclaw_provider_key_add,
+            provider_keys::openclaw_provider_key_remove,
+            provider_keys::openclaw_provider_key_set_primary,
+            provider_keys::openclaw_provider_keys_reorder,
+            vault::vault_status,
+            vault::vault_unlock,
+            vault::vault_lock,
+            vault::vault_reset,
+            vault::vault_set_passphrase,
+            vault::vault_list_keys,
+            vault::vault_store_key,
+            vault::vault_delete_key,
+            settings::settings_get,
+            settings::settings_load_error,
+            settings::settings_update,
+            settings::settings_set_openrouter_key,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
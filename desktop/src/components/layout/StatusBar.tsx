@@ -1,11 +1,55 @@
-import { Circle } from "lucide-react";
+import { useEffect, useState } from "react";
+import { Circle, Loader2 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { useSettings } from "@/hooks/useSettings";
+import {
+  onOpenClawGatewayStatus,
+  onOperation,
+  openclawGatewayStatus,
+  operationsList,
+} from "@/lib/tauri";
+import type { GatewayState, GatewayStatus, OperationStatus } from "@/types";
+
+const GATEWAY_COLORS: Record<GatewayState, string> = {
+  starting: "text-muted-foreground",
+  healthy: "text-success",
+  degraded: "text-warning",
+  down: "text-destructive",
+};
 
 export function StatusBar() {
-  const { openrouterApiKey, taskComplexity } = useSettings();
+  const { openrouterKeyRef, taskComplexity } = useSettings();
+
+  const isConfigured = !!openrouterKeyRef;
+  const [gateway, setGateway] = useState<GatewayStatus | null>(null);
+  // Running tasks, so it's clear why other setup actions report busy
+  const [tasks, setTasks] = useState<OperationStatus[]>([]);
 
-  const isConfigured = !!openrouterApiKey;
+  useEffect(() => {
+    openclawGatewayStatus()
+      .then(setGateway)
+      .catch(() => setGateway(null));
+    const unlisten = onOpenClawGatewayStatus(setGateway);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    operationsList()
+      .then((ops) => setTasks(ops.filter((op) => op.kind === "task")))
+      .catch(() => setTasks([]));
+    const unlisten = onOperation((op) => {
+      if (op.kind !== "task") return;
+      setTasks((prev) => {
+        const rest = prev.filter((t) => t.id !== op.id);
+        return op.state === "running" ? [...rest, op] : rest;
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   // We consider "api_reachable" effectively true if the key exists for the sake of the status bar.
   // Proper validation happens during chat.
@@ -28,6 +72,26 @@ export function StatusBar() {
           <span className="font-medium text-foreground capitalize">{taskComplexity}</span>
         </div>
       )}
+      {gateway && (
+        <div className="flex items-center gap-1.5" title={gateway.message}>
+          <span>Gateway:</span>
+          <Circle className={cn("size-2 fill-current", GATEWAY_COLORS[gateway.state])} />
+          <span className="capitalize">{gateway.state}</span>
+          {gateway.next_restart_at && (
+            <span>· restarting at {new Date(gateway.next_restart_at).toLocaleTimeString()}</span>
+          )}
+        </div>
+      )}
+      {tasks.length > 0 && (
+        <div className="ml-auto flex items-center gap-1.5">
+          <Loader2 className="size-3 animate-spin" />
+          <span>
+            {tasks[0].label}
+            {tasks[0].step && ` · ${tasks[0].step}`}
+            {tasks.length > 1 && ` (+${tasks.length - 1})`}
+          </span>
+        </div>
+      )}
     </div>
   );
 }
@@ -0,0 +1,248 @@
+import { useCallback, useEffect, useState } from "react";
+import { Server } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+  onOpenClawConfigChanged,
+  onOpenClawGatewayConnection,
+  onOpenClawGatewayEvent,
+  onOpenClawGatewayStatus,
+  onSettingsChanged,
+  openclawGatewayChatEnable,
+  openclawGatewayCheckNow,
+  openclawGatewayConnection,
+  openclawGatewayRestart,
+  openclawGatewaySetLocal,
+  openclawGatewaySetRemote,
+  openclawGatewayStatus,
+  openclawGatewayTarget,
+  openclawGatewayVerify,
+  settingsUpdate,
+  vaultStoreKey,
+} from "@/lib/tauri";
+import type {
+  GatewayCheck,
+  GatewayConnection,
+  GatewayEvent,
+  GatewayStatus,
+  GatewayTarget,
+} from "@/types";
+
+// Live events kept for display
+const MAX_EVENTS = 20;
+
+export function GatewayCard() {
+  const [target, setTarget] = useState<GatewayTarget | null>(null);
+  const [check, setCheck] = useState<GatewayCheck | null>(null);
+  const [status, setStatus] = useState<GatewayStatus | null>(null);
+  const [connection, setConnection] = useState<GatewayConnection | null>(null);
+  const [events, setEvents] = useState<GatewayEvent[]>([]);
+  const [draft, setDraft] = useState({ url: "", token: "", sshTarget: "" });
+  const [rejected, setRejected] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    openclawGatewayTarget()
+      .then(setTarget)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  useEffect(() => {
+    load();
+    openclawGatewayStatus()
+      .then(setStatus)
+      .catch((err) => setError(String(err)));
+    openclawGatewayConnection()
+      .then(setConnection)
+      .catch((err) => setError(String(err)));
+    const unlistenConfig = onOpenClawConfigChanged(load);
+    const unlistenSettings = onSettingsChanged(load);
+    const unlistenStatus = onOpenClawGatewayStatus(setStatus);
+    const unlistenConnection = onOpenClawGatewayConnection(setConnection);
+    const unlistenEvents = onOpenClawGatewayEvent((event) =>
+      setEvents((prev) => [event, ...prev].slice(0, MAX_EVENTS))
+    );
+    return () => {
+      unlistenConfig.then((fn) => fn());
+      unlistenSettings.then((fn) => fn());
+      unlistenStatus.then((fn) => fn());
+      unlistenConnection.then((fn) => fn());
+      unlistenEvents.then((fn) => fn());
+    };
+  }, [load]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const saveRemote = async (force: boolean) => {
+    setError(null);
+    setRejected(false);
+    try {
+      const token = draft.token.trim();
+      const stored = token ? await vaultStoreKey("openclaw-gateway", "remote", token) : null;
+      await openclawGatewaySetRemote(
+        {
+          url: draft.url.trim(),
+          tokenRef: stored?.key_ref ?? null,
+          sshTarget: draft.sshTarget.trim() || null,
+        },
+        force
+      );
+      setDraft({ url: "", token: "", sshTarget: "" });
+      load();
+    } catch (err) {
+      setError(String(err));
+      if (!force) setRejected(true);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="text-base flex items-center gap-2">
+          <Server className="size-4" />
+          Gateway
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {target && (
+          <p className="text-sm">
+            {target.remote ? "Remote" : "Local"} gateway at{" "}
+            <span className="font-mono text-xs">{target.url}</span>
+            {target.ssh_target && (
+              <span className="text-xs text-muted-foreground"> (via {target.ssh_target})</span>
+            )}
+          </p>
+        )}
+        {status && (
+          <p className="text-xs text-muted-foreground">
+            <span className="capitalize">{status.state}</span> since{" "}
+            {new Date(status.since).toLocaleString()} · {status.message}
+            {status.restart_attempts > 0 && ` · ${status.restart_attempts} restart(s)`}
+          </p>
+        )}
+        {connection && (
+          <p className="text-xs text-muted-foreground">
+            Live connection: {connection.state}
+            {connection.server_version && ` · OpenClaw ${connection.server_version}`}
+            {connection.message && ` · ${connection.message}`}
+          </p>
+        )}
+        {check && (
+          <p
+            className={
+              check.reachable && check.authenticated !== false
+                ? "text-xs text-muted-foreground"
+                : "text-xs text-destructive"
+            }
+          >
+            {check.message}
+            {check.probe.version && ` · OpenClaw ${check.probe.version}`}
+            {check.probe.channels.length > 0 && ` · channels: ${check.probe.channels.join(", ")}`}
+          </p>
+        )}
+        {error && <p className="text-xs text-destructive">{error}</p>}
+
+        <div className="flex gap-2">
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => run(() => openclawGatewayVerify().then(setCheck))}
+          >
+            Check Connection
+          </Button>
+          <Button variant="outline" size="sm" onClick={() => run(openclawGatewayRestart)}>
+            Restart
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() =>
+              run(async () => {
+                await settingsUpdate({ gatewayAutoRestart: !(status?.auto_restart ?? true) });
+                await openclawGatewayCheckNow();
+              })
+            }
+          >
+            {status?.auto_restart ?? true ? "Disable Auto-Restart" : "Enable Auto-Restart"}
+          </Button>
+          <Button variant="ghost" size="sm" onClick={() => run(() => openclawGatewayChatEnable())}>
+            Enable Chat Endpoint
+          </Button>
+          {target?.remote && (
+            <Button variant="ghost" size="sm" onClick={() => run(() => openclawGatewaySetLocal())}>
+              Use Local Gateway
+            </Button>
+          )}
+        </div>
+
+        {events.length > 0 && (
+          <div className="max-h-40 space-y-1 overflow-y-auto rounded-md border p-2 font-mono text-xs">
+            {events.map((event, i) => (
+              <div key={`${event.seq ?? "x"}-${i}`} className="truncate">
+                <span className="font-medium">{event.event}</span>{" "}
+                <span className="text-muted-foreground">{JSON.stringify(event.payload)}</span>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="space-y-2">
+          <p className="text-xs text-muted-foreground">
+            Manage a gateway running on another machine. Restarts go over SSH when an SSH target
+            is set.
+          </p>
+          <div className="flex gap-2">
+            <input
+              placeholder="ws://beelink.local:18789"
+              value={draft.url}
+              onChange={(e) => {
+                setRejected(false);
+                setDraft((prev) => ({ ...prev, url: e.target.value }));
+              }}
+              className="flex-1 rounded-md border bg-background px-3 py-2 text-sm"
+            />
+            <input
+              type="password"
+              placeholder="Gateway token"
+              value={draft.token}
+              onChange={(e) => {
+                setRejected(false);
+                setDraft((prev) => ({ ...prev, token: e.target.value }));
+              }}
+              className="w-40 rounded-md border bg-background px-3 py-2 text-sm"
+            />
+            <input
+              placeholder="SSH target (optional)"
+              value={draft.sshTarget}
+              onChange={(e) => setDraft((prev) => ({ ...prev, sshTarget: e.target.value }))}
+              className="w-44 rounded-md border bg-background px-3 py-2 text-sm"
+            />
+          </div>
+          <div className="flex gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={!draft.url.trim()}
+              onClick={() => saveRemote(false)}
+            >
+              Use Remote Gateway
+            </Button>
+            {rejected && (
+              <Button variant="ghost" size="sm" onClick={() => saveRemote(true)}>
+                Save anyway
+              </Button>
+            )}
+          </div>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,154 @@
+import { useCallback, useEffect, useState } from "react";
+import { Bot } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+  onOpenClawConfigChanged,
+  openclawAgentBind,
+  openclawAgentCreate,
+  openclawAgentDelete,
+  openclawAgentSetDefault,
+  openclawAgentsList,
+  openclawAgentUnbind,
+} from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+import type { AgentSummary } from "@/types";
+
+export function OpenClawAgentsCard() {
+  const [agents, setAgents] = useState<AgentSummary[]>([]);
+  const [draft, setDraft] = useState({ id: "", name: "", model: "" });
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    openclawAgentsList()
+      .then(setAgents)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  useEffect(() => {
+    load();
+    const unlisten = onOpenClawConfigChanged(load);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="text-base flex items-center gap-2">
+          <Bot className="size-4" />
+          OpenClaw Agents
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && <p className="text-xs text-destructive">{error}</p>}
+
+        <div className="space-y-2">
+          {agents.map((agent) => {
+            const whatsapp = agent.bindings.some((b) => b.channel === "whatsapp");
+            return (
+              <div
+                key={agent.id}
+                className={cn(
+                  "flex items-center justify-between rounded-lg border px-3 py-2 text-sm",
+                  agent.is_default && "border-primary bg-primary/5"
+                )}
+              >
+                <div>
+                  <span className="font-medium">{agent.name ?? agent.id}</span>
+                  <span className="ml-2 text-xs text-muted-foreground">
+                    {[
+                      agent.effective_model ?? "no model",
+                      `${agent.auth_profiles.length} key(s)`,
+                      ...agent.bindings.map((b) =>
+                        b.accountId ? `${b.channel}:${b.accountId}` : b.channel
+                      ),
+                    ].join(" · ")}
+                  </span>
+                </div>
+                <div className="flex gap-1">
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() =>
+                      run(() =>
+                        whatsapp
+                          ? openclawAgentUnbind(agent.id, "whatsapp")
+                          : openclawAgentBind(agent.id, "whatsapp")
+                      )
+                    }
+                  >
+                    {whatsapp ? "Unbind WhatsApp" : "Bind WhatsApp"}
+                  </Button>
+                  {!agent.is_default && (
+                    <>
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        onClick={() => run(() => openclawAgentSetDefault(agent.id))}
+                      >
+                        Make Default
+                      </Button>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        onClick={() => run(() => openclawAgentDelete(agent.id))}
+                      >
+                        Remove
+                      </Button>
+                    </>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+
+        <div className="grid grid-cols-3 gap-2">
+          <input
+            placeholder="Agent id (e.g. work)"
+            value={draft.id}
+            onChange={(e) => setDraft((prev) => ({ ...prev, id: e.target.value }))}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            placeholder="Name (optional)"
+            value={draft.name}
+            onChange={(e) => setDraft((prev) => ({ ...prev, name: e.target.value }))}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            placeholder="Model (default: agents.defaults)"
+            value={draft.model}
+            onChange={(e) => setDraft((prev) => ({ ...prev, model: e.target.value }))}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!draft.id.trim()}
+          onClick={async () => {
+            await run(() =>
+              openclawAgentCreate(draft.id.trim(), draft.name || null, draft.model || null)
+            );
+            setDraft({ id: "", name: "", model: "" });
+          }}
+        >
+          Add Agent
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
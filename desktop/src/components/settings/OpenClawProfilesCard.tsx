@@ -0,0 +1,170 @@
+import { useCallback, useEffect, useState } from "react";
+import { Layers } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+  onSettingsChanged,
+  openclawProfileDelete,
+  openclawProfileSave,
+  openclawProfileSwitch,
+  openclawProfilesList,
+} from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+import type { OpenClawProfile, OpenClawProfilesSnapshot } from "@/types";
+
+const EMPTY_DRAFT: OpenClawProfile = {
+  name: "",
+  home: null,
+  gatewayUrl: null,
+  agentId: null,
+};
+
+export function OpenClawProfilesCard() {
+  const [snapshot, setSnapshot] = useState<OpenClawProfilesSnapshot | null>(null);
+  const [draft, setDraft] = useState<OpenClawProfile>(EMPTY_DRAFT);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    openclawProfilesList()
+      .then(setSnapshot)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  useEffect(() => {
+    load();
+    const unlisten = onSettingsChanged(load);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  const run = async (action: () => Promise<OpenClawProfilesSnapshot>) => {
+    setError(null);
+    try {
+      setSnapshot(await action());
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const setField = (field: keyof OpenClawProfile, value: string) =>
+    setDraft((prev) => ({
+      ...prev,
+      [field]: field === "name" ? value : value.trim() ? value : null,
+    }));
+
+  if (!snapshot) return null;
+
+  const rows: { name: string; profile: OpenClawProfile | null }[] = [
+    { name: "default", profile: null },
+    ...snapshot.profiles.map((p) => ({ name: p.name, profile: p })),
+  ];
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="text-base flex items-center gap-2">
+          <Layers className="size-4" />
+          OpenClaw Profiles
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <p className="text-xs text-muted-foreground">
+          Active: <span className="font-mono">{snapshot.active_paths.home}</span> ·{" "}
+          <span className="font-mono">{snapshot.active_paths.gateway_url}</span> · agent{" "}
+          <span className="font-mono">{snapshot.active_paths.agent_id}</span>
+        </p>
+        {error && <p className="text-xs text-destructive">{error}</p>}
+
+        <div className="space-y-2">
+          {rows.map(({ name, profile }) => {
+            const isActive = snapshot.active === name;
+            return (
+              <div
+                key={name}
+                className={cn(
+                  "flex items-center justify-between rounded-lg border px-3 py-2 text-sm",
+                  isActive && "border-primary bg-primary/5"
+                )}
+              >
+                <div>
+                  <span className="font-medium">{name}</span>
+                  {profile && (
+                    <span className="ml-2 text-xs text-muted-foreground">
+                      {[profile.home, profile.gatewayUrl, profile.agentId]
+                        .filter(Boolean)
+                        .join(" · ")}
+                    </span>
+                  )}
+                </div>
+                <div className="flex gap-1">
+                  {!isActive && (
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={() => run(() => openclawProfileSwitch(profile ? name : null))}
+                    >
+                      Use
+                    </Button>
+                  )}
+                  {profile && (
+                    <>
+                      <Button variant="ghost" size="sm" onClick={() => setDraft(profile)}>
+                        Edit
+                      </Button>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        onClick={() => run(() => openclawProfileDelete(name))}
+                      >
+                        Remove
+                      </Button>
+                    </>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+
+        <div className="grid grid-cols-2 gap-2">
+          <input
+            placeholder="Profile name (e.g. work)"
+            value={draft.name}
+            onChange={(e) => setField("name", e.target.value)}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            placeholder="Home (default ~/.openclaw)"
+            value={draft.home ?? ""}
+            onChange={(e) => setField("home", e.target.value)}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            placeholder="Gateway URL (default http://127.0.0.1:18789)"
+            value={draft.gatewayUrl ?? ""}
+            onChange={(e) => setField("gatewayUrl", e.target.value)}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            placeholder="Agent id (default main)"
+            value={draft.agentId ?? ""}
+            onChange={(e) => setField("agentId", e.target.value)}
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+          />
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!draft.name.trim()}
+          onClick={async () => {
+            await run(() => openclawProfileSave(draft));
+            setDraft(EMPTY_DRAFT);
+          }}
+        >
+          Save Profile
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,216 @@
+import { useCallback, useEffect, useState } from "react";
+import { ArrowDown, ArrowUp, KeyRound } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+  onOpenClawConfigChanged,
+  onSettingsChanged,
+  openclawProviderKeyAdd,
+  openclawProviderKeyRemove,
+  openclawProviderKeySetPrimary,
+  openclawProviderKeysList,
+  openclawProviderKeysReorder,
+  vaultStoreKey,
+} from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+import type { ProviderKeys } from "@/types";
+
+function formatTime(ms: number | null) {
+  return ms ? new Date(ms).toLocaleString() : null;
+}
+
+export function ProviderKeysCard() {
+  const [providers, setProviders] = useState<ProviderKeys[]>([]);
+  const [provider, setProvider] = useState("openrouter");
+  const [draft, setDraft] = useState({ name: "", key: "" });
+  const [rejected, setRejected] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    openclawProviderKeysList()
+      .then(setProviders)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  useEffect(() => {
+    load();
+    const unlistenConfig = onOpenClawConfigChanged(load);
+    const unlistenSettings = onSettingsChanged(load);
+    return () => {
+      unlistenConfig.then((fn) => fn());
+      unlistenSettings.then((fn) => fn());
+    };
+  }, [load]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+      load();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const addKey = async (force: boolean) => {
+    setError(null);
+    setRejected(false);
+    try {
+      const name = draft.name.trim();
+      const stored = await vaultStoreKey(provider, name, draft.key.trim());
+      await openclawProviderKeyAdd(provider, name, stored.key_ref, false, force);
+      setDraft({ name: "", key: "" });
+      load();
+    } catch (err) {
+      setError(String(err));
+      if (!force) setRejected(true);
+    }
+  };
+
+  const current = providers.find((p) => p.provider === provider);
+
+  const move = (index: number, delta: number) => {
+    if (!current) return;
+    const ids = current.keys.map((k) => k.profile_id);
+    const [id] = ids.splice(index, 1);
+    ids.splice(index + delta, 0, id);
+    run(() => openclawProviderKeysReorder(provider, ids));
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="text-base flex items-center gap-2">
+          <KeyRound className="size-4" />
+          Provider Keys &amp; Failover
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <p className="text-xs text-muted-foreground">
+          Store several named keys per provider. OpenClaw uses the primary key first and falls
+          back to the others in order.
+        </p>
+        {error && <p className="text-xs text-destructive">{error}</p>}
+
+        <select
+          value={provider}
+          onChange={(e) => setProvider(e.target.value)}
+          className="rounded-md border bg-background px-3 py-2 text-sm"
+        >
+          {providers.map((p) => (
+            <option key={p.provider} value={p.provider}>
+              {p.label} ({p.keys.length})
+            </option>
+          ))}
+        </select>
+
+        <div className="space-y-2">
+          {current?.keys.map((key, index) => (
+            <div
+              key={key.profile_id}
+              className={cn(
+                "flex items-center justify-between rounded-lg border px-3 py-2 text-sm",
+                key.primary && "border-primary bg-primary/5"
+              )}
+            >
+              <div className="min-w-0">
+                <div>
+                  <span className="font-medium">{key.name}</span>
+                  {key.masked && (
+                    <span className="ml-2 text-xs text-muted-foreground">{key.masked}</span>
+                  )}
+                  {key.primary && <span className="ml-2 text-xs text-primary">primary</span>}
+                </div>
+                <div className="text-xs text-muted-foreground">
+                  {formatTime(key.last_used)
+                    ? `Last used ${formatTime(key.last_used)}`
+                    : "Not used yet"}
+                  {key.error_count > 0 && (
+                    <span className="text-destructive">
+                      {" "}
+                      · {key.error_count} error(s)
+                      {key.last_error && `: ${key.last_error}`}
+                      {key.last_failure_at && ` at ${formatTime(key.last_failure_at)}`}
+                    </span>
+                  )}
+                  {key.cooldown_until && key.cooldown_until > Date.now() && (
+                    <span> · cooling down until {formatTime(key.cooldown_until)}</span>
+                  )}
+                </div>
+              </div>
+              <div className="flex gap-1">
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  disabled={index === 0}
+                  onClick={() => move(index, -1)}
+                >
+                  <ArrowUp className="size-3" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  disabled={index === current.keys.length - 1}
+                  onClick={() => move(index, 1)}
+                >
+                  <ArrowDown className="size-3" />
+                </Button>
+                {!key.primary && (
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    onClick={() => run(() => openclawProviderKeySetPrimary(provider, key.name))}
+                  >
+                    Make Primary
+                  </Button>
+                )}
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => run(() => openclawProviderKeyRemove(provider, key.name))}
+                >
+                  Remove
+                </Button>
+              </div>
+            </div>
+          ))}
+        </div>
+
+        <div className="flex gap-2">
+          <input
+            placeholder="Name (e.g. company)"
+            value={draft.name}
+            onChange={(e) => {
+              setRejected(false);
+              setDraft((prev) => ({ ...prev, name: e.target.value }));
+            }}
+            className="w-40 rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <input
+            type="password"
+            placeholder="Paste API key"
+            value={draft.key}
+            onChange={(e) => {
+              setRejected(false);
+              setDraft((prev) => ({ ...prev, key: e.target.value }));
+            }}
+            className="flex-1 rounded-md border bg-background px-3 py-2 text-sm"
+          />
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={!draft.name.trim() || !draft.key.trim()}
+            onClick={() => addKey(false)}
+          >
+            Add Key
+          </Button>
+          {rejected && (
+            <Button variant="ghost" size="sm" onClick={() => addKey(true)}>
+              Add anyway
+            </Button>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
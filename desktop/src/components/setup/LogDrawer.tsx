@@ -1,13 +1,18 @@
 import { useState } from "react";
-import { ChevronDown, ChevronUp, Terminal } from "lucide-react";
+import { ChevronDown, ChevronUp, Square, Terminal } from "lucide-react";
+import { Button } from "@/components/ui/button";
 
 interface LogDrawerProps {
   logs: string[];
   logsEndRef: React.RefObject<HTMLDivElement | null>;
+  // Set while a process is running: answer its prompts, or stop it
+  onInput?: (line: string) => void;
+  onCancel?: () => void;
 }
 
-export function LogDrawer({ logs, logsEndRef }: LogDrawerProps) {
+export function LogDrawer({ logs, logsEndRef, onInput, onCancel }: LogDrawerProps) {
   const [expanded, setExpanded] = useState(false);
+  const [input, setInput] = useState("");
 
   return (
     <div className="border-t">
@@ -36,6 +41,33 @@ export function LogDrawer({ logs, logsEndRef }: LogDrawerProps) {
           <div ref={logsEndRef} />
         </div>
       )}
+      {(onInput || onCancel) && (
+        <div className="flex items-center gap-2 border-t px-4 py-2">
+          {onInput && (
+            <form
+              className="flex-1"
+              onSubmit={(e) => {
+                e.preventDefault();
+                onInput(input);
+                setInput("");
+              }}
+            >
+              <input
+                value={input}
+                onChange={(e) => setInput(e.target.value)}
+                placeholder="Type a reply and press Enter"
+                className="w-full rounded-md border bg-background px-2 py-1 font-mono text-xs focus:outline-none focus:ring-2 focus:ring-ring"
+              />
+            </form>
+          )}
+          {onCancel && (
+            <Button variant="ghost" size="xs" onClick={onCancel} className="text-destructive">
+              <Square className="size-3" />
+              Cancel
+            </Button>
+          )}
+        </div>
+      )}
     </div>
   );
 }
@@ -1,7 +1,12 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 import type { DiagnosticsReport, SystemInfo } from "@/types";
-import { diagnosticsFull, systemInfo, openclawGatewayRestart } from "@/lib/tauri";
-import { useSettings } from "@/hooks/useSettings";
+import {
+  diagnosticsFull,
+  systemInfo,
+  openclawGatewayRestart,
+  openclawSetVersion,
+  openclawVersion,
+} from "@/lib/tauri";
 
 const DIAGNOSTICS_POLL_INTERVAL = 10_000; // 10 seconds
 
@@ -12,13 +17,12 @@ export function useDiagnostics() {
   const [countdown, setCountdown] = useState(DIAGNOSTICS_POLL_INTERVAL / 1000);
   const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const countdownRef = useRef<ReturnType<typeof setInterval> | null>(null);
-  const { openrouterApiKey } = useSettings();
 
   const refresh = useCallback(async () => {
     setLoading(true);
     try {
       const [diagResult, sysResult] = await Promise.all([
-        diagnosticsFull(openrouterApiKey),
+        diagnosticsFull(),
         systemInfo(),
       ]);
       setReport(diagResult);
@@ -29,7 +33,7 @@ export function useDiagnostics() {
       setLoading(false);
       setCountdown(DIAGNOSTICS_POLL_INTERVAL / 1000);
     }
-  }, [openrouterApiKey]);
+  }, []);
 
   // Auto-refresh polling
   useEffect(() => {
@@ -53,6 +57,11 @@ export function useDiagnostics() {
           case "start-openclaw-gateway":
             await openclawGatewayRestart();
             break;
+          case "upgrade-openclaw": {
+            const { pinned } = await openclawVersion();
+            await openclawSetVersion(pinned);
+            break;
+          }
           default:
             console.warn("Unknown diagnostic action:", command);
             return;
@@ -1,14 +1,17 @@
 import { useCallback, useEffect, useRef } from "react";
 import { useAtom } from "jotai";
 import { load } from "@tauri-apps/plugin-store";
-import { chatMessagesAtom, chatLoadingAtom } from "@/store/atoms";
-import { openrouterChat, onChatToken } from "@/lib/tauri";
+import { chatMessagesAtom, chatLoadingAtom, chatBackendAtom } from "@/store/atoms";
+import { openrouterChat, openclawGatewayChat, onChatToken } from "@/lib/tauri";
 import { useSettings } from "@/hooks/useSettings";
 import { QUICK_MODEL, STANDARD_MODEL, COMPLEX_MODEL, DEFAULT_TASK_COMPLEXITY, DEFAULT_SYSTEM_PROMPT } from "@/store/constants";
-import type { ChatMessage, Message } from "@/types";
+import type { ChatBackend, ChatMessage, Message } from "@/types";
 
 const CHAT_STORE_PATH = "chat.json";
 const CHAT_STORE_KEY = "chatHistory";
+const CHAT_BACKEND_KEY = "chatBackend";
+// Model label shown on messages answered by the OpenClaw agent
+const OPENCLAW_AGENT_MODEL = "openclaw-agent";
 
 async function persistMessages(messages: ChatMessage[]) {
   try {
@@ -19,6 +22,24 @@ async function persistMessages(messages: ChatMessage[]) {
   }
 }
 
+async function persistBackend(backend: ChatBackend) {
+  try {
+    const store = await load(CHAT_STORE_PATH, { defaults: {}, autoSave: true });
+    await store.set(CHAT_BACKEND_KEY, backend);
+  } catch {
+    // Best-effort, like the history
+  }
+}
+
+async function loadPersistedBackend(): Promise<ChatBackend | null> {
+  try {
+    const store = await load(CHAT_STORE_PATH, { defaults: {}, autoSave: true });
+    return (await store.get<ChatBackend>(CHAT_BACKEND_KEY)) ?? null;
+  } catch {
+    return null;
+  }
+}
+
 async function loadPersistedMessages(): Promise<ChatMessage[]> {
   try {
     const store = await load(CHAT_STORE_PATH, { defaults: {}, autoSave: true });
@@ -32,7 +53,8 @@ async function loadPersistedMessages(): Promise<ChatMessage[]> {
 export function useOpenRouterChat() {
   const [messages, setMessages] = useAtom(chatMessagesAtom);
   const [loading, setLoading] = useAtom(chatLoadingAtom);
-  const { openrouterApiKey, taskComplexity = DEFAULT_TASK_COMPLEXITY } = useSettings();
+  const [backend, setBackendState] = useAtom(chatBackendAtom);
+  const { openrouterKeyRef, taskComplexity = DEFAULT_TASK_COMPLEXITY } = useSettings();
   
   const streamContentRef = useRef("");
   const loadedRef = useRef(false);
@@ -46,21 +68,35 @@ export function useOpenRouterChat() {
         setMessages(history);
       }
     });
-  }, [setMessages]);
+    loadPersistedBackend().then((saved) => {
+      if (saved) setBackendState(saved);
+    });
+  }, [setMessages, setBackendState]);
+
+  // The backend belongs to the conversation and is saved alongside its history
+  const setBackend = useCallback(
+    (next: ChatBackend) => {
+      setBackendState(next);
+      persistBackend(next);
+    },
+    [setBackendState]
+  );
 
   const sendMessage = useCallback(
     async (content: string) => {
-      if (!openrouterApiKey) {
+      if (backend === "openrouter" && !openrouterKeyRef) {
         alert("Please set your OpenRouter API key in Settings first.");
         return;
       }
 
       const model =
-        taskComplexity === "complex"
-          ? COMPLEX_MODEL
-          : taskComplexity === "standard"
-            ? STANDARD_MODEL
-            : QUICK_MODEL;
+        backend === "openclaw"
+          ? OPENCLAW_AGENT_MODEL
+          : taskComplexity === "complex"
+            ? COMPLEX_MODEL
+            : taskComplexity === "standard"
+              ? STANDARD_MODEL
+              : QUICK_MODEL;
 
       const userMsg: ChatMessage = {
         id: crypto.randomUUID(),
@@ -108,7 +144,14 @@ export function useOpenRouterChat() {
           })),
         ];
 
-        const response = await openrouterChat(model, apiMessages, openrouterApiKey, true);
+        // The OpenClaw agent brings its own system prompt, tools and memory
+        const response =
+          backend === "openclaw"
+            ? await openclawGatewayChat(
+                apiMessages.filter((m) => m.role !== "system"),
+                true
+              )
+            : await openrouterChat(model, apiMessages, true);
 
         // Update with final stats and persist
         setMessages((prev) => {
@@ -152,7 +195,7 @@ export function useOpenRouterChat() {
         setLoading(false);
       }
     },
-    [messages, taskComplexity, openrouterApiKey, setMessages, setLoading]
+    [messages, backend, taskComplexity, openrouterKeyRef, setMessages, setLoading]
   );
 
   const clearChat = useCallback(() => {
@@ -160,5 +203,5 @@ export function useOpenRouterChat() {
     persistMessages([]);
   }, [setMessages]);
 
-  return { messages, loading, sendMessage, clearChat };
+  return { messages, loading, backend, setBackend, sendMessage, clearChat };
 }
@@ -1,50 +1,62 @@
 import { useState, useCallback, useEffect } from "react";
-import { load } from "@tauri-apps/plugin-store";
-import type { Theme, TaskComplexity } from "@/types";
+import type { AppSettings, SettingsPatch, Theme, TaskComplexity } from "@/types";
 import { DEFAULT_TASK_COMPLEXITY, DEFAULT_SYSTEM_PROMPT } from "@/store/constants";
-
-const STORE_PATH = "settings.json";
+import {
+  settingsGet,
+  settingsUpdate,
+  settingsSetOpenrouterKey,
+  onSettingsChanged,
+} from "@/lib/tauri";
 
 interface SettingsState {
   theme: Theme;
   systemPrompt: string;
-  openrouterApiKey: string;
+  // Vault reference for the OpenRouter key; the key itself stays in the backend
+  openrouterKeyRef: string | null;
   taskComplexity: TaskComplexity;
 }
 
 const DEFAULTS: SettingsState = {
   theme: "system",
   systemPrompt: DEFAULT_SYSTEM_PROMPT,
-  openrouterApiKey: "",
+  openrouterKeyRef: null,
   taskComplexity: DEFAULT_TASK_COMPLEXITY as TaskComplexity,
 };
 
+function fromBackend(settings: AppSettings): SettingsState {
+  return {
+    theme: settings.theme,
+    systemPrompt: settings.systemPrompt ?? DEFAULTS.systemPrompt,
+    openrouterKeyRef: settings.openrouterKeyRef,
+    taskComplexity: settings.taskComplexity,
+  };
+}
+
 export function useSettings() {
   const [settings, setSettings] = useState<SettingsState>(DEFAULTS);
   const [loaded, setLoaded] = useState(false);
 
-  // Load settings from store on mount
+  // Load settings from the backend on mount and follow changes made elsewhere
   useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
     (async () => {
       try {
-        const store = await load(STORE_PATH, { defaults: {}, autoSave: true });
-        const theme = await store.get<Theme>("theme");
-        const systemPrompt = await store.get<string>("systemPrompt");
-        const openrouterApiKey = await store.get<string>("openrouterApiKey");
-        const taskComplexity = await store.get<TaskComplexity>("taskComplexity");
-
-        setSettings({
-          theme: theme ?? DEFAULTS.theme,
-          systemPrompt: systemPrompt ?? DEFAULTS.systemPrompt,
-          openrouterApiKey: openrouterApiKey ?? DEFAULTS.openrouterApiKey,
-          taskComplexity: taskComplexity ?? DEFAULTS.taskComplexity,
-        });
+        const current = await settingsGet();
+        if (!cancelled) setSettings(fromBackend(current));
+        const stop = await onSettingsChanged((next) => setSettings(fromBackend(next)));
+        if (cancelled) stop();
+        else unlisten = stop;
       } catch (err) {
         console.error("Failed to load settings:", err);
       } finally {
-        setLoaded(true);
+        if (!cancelled) setLoaded(true);
       }
     })();
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
   }, []);
 
   // Apply theme whenever it changes
@@ -53,37 +65,35 @@ export function useSettings() {
     applyTheme(settings.theme);
   }, [settings.theme, loaded]);
 
-  const updateSetting = useCallback(
-    async <K extends keyof SettingsState>(key: K, value: SettingsState[K]) => {
-      setSettings((prev) => ({ ...prev, [key]: value }));
-      try {
-        const store = await load(STORE_PATH, { defaults: {}, autoSave: true });
-        await store.set(key, value);
-      } catch (err) {
-        console.error(`Failed to save setting ${key}:`, err);
-      }
-    },
-    []
-  );
+  const updateSettings = useCallback(async (patch: SettingsPatch) => {
+    try {
+      setSettings(fromBackend(await settingsUpdate(patch)));
+    } catch (err) {
+      console.error("Failed to save settings:", err);
+    }
+  }, []);
 
   const setTheme = useCallback(
-    (theme: Theme) => updateSetting("theme", theme),
-    [updateSetting]
+    (theme: Theme) => updateSettings({ theme }),
+    [updateSettings]
   );
 
   const setSystemPrompt = useCallback(
-    (prompt: string) => updateSetting("systemPrompt", prompt),
-    [updateSetting]
+    (prompt: string) =>
+      updateSettings({ systemPrompt: prompt === DEFAULT_SYSTEM_PROMPT ? null : prompt }),
+    [updateSettings]
   );
 
-  const setOpenrouterApiKey = useCallback(
-    (key: string) => updateSetting("openrouterApiKey", key),
-    [updateSetting]
-  );
+  // Hands the key to the backend vault; settings only keep its reference
+  const setOpenrouterApiKey = useCallback(async (key: string) => {
+    const next = await settingsSetOpenrouterKey(key);
+    setSettings(fromBackend(next));
+    return next.openrouterKeyRef;
+  }, []);
 
   const setTaskComplexity = useCallback(
-    (complexity: TaskComplexity) => updateSetting("taskComplexity", complexity),
-    [updateSetting]
+    (complexity: TaskComplexity) => updateSettings({ taskComplexity: complexity }),
+    [updateSettings]
   );
 
   return {
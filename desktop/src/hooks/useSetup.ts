@@ -7,6 +7,8 @@ import {
   openclawInstall,
   openclawConfigureModel,
   onOpenClawLog,
+  onOpenClawInstallProgress,
+  operationCancel,
 } from "@/lib/tauri";
 import { useSettings } from "@/hooks/useSettings";
 import { OPENCLAW_DEFAULT_MODEL } from "@/store/constants";
@@ -63,15 +65,10 @@ export function useSetup() {
   const [openclawChecked, setOpenclawChecked] = useState(false);
   const logsEndRef = useRef<HTMLDivElement>(null);
 
-  const { openrouterApiKey, setOpenrouterApiKey } = useSettings();
+  const { openrouterKeyRef, setOpenrouterApiKey } = useSettings();
   const [apiKeyInput, setApiKeyInput] = useState("");
-
-  // Pre-fill key input when saved settings load
-  useEffect(() => {
-    if (openrouterApiKey && !apiKeyInput) {
-      setApiKeyInput(openrouterApiKey);
-    }
-  }, [openrouterApiKey]); // eslint-disable-line react-hooks/exhaustive-deps
+  // Id of the running install, so it can be cancelled
+  const [activeOp, setActiveOp] = useState<string | null>(null);
 
   const addLog = useCallback((line: string) => {
     setLogs((prev) => [...prev, line]);
@@ -112,7 +109,8 @@ export function useSetup() {
             addLog("Testing OpenRouter API key...");
             await openrouterTestKey(key);
             await setOpenrouterApiKey(key);
-            addLog("API key verified and saved");
+            setApiKeyInput("");
+            addLog("API key verified and saved to the encrypted vault");
             updateStep(stepIndex, "done");
             break;
           }
@@ -136,11 +134,17 @@ export function useSetup() {
             } else {
               // Second call: user clicked Install
               addLog("Installing OpenClaw...");
+              const opId = crypto.randomUUID();
               const unlisten = await onOpenClawLog((event) => {
+                if (event.op_id !== opId) return;
                 addLog(`[${event.stream}] ${event.line}`);
               });
+              const unlistenProgress = await onOpenClawInstallProgress((progress) => {
+                if (progress.phase !== "downloading") addLog(progress.message);
+              });
+              setActiveOp(opId);
               try {
-                await openclawInstall();
+                await openclawInstall(undefined, opId);
                 addLog("Verifying installation...");
                 const status = await openclawCheck();
                 if (status.installed) {
@@ -153,21 +157,22 @@ export function useSetup() {
                   );
                 }
               } finally {
+                setActiveOp(null);
                 unlisten();
+                unlistenProgress();
               }
             }
             break;
           }
 
           case "configure-openclaw": {
-            const key = openrouterApiKey || apiKeyInput.trim();
-            if (!key) {
+            if (!openrouterKeyRef) {
               addLog("Error: No API key found. Please complete the previous step first.");
               updateStep(stepIndex, "error");
               break;
             }
             addLog(`Configuring OpenClaw with model: ${OPENCLAW_DEFAULT_MODEL}...`);
-            await openclawConfigureModel(OPENCLAW_DEFAULT_MODEL, key);
+            await openclawConfigureModel(OPENCLAW_DEFAULT_MODEL);
             addLog("Config written to ~/.openclaw/openclaw.json");
             addLog(
               "Auth profiles written to ~/.openclaw/agents/main/agent/auth-profiles.json"
@@ -194,7 +199,7 @@ export function useSetup() {
       updateStep,
       addLog,
       apiKeyInput,
-      openrouterApiKey,
+      openrouterKeyRef,
       setOpenrouterApiKey,
       openclawInstalled,
     ]
@@ -223,6 +228,16 @@ export function useSetup() {
     }
   }, [steps, updateStep, addLog]);
 
+  const cancelOperation = useCallback(async () => {
+    if (!activeOp) return;
+    try {
+      await operationCancel(activeOp);
+      addLog("Cancelling...");
+    } catch (err) {
+      addLog(`Cancel failed: ${err instanceof Error ? err.message : String(err)}`);
+    }
+  }, [activeOp, addLog]);
+
   const nextStep = useCallback(() => {
     const next = currentStep + 1;
     if (next < steps.length) {
@@ -241,6 +256,8 @@ export function useSetup() {
     openclawChecked,
     apiKeyInput,
     setApiKeyInput,
+    activeOp,
+    cancelOperation,
     runStep,
     nextStep,
     recheckOpenClaw,
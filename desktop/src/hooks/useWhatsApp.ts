@@ -8,7 +8,10 @@ import {
   openclawConfigureModel,
   openclawGatewayRestart,
   onOpenClawLog,
+  onOpenClawInstallProgress,
   onOpenClawQr,
+  operationCancel,
+  operationWriteStdin,
 } from "@/lib/tauri";
 import { useSettings } from "@/hooks/useSettings";
 import { OPENCLAW_DEFAULT_MODEL } from "@/store/constants";
@@ -80,8 +83,10 @@ export function useWhatsApp() {
   const [openclawInstalled, setOpenclawInstalled] = useState(false);
   const [gatewayRunning, setGatewayRunning] = useState(false);
   const [qrData, setQrData] = useState<string | null>(null);
+  // Id of the install/onboard/login process currently running, if any
+  const [activeOp, setActiveOp] = useState<string | null>(null);
   const logsEndRef = useRef<HTMLDivElement>(null);
-  const { openrouterApiKey } = useSettings();
+  const { openrouterKeyRef } = useSettings();
 
   const addLog = useCallback((line: string) => {
     setLogs((prev) => [...prev, line]);
@@ -145,16 +150,28 @@ export function useWhatsApp() {
               break;
             }
             addLog("Installing OpenClaw...");
+            const opId = crypto.randomUUID();
             const unlisten = await onOpenClawLog((event) => {
+              if (event.op_id !== opId) return;
               addLog(`[${event.stream}] ${event.line}`);
             });
+            const unlistenProgress = await onOpenClawInstallProgress((progress) => {
+              if (progress.phase !== "downloading") addLog(progress.message);
+            });
+            setActiveOp(opId);
             try {
-              await openclawInstall();
-              addLog("OpenClaw installed successfully");
+              const result = await openclawInstall(undefined, opId);
+              addLog(
+                result.integrity
+                  ? `OpenClaw ${result.version ?? "(local tarball)"} installed; checksum verified`
+                  : "OpenClaw installed with its install script"
+              );
               setOpenclawInstalled(true);
               updateStep(stepIndex, "done");
             } finally {
+              setActiveOp(null);
               unlisten();
+              unlistenProgress();
             }
             break;
           }
@@ -166,14 +183,18 @@ export function useWhatsApp() {
               break;
             }
             addLog("Running OpenClaw onboarding...");
+            const opId = crypto.randomUUID();
             const unlisten = await onOpenClawLog((event) => {
+              if (event.op_id !== opId) return;
               addLog(`[${event.stream}] ${event.line}`);
             });
+            setActiveOp(opId);
             try {
-              await openclawOnboard();
+              await openclawOnboard(false, opId);
               addLog("Onboarding complete");
               updateStep(stepIndex, "done");
             } finally {
+              setActiveOp(null);
               unlisten();
             }
             break;
@@ -211,11 +232,14 @@ export function useWhatsApp() {
               addLog("QR code received — scan with your phone");
             });
 
+            const opId = crypto.randomUUID();
+            setActiveOp(opId);
             try {
-              await openclawConnectWhatsapp();
+              await openclawConnectWhatsapp(false, opId);
               addLog("WhatsApp connected successfully");
               updateStep(stepIndex, "done");
             } finally {
+              setActiveOp(null);
               // Flush any trailing QR from log fallback
               if (qrLines.length > 0) {
                 const rows = qrLines.length;
@@ -233,12 +257,12 @@ export function useWhatsApp() {
 
           case "configure-model": {
             addLog("Configuring Daemon as default model...");
-            if (!openrouterApiKey) {
+            if (!openrouterKeyRef) {
               addLog("Error: OpenRouter API key is missing. Please configure it in Settings first.");
               updateStep(stepIndex, "error");
               break;
             }
-            await openclawConfigureModel(OPENCLAW_DEFAULT_MODEL, openrouterApiKey);
+            await openclawConfigureModel(OPENCLAW_DEFAULT_MODEL);
             addLog(`Model configured: openrouter/${OPENCLAW_DEFAULT_MODEL} (provider + auth-profiles written)`);
             updateStep(stepIndex, "done");
             break;
@@ -285,7 +309,30 @@ export function useWhatsApp() {
         updateStep(stepIndex, "error");
       }
     },
-    [steps, updateStep, addLog, openclawInstalled, gatewayRunning, openrouterApiKey]
+    [steps, updateStep, addLog, openclawInstalled, gatewayRunning, openrouterKeyRef]
+  );
+
+  const cancelOperation = useCallback(async () => {
+    if (!activeOp) return;
+    try {
+      await operationCancel(activeOp);
+      addLog("Cancelling...");
+    } catch (err) {
+      addLog(`Cancel failed: ${err instanceof Error ? err.message : String(err)}`);
+    }
+  }, [activeOp, addLog]);
+
+  const sendInput = useCallback(
+    async (line: string) => {
+      if (!activeOp) return;
+      try {
+        await operationWriteStdin(activeOp, line);
+        addLog(`> ${line}`);
+      } catch (err) {
+        addLog(`Input failed: ${err instanceof Error ? err.message : String(err)}`);
+      }
+    },
+    [activeOp, addLog]
   );
 
   const recheckOpenClaw = useCallback(async () => {
@@ -348,6 +395,9 @@ export function useWhatsApp() {
     openclawInstalled,
     gatewayRunning,
     qrData,
+    activeOp,
+    cancelOperation,
+    sendInput,
     runStep,
     retryStep,
     nextStep,
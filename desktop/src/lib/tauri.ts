@@ -9,8 +9,43 @@ import type {
   SystemInfo,
   OpenClawStatus,
   OpenClawLogEvent,
+  OperationStatus,
   OpenClawQrEvent,
-  ApiKeysStatus,
+  InstallSource,
+  InstallProgress,
+  InstallResult,
+  OpenClawVersion,
+  VersionChange,
+  UninstallOptions,
+  UninstallReport,
+  SnapshotInfo,
+  SnapshotRestore,
+  ApiKeyStatus,
+  ProviderSpec,
+  KeyCheck,
+  ProviderKeys,
+  ModelSetup,
+  GatewayTokenStatus,
+  GatewayTarget,
+  GatewayCheck,
+  GatewayStatus,
+  GatewayConnection,
+  GatewayEvent,
+  RemoteGatewaySetup,
+  CustomProvider,
+  OpenClawConfigSnapshot,
+  ConfigBackup,
+  ConfigPreview,
+  OpenClawConfigChangedEvent,
+  ConfigWatcherError,
+  OpenClawProfile,
+  OpenClawProfilesSnapshot,
+  AgentSummary,
+  AgentModelConfig,
+  VaultStatus,
+  VaultKeySummary,
+  AppSettings,
+  SettingsPatch,
 } from "@/types";
 
 // OpenRouter commands
@@ -18,10 +53,23 @@ import type {
 export async function openrouterChat(
   model: string,
   messages: Message[],
-  apiKey: string,
   stream: boolean
 ): Promise<ChatResponse> {
-  return invoke("openrouter_chat", { model, messages, apiKey, stream });
+  return invoke("openrouter_chat", { model, messages, stream });
+}
+
+// OpenClaw gateway chat (same streaming events as openrouterChat)
+
+export async function openclawGatewayChat(
+  messages: Message[],
+  stream: boolean,
+  agentId?: string
+): Promise<ChatResponse> {
+  return invoke("openclaw_gateway_chat", { messages, stream, agentId });
+}
+
+export async function openclawGatewayChatEnable(dryRun = false): Promise<ConfigPreview | null> {
+  return invoke("openclaw_gateway_chat_enable", { dryRun });
 }
 
 export async function openrouterTestKey(apiKey: string): Promise<void> {
@@ -36,8 +84,8 @@ export async function detectOs(): Promise<string> {
 
 // Diagnostics commands
 
-export async function diagnosticsFull(apiKey: string): Promise<DiagnosticsReport> {
-  return invoke("diagnostics_full", { apiKey });
+export async function diagnosticsFull(): Promise<DiagnosticsReport> {
+  return invoke("diagnostics_full");
 }
 
 export async function systemInfo(): Promise<SystemInfo> {
@@ -64,36 +112,471 @@ export async function openclawCheck(): Promise<OpenClawStatus> {
   return invoke("openclaw_check");
 }
 
-export async function openclawInstall(): Promise<void> {
-  return invoke("openclaw_install");
+// Long-running commands take an optional `opId` (used to cancel the operation or write to
+// its stdin while it runs, and to tag its log lines) and `timeoutSecs` (a default applies).
+
+// Installs the pinned release from npm unless `source` says otherwise (OpenClaw's install
+// script while the pinned release's checksum is unknown); a tarball's checksum is verified
+// before anything is installed
+export async function openclawInstall(
+  source?: InstallSource,
+  opId?: string,
+  timeoutSecs?: number
+): Promise<InstallResult> {
+  return invoke("openclaw_install", { source, opId, timeoutSecs });
+}
+
+export async function openclawVersion(): Promise<OpenClawVersion> {
+  return invoke("openclaw_version");
+}
+
+// Installs `version` through the verified installer; the previous version is reinstalled if
+// the new one fails. Versions older than this app supports need `force`.
+export async function openclawSetVersion(
+  version: string,
+  options: {
+    registry?: string;
+    // expected SRI checksum of the release's tarball
+    integrity?: string;
+    force?: boolean;
+    opId?: string;
+    timeoutSecs?: number;
+  } = {}
+): Promise<VersionChange> {
+  return invoke("openclaw_set_version", { version, ...options });
+}
+
+// With dryRun, returns the steps without running them.
+export async function openclawUninstall(
+  options: UninstallOptions = {},
+  dryRun = false
+): Promise<UninstallReport> {
+  return invoke("openclaw_uninstall", { options, dryRun });
+}
+
+// Snapshots of the OpenClaw state directory
+
+export async function openclawSnapshotsList(): Promise<SnapshotInfo[]> {
+  return invoke("openclaw_snapshots_list");
+}
+
+export async function openclawSnapshotCreate(
+  label?: string,
+  passphrase?: string
+): Promise<SnapshotInfo> {
+  return invoke("openclaw_snapshot_create", { label, passphrase });
+}
+
+export async function openclawSnapshotImport(path: string): Promise<SnapshotInfo> {
+  return invoke("openclaw_snapshot_import", { path });
+}
+
+export async function openclawSnapshotDelete(id: string): Promise<void> {
+  return invoke("openclaw_snapshot_delete", { id });
+}
+
+export async function openclawSnapshotRestore(
+  id: string,
+  passphrase?: string
+): Promise<SnapshotRestore> {
+  return invoke("openclaw_snapshot_restore", { id, passphrase });
+}
+
+// Config-mutating commands take `dryRun`: when true nothing is written and a preview is
+// returned, which can be applied with openclawConfigApplyPreview. Otherwise they return null.
+
+export async function openclawOnboard(
+  dryRun = false,
+  opId?: string,
+  timeoutSecs?: number
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_onboard", { dryRun, opId, timeoutSecs });
+}
+
+export async function openclawConnectWhatsapp(
+  dryRun = false,
+  opId?: string,
+  timeoutSecs?: number
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_connect_whatsapp", { dryRun, opId, timeoutSecs });
+}
+
+// Operations
+
+// Running tasks and processes, oldest first
+export async function operationsList(): Promise<OperationStatus[]> {
+  return invoke("operations_list");
+}
+
+export async function operationCancel(opId: string): Promise<void> {
+  return invoke("operation_cancel", { opId });
+}
+
+// Sends `line` plus a newline to the operation's stdin, e.g. to answer a prompt
+export async function operationWriteStdin(opId: string, line: string): Promise<void> {
+  return invoke("operation_write_stdin", { opId, line });
+}
+
+export async function openclawConfigureModel(
+  model: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_configure_model", { model, dryRun });
+}
+
+// Registers several OpenRouter models with fallbacks, an image model and per-agent or
+// per-channel overrides
+export async function openclawConfigureModels(
+  setup: ModelSetup,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_configure_models", { setup, dryRun });
+}
+
+export async function openclawGatewayTarget(): Promise<GatewayTarget> {
+  return invoke("openclaw_gateway_target");
 }
 
-export async function openclawOnboard(): Promise<void> {
-  return invoke("openclaw_onboard");
+export async function openclawGatewayVerify(): Promise<GatewayCheck> {
+  return invoke("openclaw_gateway_verify");
 }
 
-export async function openclawConnectWhatsapp(): Promise<void> {
-  return invoke("openclaw_connect_whatsapp");
+export async function openclawGatewaySetRemote(
+  setup: RemoteGatewaySetup,
+  force = false,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_gateway_set_remote", { setup, force, dryRun });
 }
 
-export async function openclawConfigureModel(model: string, apiKey: string): Promise<void> {
-  return invoke("openclaw_configure_model", { model, apiKey });
+export async function openclawGatewaySetLocal(dryRun = false): Promise<ConfigPreview | null> {
+  return invoke("openclaw_gateway_set_local", { dryRun });
+}
+
+export async function openclawGatewayStatus(): Promise<GatewayStatus> {
+  return invoke("openclaw_gateway_status");
+}
+
+export async function openclawGatewayCheckNow(): Promise<void> {
+  return invoke("openclaw_gateway_check_now");
+}
+
+export async function openclawGatewayConnection(): Promise<GatewayConnection> {
+  return invoke("openclaw_gateway_connection");
+}
+
+export async function openclawGatewayReconnect(): Promise<void> {
+  return invoke("openclaw_gateway_reconnect");
+}
+
+export async function openclawGatewayCall<T = unknown>(
+  method: string,
+  params?: Record<string, unknown>
+): Promise<T> {
+  return invoke("openclaw_gateway_call", { method, params });
+}
+
+export async function openclawGatewayTokenStatus(): Promise<GatewayTokenStatus> {
+  return invoke("openclaw_gateway_token_status");
+}
+
+export async function openclawGatewayTokenSync(restart = false): Promise<GatewayTokenStatus> {
+  return invoke("openclaw_gateway_token_sync", { restart });
+}
+
+// No dry run: the config and the service definitions change together
+export async function openclawGatewayTokenRotate(restart = true): Promise<void> {
+  return invoke("openclaw_gateway_token_rotate", { restart });
 }
 
 export async function openclawGatewayRestart(): Promise<void> {
   return invoke("openclaw_gateway_restart");
 }
 
-export async function openclawGetApiKeys(): Promise<ApiKeysStatus> {
+export async function openclawGetApiKeys(): Promise<ApiKeyStatus[]> {
   return invoke("openclaw_get_api_keys");
 }
 
-export async function openclawSetApiKey(provider: string, key: string): Promise<void> {
-  return invoke("openclaw_set_api_key", { provider, key });
+// The key is checked against the provider first; `force` saves it even if the check fails
+export async function openclawSetApiKey(
+  provider: string,
+  keyRef: string,
+  force = false,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_set_api_key", { provider, keyRef, force, dryRun });
+}
+
+export async function openclawValidateApiKey(provider: string, keyRef: string): Promise<KeyCheck> {
+  return invoke("openclaw_validate_api_key", { provider, keyRef });
+}
+
+// Removes every key OpenClaw has for the provider, not just "<provider>:default"
+export async function openclawRemoveApiKey(
+  provider: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_remove_api_key", { provider, dryRun });
+}
+
+// Provider registry
+
+export async function openclawProvidersList(): Promise<ProviderSpec[]> {
+  return invoke("openclaw_providers_list");
+}
+
+export async function openclawCustomProviderSave(
+  provider: CustomProvider
+): Promise<ProviderSpec[]> {
+  return invoke("openclaw_custom_provider_save", { provider });
+}
+
+export async function openclawCustomProviderDelete(id: string): Promise<ProviderSpec[]> {
+  return invoke("openclaw_custom_provider_delete", { id });
+}
+
+// Named keys per provider (`<provider>:<name>` auth profiles) with failover order
+
+export async function openclawProviderKeysList(): Promise<ProviderKeys[]> {
+  return invoke("openclaw_provider_keys_list");
+}
+
+export async function openclawProviderKeyAdd(
+  provider: string,
+  name: string,
+  keyRef: string,
+  primary = false,
+  force = false,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_provider_key_add", { provider, name, keyRef, primary, force, dryRun });
+}
+
+export async function openclawProviderKeyRemove(
+  provider: string,
+  name: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_provider_key_remove", { provider, name, dryRun });
+}
+
+export async function openclawProviderKeySetPrimary(
+  provider: string,
+  name: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_provider_key_set_primary", { provider, name, dryRun });
+}
+
+export async function openclawProviderKeysReorder(
+  provider: string,
+  profileIds: string[],
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_provider_keys_reorder", { provider, profileIds, dryRun });
+}
+
+// OpenClaw config commands
+
+export async function openclawConfigGet(): Promise<OpenClawConfigSnapshot> {
+  return invoke("openclaw_config_get");
+}
+
+// `patch` is a JSON merge patch: nested objects merge, null removes a key
+export async function openclawConfigUpdate(
+  patch: Record<string, unknown>,
+  dryRun = false
+): Promise<OpenClawConfigSnapshot> {
+  return invoke("openclaw_config_update", { patch, dryRun });
 }
 
-export async function openclawRemoveApiKey(provider: string): Promise<void> {
-  return invoke("openclaw_remove_api_key", { provider });
+export async function openclawConfigApplyPreview(id: string): Promise<OpenClawConfigSnapshot> {
+  return invoke("openclaw_config_apply_preview", { id });
+}
+
+export async function openclawConfigDiscardPreview(id: string): Promise<void> {
+  return invoke("openclaw_config_discard_preview", { id });
+}
+
+// OpenClaw profiles
+
+export async function openclawProfilesList(): Promise<OpenClawProfilesSnapshot> {
+  return invoke("openclaw_profiles_list");
+}
+
+export async function openclawProfileSave(
+  profile: OpenClawProfile
+): Promise<OpenClawProfilesSnapshot> {
+  return invoke("openclaw_profile_save", { profile });
+}
+
+export async function openclawProfileDelete(name: string): Promise<OpenClawProfilesSnapshot> {
+  return invoke("openclaw_profile_delete", { name });
+}
+
+// null switches back to the built-in profile
+export async function openclawProfileSwitch(
+  name: string | null
+): Promise<OpenClawProfilesSnapshot> {
+  return invoke("openclaw_profile_switch", { name });
+}
+
+// OpenClaw agents
+
+export async function openclawAgentsList(): Promise<AgentSummary[]> {
+  return invoke("openclaw_agents_list");
+}
+
+export async function openclawAgentCreate(
+  id: string,
+  name: string | null,
+  model: string | null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_create", { id, name, model, dryRun });
+}
+
+export async function openclawAgentClone(
+  sourceId: string,
+  newId: string,
+  name: string | null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_clone", { sourceId, newId, name, dryRun });
+}
+
+export async function openclawAgentDelete(
+  id: string,
+  deleteFiles = false,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_delete", { id, deleteFiles, dryRun });
+}
+
+export async function openclawAgentSetDefault(
+  id: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_set_default", { id, dryRun });
+}
+
+// null clears the agent's override so it follows agents.defaults.model
+export async function openclawAgentSetModel(
+  id: string,
+  model: AgentModelConfig | null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_set_model", { id, model, dryRun });
+}
+
+export async function openclawAgentSetAuth(
+  id: string,
+  provider: string,
+  keyRef: string,
+  profileName: string | null = null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_set_auth", { id, provider, keyRef, profileName, dryRun });
+}
+
+export async function openclawAgentRemoveAuth(
+  id: string,
+  profileId: string,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_remove_auth", { id, profileId, dryRun });
+}
+
+export async function openclawAgentBind(
+  id: string,
+  channel: string,
+  accountId: string | null = null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_bind", { id, channel, accountId, dryRun });
+}
+
+export async function openclawAgentUnbind(
+  id: string,
+  channel: string,
+  accountId: string | null = null,
+  dryRun = false
+): Promise<ConfigPreview | null> {
+  return invoke("openclaw_agent_unbind", { id, channel, accountId, dryRun });
+}
+
+// OpenClaw config backups
+
+export async function openclawConfigBackupsList(): Promise<ConfigBackup[]> {
+  return invoke("openclaw_config_backups_list");
+}
+
+export async function openclawConfigRestore(id: string): Promise<ConfigBackup> {
+  return invoke("openclaw_config_restore", { id });
+}
+
+// Vault commands
+
+export async function vaultStatus(): Promise<VaultStatus> {
+  return invoke("vault_status");
+}
+
+export async function vaultUnlock(passphrase?: string): Promise<VaultStatus> {
+  return invoke("vault_unlock", { passphrase });
+}
+
+// Discards a vault that can't be unlocked; the old file is kept alongside
+export async function vaultReset(): Promise<VaultStatus> {
+  return invoke("vault_reset");
+}
+
+export async function vaultLock(): Promise<VaultStatus> {
+  return invoke("vault_lock");
+}
+
+export async function vaultSetPassphrase(passphrase: string | null): Promise<VaultStatus> {
+  return invoke("vault_set_passphrase", { passphrase });
+}
+
+export async function vaultListKeys(): Promise<VaultKeySummary[]> {
+  return invoke("vault_list_keys");
+}
+
+export async function vaultStoreKey(
+  provider: string,
+  label: string,
+  secret: string
+): Promise<VaultKeySummary> {
+  return invoke("vault_store_key", { provider, label, secret });
+}
+
+export async function vaultDeleteKey(keyRef: string): Promise<void> {
+  return invoke("vault_delete_key", { keyRef });
+}
+
+// Settings commands
+
+export async function settingsGet(): Promise<AppSettings> {
+  return invoke("settings_get");
+}
+
+// Why settings couldn't be loaded at startup; the app is then running on defaults
+export async function settingsLoadError(): Promise<string | null> {
+  return invoke("settings_load_error");
+}
+
+export async function settingsUpdate(patch: SettingsPatch): Promise<AppSettings> {
+  return invoke("settings_update", { patch });
+}
+
+export async function settingsSetOpenrouterKey(key: string): Promise<AppSettings> {
+  return invoke("settings_set_openrouter_key", { key });
+}
+
+export function onSettingsChanged(
+  callback: (settings: AppSettings) => void
+): Promise<UnlistenFn> {
+  return listen<AppSettings>("settings-changed", (e) => callback(e.payload));
 }
 
 // OpenClaw event listeners
@@ -104,8 +587,56 @@ export function onOpenClawLog(
   return listen<OpenClawLogEvent>("openclaw-log", (e) => callback(e.payload));
 }
 
+// Fired when an operation starts, moves to another step, or ends
+export function onOperation(
+  callback: (status: OperationStatus) => void
+): Promise<UnlistenFn> {
+  return listen<OperationStatus>("openclaw-operation", (e) => callback(e.payload));
+}
+
+export function onOpenClawInstallProgress(
+  callback: (progress: InstallProgress) => void
+): Promise<UnlistenFn> {
+  return listen<InstallProgress>("openclaw-install-progress", (e) => callback(e.payload));
+}
+
 export function onOpenClawQr(
   callback: (event: OpenClawQrEvent) => void
 ): Promise<UnlistenFn> {
   return listen<OpenClawQrEvent>("openclaw-qr", (e) => callback(e.payload));
 }
+
+// Fired when openclaw.json or auth-profiles.json changes on disk, by this app or anything else
+export function onOpenClawGatewayStatus(
+  callback: (status: GatewayStatus) => void
+): Promise<UnlistenFn> {
+  return listen<GatewayStatus>("openclaw-gateway-status", (e) => callback(e.payload));
+}
+
+export function onOpenClawGatewayConnection(
+  callback: (connection: GatewayConnection) => void
+): Promise<UnlistenFn> {
+  return listen<GatewayConnection>("openclaw-gateway-connection", (e) => callback(e.payload));
+}
+
+export function onOpenClawGatewayEvent(
+  callback: (event: GatewayEvent) => void
+): Promise<UnlistenFn> {
+  return listen<GatewayEvent>("openclaw-gateway-event", (e) => callback(e.payload));
+}
+
+export function onOpenClawConfigChanged(
+  callback: (event: OpenClawConfigChangedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<OpenClawConfigChangedEvent>("openclaw-config-changed", (e) =>
+    callback(e.payload)
+  );
+}
+
+export function onOpenClawConfigWatcherError(
+  callback: (error: ConfigWatcherError) => void
+): Promise<UnlistenFn> {
+  return listen<ConfigWatcherError>("openclaw-config-watcher-error", (e) =>
+    callback(e.payload)
+  );
+}
@@ -2,17 +2,32 @@ import { useOpenRouterChat } from "@/hooks/useOpenRouter";
 import { MessageList } from "@/components/chat/MessageList";
 import { ChatInput } from "@/components/chat/ChatInput";
 import { ComplexitySelector } from "@/components/chat/ComplexitySelector";
-import { Trash2 } from "lucide-react";
+import { ChevronDown, Trash2 } from "lucide-react";
 import { Button } from "@/components/ui/button";
+import type { ChatBackend } from "@/types";
 
 export default function Chat() {
-  const { messages, loading, sendMessage, clearChat } = useOpenRouterChat();
+  const { messages, loading, backend, setBackend, sendMessage, clearChat } = useOpenRouterChat();
 
   return (
     <div className="flex h-full flex-col">
       {/* Header */}
       <div className="flex items-center justify-between border-b px-4 py-2">
-        <ComplexitySelector />
+        <div className="flex items-center gap-2">
+          <div className="relative">
+            <select
+              value={backend}
+              onChange={(e) => setBackend(e.target.value as ChatBackend)}
+              disabled={loading}
+              className="appearance-none rounded-md border bg-background pl-3 pr-8 py-1.5 text-sm focus:outline-none focus:ring-2 focus:ring-ring cursor-pointer"
+            >
+              <option value="openrouter">OpenRouter</option>
+              <option value="openclaw">OpenClaw agent</option>
+            </select>
+            <ChevronDown className="absolute right-2 top-1/2 -translate-y-1/2 size-3.5 text-muted-foreground pointer-events-none" />
+          </div>
+          {backend === "openrouter" && <ComplexitySelector />}
+        </div>
         <Button
           variant="ghost"
           size="xs"
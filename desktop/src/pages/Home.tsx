@@ -11,7 +11,7 @@ import { cn } from "@/lib/utils";
 
 export default function Home() {
   const navigate = useNavigate();
-  const { openrouterApiKey } = useSettings();
+  const { openrouterKeyRef } = useSettings();
   const [openclaw, setOpenclaw] = useState<OpenClawStatus | null>(null);
 
   useEffect(() => {
@@ -23,8 +23,8 @@ export default function Home() {
   const checks = [
     {
       label: "OpenRouter API Key",
-      ok: !!openrouterApiKey,
-      detail: openrouterApiKey ? "Configured" : "Missing — go to Settings",
+      ok: !!openrouterKeyRef,
+      detail: openrouterKeyRef ? "Configured" : "Missing — go to Settings",
     },
     {
       label: "OpenClaw Installed",
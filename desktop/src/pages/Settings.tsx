@@ -2,32 +2,41 @@ import { useCallback, useEffect, useState } from "react";
 import { Key, Monitor, Moon, Sun } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { GatewayCard } from "@/components/settings/GatewayCard";
+import { OpenClawAgentsCard } from "@/components/settings/OpenClawAgentsCard";
+import { OpenClawProfilesCard } from "@/components/settings/OpenClawProfilesCard";
+import { ProviderKeysCard } from "@/components/settings/ProviderKeysCard";
 import { useSettings } from "@/hooks/useSettings";
 import {
+  onOpenClawConfigChanged,
+  onOpenClawConfigWatcherError,
+  onSettingsChanged,
   openclawGetApiKeys,
+  openclawCustomProviderDelete,
+  openclawCustomProviderSave,
   openclawRemoveApiKey,
   openclawSetApiKey,
+  settingsLoadError,
+  vaultListKeys,
+  vaultReset,
+  vaultStatus,
+  vaultStoreKey,
 } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
-import type { ApiKeysStatus, Theme } from "@/types";
+import type {
+  ApiKeyStatus,
+  CustomProvider,
+  Theme,
+  VaultKeySummary,
+  VaultStatus,
+} from "@/types";
 
-const API_KEY_PROVIDERS = [
-  {
-    id: "gemini" as const,
-    label: "Gemini",
-    getKeyUrl: "https://aistudio.google.com/apikey",
-  },
-  {
-    id: "openai" as const,
-    label: "OpenAI",
-    getKeyUrl: "https://platform.openai.com/api-keys",
-  },
-  {
-    id: "anthropic" as const,
-    label: "Claude (Anthropic)",
-    getKeyUrl: "https://console.anthropic.com/settings/keys",
-  },
-] as const;
+const EMPTY_CUSTOM_PROVIDER: CustomProvider = {
+  id: "",
+  label: null,
+  baseUrl: "",
+  keyPattern: null,
+};
 
 const THEME_OPTIONS: { value: Theme; label: string; icon: React.ElementType }[] = [
   { value: "light", label: "Light", icon: Sun },
@@ -39,7 +48,7 @@ export default function Settings() {
   const {
     theme,
     systemPrompt,
-    openrouterApiKey,
+    openrouterKeyRef,
     taskComplexity,
     loaded,
     setTheme,
@@ -49,21 +58,95 @@ export default function Settings() {
   } = useSettings();
 
   const [promptDraft, setPromptDraft] = useState(systemPrompt);
-  const [apiKeys, setApiKeys] = useState<ApiKeysStatus | null>(null);
+  const [vaultKeys, setVaultKeys] = useState<VaultKeySummary[]>([]);
+  const [vault, setVault] = useState<VaultStatus | null>(null);
+  const [vaultError, setVaultError] = useState<string | null>(null);
+  const [settingsError, setSettingsError] = useState<string | null>(null);
+  const [watcherError, setWatcherError] = useState<string | null>(null);
+  const [openrouterDraft, setOpenrouterDraft] = useState("");
+  const [openrouterError, setOpenrouterError] = useState<string | null>(null);
+  const [apiKeys, setApiKeys] = useState<ApiKeyStatus[]>([]);
+  const [customDraft, setCustomDraft] = useState<CustomProvider>(EMPTY_CUSTOM_PROVIDER);
   const [apiKeyDrafts, setApiKeyDrafts] = useState<Record<string, string>>({});
   const [apiKeySaving, setApiKeySaving] = useState<string | null>(null);
   const [apiKeyError, setApiKeyError] = useState<string | null>(null);
+  // Provider whose key just failed the live check, offered "Save anyway"
+  const [apiKeyRejected, setApiKeyRejected] = useState<string | null>(null);
 
   const loadApiKeys = useCallback(() => {
     openclawGetApiKeys()
       .then(setApiKeys)
-      .catch(() => setApiKeys(null));
+      .catch(() => setApiKeys([]));
   }, []);
 
+  const saveApiKey = async (id: string, draft: string, force: boolean) => {
+    setApiKeySaving(id);
+    setApiKeyError(null);
+    setApiKeyRejected(null);
+    try {
+      const stored = await vaultStoreKey(id, "default", draft.trim());
+      await openclawSetApiKey(id, stored.key_ref, force);
+      setApiKeyDrafts((prev) => ({ ...prev, [id]: "" }));
+      loadApiKeys();
+    } catch (err) {
+      setApiKeyError(String(err));
+      if (!force) setApiKeyRejected(id);
+    } finally {
+      setApiKeySaving(null);
+    }
+  };
+
+  const loadVaultKeys = useCallback(() => {
+    vaultStatus()
+      .then(setVault)
+      .catch(() => setVault(null));
+    vaultListKeys()
+      .then(setVaultKeys)
+      .catch(() => setVaultKeys([]));
+  }, []);
+
+  const resetVault = async () => {
+    setVaultError(null);
+    try {
+      setVault(await vaultReset());
+      loadVaultKeys();
+      loadApiKeys();
+    } catch (err) {
+      setVaultError(String(err));
+    }
+  };
+
   useEffect(() => {
     loadApiKeys();
+    loadVaultKeys();
+    settingsLoadError()
+      .then(setSettingsError)
+      .catch(() => setSettingsError(null));
+  }, [loadApiKeys, loadVaultKeys]);
+
+  // Keys can also be changed by hand, by the openclaw CLI, or by switching profiles
+  useEffect(() => {
+    const unlistenConfig = onOpenClawConfigChanged((event) => {
+      if (event.file === "auth-profiles.json") loadApiKeys();
+    });
+    const unlistenWatcher = onOpenClawConfigWatcherError((error) => {
+      if (error.stopped) setWatcherError(error.message);
+    });
+    const unlistenSettings = onSettingsChanged(() => {
+      loadApiKeys();
+      settingsLoadError()
+        .then(setSettingsError)
+        .catch(() => setSettingsError(null));
+    });
+    return () => {
+      unlistenConfig.then((fn) => fn());
+      unlistenWatcher.then((fn) => fn());
+      unlistenSettings.then((fn) => fn());
+    };
   }, [loadApiKeys]);
 
+  const openrouterMasked = vaultKeys.find((k) => k.key_ref === openrouterKeyRef)?.masked;
+
   // Sync draft when settings load
   useEffect(() => {
     if (loaded) setPromptDraft(systemPrompt);
@@ -81,6 +164,56 @@ export default function Settings() {
       </div>
 
       <div className="flex-1 overflow-y-auto p-6 space-y-6 max-w-2xl">
+        {/* Settings store that couldn't be loaded at startup */}
+        {settingsError && (
+          <Card className="border-destructive">
+            <CardContent className="space-y-1">
+              <p className="text-sm text-destructive">
+                Your saved settings couldn't be loaded: {settingsError}
+              </p>
+              <p className="text-xs text-muted-foreground">
+                Defaults are in use. The old settings file is backed up before your next change
+                is saved.
+              </p>
+            </CardContent>
+          </Card>
+        )}
+
+        {watcherError && (
+          <Card className="border-destructive">
+            <CardContent className="space-y-1">
+              <p className="text-sm text-destructive">{watcherError}</p>
+              <p className="text-xs text-muted-foreground">
+                Changes made to openclaw.json outside the app won't show up until it restarts.
+              </p>
+            </CardContent>
+          </Card>
+        )}
+
+        {/* Vault that couldn't be opened at startup */}
+        {vault?.error && (
+          <Card className="border-destructive">
+            <CardHeader>
+              <CardTitle className="text-base flex items-center gap-2">
+                <Key className="size-4" />
+                Credential vault unavailable
+              </CardTitle>
+            </CardHeader>
+            <CardContent className="space-y-2">
+              <p className="text-sm text-destructive">{vault.error}</p>
+              <p className="text-xs text-muted-foreground">
+                Saved API keys can't be used until the vault is unlocked. Resetting starts an
+                empty vault and keeps the old file next to {vault.path}; you'll need to enter
+                your keys again.
+              </p>
+              <Button variant="outline" size="sm" onClick={resetVault}>
+                Reset vault
+              </Button>
+              {vaultError && <p className="text-xs text-destructive">{vaultError}</p>}
+            </CardContent>
+          </Card>
+        )}
+
         {/* Theme */}
         <Card>
           <CardHeader>
@@ -135,14 +268,43 @@ export default function Settings() {
           </CardHeader>
           <CardContent>
             <div className="space-y-2">
-              <label className="text-sm font-medium">API Key</label>
-              <input
-                type="password"
-                placeholder="sk-or-v1-..."
-                value={openrouterApiKey}
-                onChange={(e) => setOpenrouterApiKey(e.target.value)}
-                className="w-full rounded-md border bg-background px-3 py-2 text-sm"
-              />
+              <div className="flex items-center justify-between">
+                <label className="text-sm font-medium">API Key</label>
+                {openrouterMasked && (
+                  <span className="text-xs text-muted-foreground">{openrouterMasked}</span>
+                )}
+              </div>
+              <div className="flex gap-2">
+                <input
+                  type="password"
+                  placeholder={openrouterMasked ? "Enter new key to replace" : "sk-or-v1-..."}
+                  value={openrouterDraft}
+                  onChange={(e) => {
+                    setOpenrouterError(null);
+                    setOpenrouterDraft(e.target.value);
+                  }}
+                  className="flex-1 rounded-md border bg-background px-3 py-2 text-sm"
+                />
+                <Button
+                  variant="outline"
+                  size="sm"
+                  disabled={!openrouterDraft.trim()}
+                  onClick={async () => {
+                    try {
+                      await setOpenrouterApiKey(openrouterDraft.trim());
+                      setOpenrouterDraft("");
+                      loadVaultKeys();
+                    } catch (err) {
+                      setOpenrouterError(String(err));
+                    }
+                  }}
+                >
+                  Save
+                </Button>
+              </div>
+              {openrouterError && (
+                <p className="text-xs text-destructive">{openrouterError}</p>
+              )}
               <p className="text-xs text-muted-foreground">
                 Required for cloud completions. Get one at{" "}
                 <a
@@ -186,6 +348,11 @@ export default function Settings() {
           </CardContent>
         </Card>
 
+        {/* OpenClaw Profiles */}
+        <OpenClawProfilesCard />
+        <GatewayCard />
+        <OpenClawAgentsCard />
+
         {/* API Keys (OpenClaw) */}
         <Card>
           <CardHeader>
@@ -202,8 +369,8 @@ export default function Settings() {
             {apiKeyError && (
               <p className="text-xs text-destructive">{apiKeyError}</p>
             )}
-            {API_KEY_PROVIDERS.map(({ id, label, getKeyUrl }) => {
-              const status = apiKeys?.[id];
+            {apiKeys.map((status) => {
+              const { provider: id, label } = status;
               const draft = apiKeyDrafts[id] ?? "";
               const isSaving = apiKeySaving === id;
               return (
@@ -213,7 +380,7 @@ export default function Settings() {
                 >
                   <div className="flex items-center justify-between">
                     <span className="text-sm font-medium">{label}</span>
-                    {status?.configured && status.masked && (
+                    {status.configured && status.masked && (
                       <span className="text-xs text-muted-foreground">
                         {status.masked}
                       </span>
@@ -222,10 +389,11 @@ export default function Settings() {
                   <div className="flex gap-2">
                     <input
                       type="password"
-                      placeholder={status?.configured ? "Enter new key to replace" : "Paste API key"}
+                      placeholder={status.configured ? "Enter new key to replace" : "Paste API key"}
                       value={draft}
                       onChange={(e) => {
                         setApiKeyError(null);
+                        setApiKeyRejected(null);
                         setApiKeyDrafts((prev) => ({ ...prev, [id]: e.target.value }));
                       }}
                       className="flex-1 rounded-md border bg-background px-3 py-2 text-sm"
@@ -234,23 +402,21 @@ export default function Settings() {
                       variant="outline"
                       size="sm"
                       disabled={!draft.trim() || isSaving}
-                      onClick={async () => {
-                        setApiKeySaving(id);
-                        setApiKeyError(null);
-                        try {
-                          await openclawSetApiKey(id, draft.trim());
-                          setApiKeyDrafts((prev) => ({ ...prev, [id]: "" }));
-                          loadApiKeys();
-                        } catch (err) {
-                          setApiKeyError(String(err));
-                        } finally {
-                          setApiKeySaving(null);
-                        }
-                      }}
+                      onClick={() => saveApiKey(id, draft, false)}
                     >
-                      {isSaving ? "…" : "Save"}
+                      {isSaving ? "Checking…" : "Save"}
                     </Button>
-                    {status?.configured && (
+                    {apiKeyRejected === id && (
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        disabled={!draft.trim() || isSaving}
+                        onClick={() => saveApiKey(id, draft, true)}
+                      >
+                        Save anyway
+                      </Button>
+                    )}
+                    {status.configured && (
                       <Button
                         variant="ghost"
                         size="sm"
@@ -273,19 +439,84 @@ export default function Settings() {
                       </Button>
                     )}
                   </div>
-                  <a
-                    href={getKeyUrl}
-                    target="_blank"
-                    rel="noopener noreferrer"
-                    className="text-xs text-primary underline"
-                  >
-                    Get key
-                  </a>
+                  <div className="flex items-center gap-3">
+                    {status.get_key_url && (
+                      <a
+                        href={status.get_key_url}
+                        target="_blank"
+                        rel="noopener noreferrer"
+                        className="text-xs text-primary underline"
+                      >
+                        Get key
+                      </a>
+                    )}
+                    {status.custom && (
+                      <button
+                        className="text-xs text-muted-foreground underline"
+                        onClick={() =>
+                          openclawCustomProviderDelete(id)
+                            .then(loadApiKeys)
+                            .catch((err) => setApiKeyError(String(err)))
+                        }
+                      >
+                        Remove provider
+                      </button>
+                    )}
+                  </div>
                 </div>
               );
             })}
+
+            {/* Custom OpenAI-compatible provider */}
+            <div className="flex flex-col gap-2 rounded-lg border border-dashed p-3">
+              <span className="text-sm font-medium">Custom provider (OpenAI-compatible)</span>
+              <div className="grid grid-cols-3 gap-2">
+                <input
+                  placeholder="Id (e.g. local-vllm)"
+                  value={customDraft.id}
+                  onChange={(e) => setCustomDraft((prev) => ({ ...prev, id: e.target.value }))}
+                  className="rounded-md border bg-background px-3 py-2 text-sm"
+                />
+                <input
+                  placeholder="Label (optional)"
+                  value={customDraft.label ?? ""}
+                  onChange={(e) =>
+                    setCustomDraft((prev) => ({ ...prev, label: e.target.value || null }))
+                  }
+                  className="rounded-md border bg-background px-3 py-2 text-sm"
+                />
+                <input
+                  placeholder="Base URL (…/v1)"
+                  value={customDraft.baseUrl}
+                  onChange={(e) =>
+                    setCustomDraft((prev) => ({ ...prev, baseUrl: e.target.value }))
+                  }
+                  className="rounded-md border bg-background px-3 py-2 text-sm"
+                />
+              </div>
+              <Button
+                variant="outline"
+                size="sm"
+                className="self-start"
+                disabled={!customDraft.id.trim() || !customDraft.baseUrl.trim()}
+                onClick={async () => {
+                  setApiKeyError(null);
+                  try {
+                    await openclawCustomProviderSave(customDraft);
+                    setCustomDraft(EMPTY_CUSTOM_PROVIDER);
+                    loadApiKeys();
+                  } catch (err) {
+                    setApiKeyError(String(err));
+                  }
+                }}
+              >
+                Add Provider
+              </Button>
+            </div>
           </CardContent>
         </Card>
+
+        <ProviderKeysCard />
       </div>
     </div>
   );
@@ -19,6 +19,8 @@ export default function Setup() {
     openclawChecked,
     apiKeyInput,
     setApiKeyInput,
+    activeOp,
+    cancelOperation,
     runStep,
     nextStep,
     recheckOpenClaw,
@@ -90,7 +92,11 @@ export default function Setup() {
         </div>
       </div>
 
-      <LogDrawer logs={logs} logsEndRef={logsEndRef} />
+      <LogDrawer
+        logs={logs}
+        logsEndRef={logsEndRef}
+        onCancel={activeOp ? cancelOperation : undefined}
+      />
     </div>
   );
 }
@@ -20,6 +20,9 @@ export default function WhatsApp() {
     openclawInstalled,
     gatewayRunning,
     qrData,
+    activeOp,
+    cancelOperation,
+    sendInput,
     runStep,
     nextStep,
     recheckOpenClaw,
@@ -114,7 +117,12 @@ export default function WhatsApp() {
         </div>
       </div>
 
-      <LogDrawer logs={logs} logsEndRef={logsEndRef} />
+      <LogDrawer
+        logs={logs}
+        logsEndRef={logsEndRef}
+        onInput={activeOp ? sendInput : undefined}
+        onCancel={activeOp ? cancelOperation : undefined}
+      />
     </div>
   );
 }
@@ -1,8 +1,10 @@
 import { atom } from "jotai";
-import type { ChatMessage } from "@/types";
+import type { ChatBackend, ChatMessage } from "@/types";
 
 export const sidebarCollapsedAtom = atom<boolean>(false);
 
 export const chatMessagesAtom = atom<ChatMessage[]>([]);
 
 export const chatLoadingAtom = atom<boolean>(false);
+
+export const chatBackendAtom = atom<ChatBackend>("openrouter");
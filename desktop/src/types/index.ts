@@ -35,6 +35,9 @@ export interface ChatMessage {
   };
 }
 
+// "openrouter" talks to OpenRouter directly; "openclaw" goes through the gateway's agent
+export type ChatBackend = "openrouter" | "openclaw";
+
 export type StepStatus = "pending" | "running" | "done" | "error";
 
 // Diagnostics types
@@ -76,11 +79,230 @@ export interface OpenClawStatus {
   installed: boolean;
   path: string | null;
   gateway_running: boolean;
+  gateway_url: string;
+  gateway_remote: boolean;
+  gateway: GatewayProbe | null;
+}
+
+export type ProbeResult = "running" | "not_running" | "foreign";
+
+export interface GatewayProbe {
+  url: string;
+  // foreign = something other than OpenClaw holds the port
+  result: ProbeResult;
+  version: string | null;
+  uptime_secs: number | null;
+  auth_mode: string | null;
+  channels: string[];
+  plugins: string[];
+  message: string;
+}
+
+export interface GatewayTarget {
+  url: string;
+  remote: boolean;
+  ssh_target: string | null;
+}
+
+export interface GatewayCheck {
+  url: string;
+  remote: boolean;
+  reachable: boolean;
+  // null when auth couldn't be checked
+  authenticated: boolean | null;
+  message: string;
+  probe: GatewayProbe;
+}
+
+export type GatewayState = "starting" | "healthy" | "degraded" | "down";
+
+export interface GatewayStatus {
+  state: GatewayState;
+  url: string;
+  remote: boolean;
+  // ms since epoch
+  since: number;
+  last_check: number | null;
+  message: string;
+  consecutive_failures: number;
+  restart_attempts: number;
+  next_restart_at: number | null;
+  auto_restart: boolean;
+}
+
+export type ConnectionState = "connecting" | "connected" | "disconnected";
+
+export interface GatewayConnection {
+  state: ConnectionState;
+  // ws:// or wss://
+  url: string;
+  message: string | null;
+  connected_since: number | null;
+  server_version: string | null;
+  next_retry_at: number | null;
+}
+
+export interface GatewayEvent {
+  // e.g. "chat", "agent", "presence", "health"
+  event: string;
+  payload: unknown;
+  seq: number | null;
+}
+
+export interface RemoteGatewaySetup {
+  // ws://, wss://, http:// or https://
+  url: string;
+  // vault reference; omitted keeps the current token
+  tokenRef?: string | null;
+  sshTarget?: string | null;
 }
 
 export interface OpenClawLogEvent {
   line: string;
   stream: "stdout" | "stderr";
+  // operation that produced the line
+  op_id: string | null;
+}
+
+// "finished" ends a task; its outcome is what the command returned
+export type OperationState =
+  | "running"
+  | "succeeded"
+  | "failed"
+  | "cancelled"
+  | "timed_out"
+  | "finished";
+
+// task = a command holding exclusion groups, process = a spawned program
+export type OperationKind = "task" | "process";
+
+export type ExclusionGroup = "install" | "config" | "gateway";
+
+export interface OperationStatus {
+  id: string;
+  label: string;
+  kind: OperationKind;
+  groups: ExclusionGroup[];
+  state: OperationState;
+  // what a task is doing now
+  step: string | null;
+  pid: number | null;
+  // ms since epoch
+  started_at: number;
+  finished_at: number | null;
+  message: string | null;
+}
+
+// Where openclawInstall gets OpenClaw from; checksums are SRI strings ("sha512-<base64>")
+export type InstallSource =
+  | {
+      kind: "registry";
+      // defaults to the release this app pins
+      version?: string | null;
+      // defaults to https://registry.npmjs.org
+      registry?: string | null;
+      integrity?: string | null;
+    }
+  | {
+      // an `npm pack` tarball; without `integrity`, `<path>.integrity` must hold it
+      kind: "tarball";
+      path: string;
+      integrity?: string | null;
+      // no network, so the tarball must bundle its dependencies; defaults to true
+      offline?: boolean | null;
+    }
+  // OpenClaw's own install script, unverified; the default until the pinned release's
+  // checksum is known
+  | { kind: "script" };
+
+export type InstallPhase =
+  | "resolving"
+  | "downloading"
+  | "verifying"
+  | "installing"
+  // a version change failed and the previous version is being reinstalled
+  | "rolling_back"
+  | "done";
+
+export interface InstallProgress {
+  phase: InstallPhase;
+  message: string;
+  bytes: number | null;
+  total: number | null;
+}
+
+export interface InstallResult {
+  version: string | null;
+  // null when installed by the install script
+  integrity: string | null;
+  prefix: string;
+  binary: string | null;
+}
+
+// supported = tested, untested = newer than any tested release
+export type VersionSupport = "supported" | "untested" | "unsupported";
+
+export interface OpenClawVersion {
+  installed: boolean;
+  path: string | null;
+  // output of `openclaw --version`
+  raw: string | null;
+  version: string | null;
+  support: VersionSupport | null;
+  message: string;
+  // release the installer installs by default
+  pinned: string;
+  min_supported: string;
+}
+
+export interface VersionChange {
+  previous: string | null;
+  current: string;
+}
+
+export interface UninstallOptions {
+  removeState?: boolean;
+  archiveState?: boolean;
+}
+
+export type UninstallAction =
+  | "stop_gateway"
+  | "remove_service"
+  | "remove_binary"
+  | "archive_state"
+  | "remove_state";
+
+export interface UninstallStep {
+  action: UninstallAction;
+  description: string;
+  path: string | null;
+  size: number | null;
+  done: boolean;
+  error: string | null;
+}
+
+export interface UninstallReport {
+  dry_run: boolean;
+  steps: UninstallStep[];
+  archive: string | null;
+}
+
+export interface SnapshotInfo {
+  id: string;
+  path: string;
+  profile: string;
+  home: string;
+  created_at: number;
+  size: number;
+  label: string | null;
+  openclaw_version: string | null;
+  encrypted: boolean;
+}
+
+export interface SnapshotRestore {
+  restored: SnapshotInfo;
+  previous: SnapshotInfo | null;
+  gateway_restarted: boolean;
 }
 
 export interface OpenClawQrEvent {
@@ -88,14 +310,142 @@ export interface OpenClawQrEvent {
 }
 
 export interface ApiKeyStatus {
+  provider: string;
+  label: string;
+  profile_key: string;
+  custom: boolean;
+  get_key_url: string | null;
   configured: boolean;
   masked: string | null;
 }
 
-export interface ApiKeysStatus {
-  gemini: ApiKeyStatus;
-  openai: ApiKeyStatus;
-  anthropic: ApiKeyStatus;
+export type KeyAuth = "bearer" | "x_api_key" | "goog_api_key";
+
+export interface ProviderSpec {
+  id: string;
+  label: string;
+  openclaw_provider: string;
+  profile_key: string;
+  key_pattern: string | null;
+  models_url: string | null;
+  key_auth: KeyAuth;
+  base_url: string | null;
+  get_key_url: string | null;
+  custom: boolean;
+}
+
+export type KeyCheckStatus = "valid" | "invalid" | "no_quota" | "network_error";
+
+export interface KeyCheck {
+  provider: string;
+  status: KeyCheckStatus;
+  http_status: number | null;
+  message: string;
+}
+
+export interface ProviderKeyStatus {
+  profile_id: string;
+  name: string;
+  masked: string | null;
+  primary: boolean;
+  // epoch milliseconds, from OpenClaw's usageStats
+  last_used: number | null;
+  last_failure_at: number | null;
+  last_error: string | null;
+  error_count: number;
+  cooldown_until: number | null;
+}
+
+export interface ProviderKeys {
+  provider: string;
+  label: string;
+  openclaw_provider: string;
+  // failover order, primary first
+  keys: ProviderKeyStatus[];
+}
+
+export interface CustomProvider {
+  id: string;
+  label: string | null;
+  baseUrl: string;
+  keyPattern: string | null;
+}
+
+export interface ConfigIssue {
+  path: string;
+  severity: "error" | "warning";
+  message: string;
+}
+
+export interface OpenClawConfigSnapshot {
+  path: string;
+  exists: boolean;
+  config: Record<string, unknown>;
+  issues: ConfigIssue[];
+  // Present for dry runs; `config` is then what would be written
+  preview?: ConfigPreview;
+}
+
+export interface ConfigDiffEntry {
+  path: string;
+  op: "added" | "removed" | "changed";
+  before: unknown | null;
+  after: unknown | null;
+}
+
+export interface ConfigFileDiff {
+  file: string;
+  changes: ConfigDiffEntry[];
+}
+
+export interface ConfigPreview {
+  id: string;
+  files: ConfigFileDiff[];
+  issues: ConfigIssue[];
+}
+
+export interface OpenClawConfigChangedEvent {
+  file: "openclaw.json" | "auth-profiles.json";
+  path: string;
+  exists: boolean;
+  changes: ConfigDiffEntry[];
+  issues: ConfigIssue[];
+  new_issues: ConfigIssue[];
+}
+
+export interface ConfigWatcherError {
+  message: string;
+  // The watcher gave up, so outside changes are no longer reported
+  stopped: boolean;
+}
+
+export interface ConfigBackup {
+  id: string;
+  file: string;
+  created_at: number;
+  size: number;
+}
+
+// Vault types
+
+export type VaultMode = "machine" | "passphrase";
+
+export interface VaultStatus {
+  mode: VaultMode;
+  locked: boolean;
+  key_count: number;
+  path: string;
+  // Why the vault couldn't be opened or unlocked
+  error: string | null;
+}
+
+export interface VaultKeySummary {
+  key_ref: string;
+  provider: string;
+  label: string;
+  masked: string;
+  created_at: number;
+  updated_at: number;
 }
 
 // Settings types
@@ -105,9 +455,106 @@ export type Theme = "light" | "dark" | "system";
 // "simple" = quick/cheap, "standard" = balanced, "complex" = most capable
 export type TaskComplexity = "simple" | "standard" | "complex";
 
+// Mirrors the backend `Settings` model (src-tauri/src/commands/settings.rs)
 export interface AppSettings {
+  schemaVersion: number;
   theme: Theme;
-  systemPrompt: string;
-  openrouterApiKey: string;
+  // null = use the built-in prompt
+  systemPrompt: string | null;
   taskComplexity: TaskComplexity;
+  openrouterKeyRef: string | null;
+  openclawProfiles: OpenClawProfile[];
+  // null = built-in profile (OPENCLAW_STATE_DIR or ~/.openclaw)
+  activeOpenclawProfile: string | null;
+  customProviders: CustomProvider[];
+  // null = on
+  gatewayAutoRestart: boolean | null;
+}
+
+export type SettingsPatch = Partial<Omit<AppSettings, "schemaVersion">>;
+
+// Unset fields fall back to OpenClaw's defaults
+export interface OpenClawProfile {
+  name: string;
+  home: string | null;
+  gatewayUrl: string | null;
+  agentId: string | null;
+}
+
+export interface OpenClawPaths {
+  profile: string;
+  home: string;
+  gateway_url: string;
+  agent_id: string;
+}
+
+export interface OpenClawProfilesSnapshot {
+  active: string;
+  active_paths: OpenClawPaths;
+  profiles: OpenClawProfile[];
+}
+
+// Model ids are OpenRouter catalog ids, e.g. "google/gemini-2.5-flash"
+export interface ModelChoice {
+  primary: string;
+  // omitted keeps the existing fallbacks
+  fallbacks?: string[] | null;
+}
+
+export interface ModelSetup {
+  primary: ModelChoice;
+  imageModel?: ModelChoice | null;
+  // registered without being assigned, e.g. every tier
+  models?: string[];
+  // by agent id
+  agents?: Record<string, ModelChoice>;
+  // by channel, e.g. { whatsapp: { primary: "google/gemini-2.0-flash-001", fallbacks: [] } }
+  channels?: Record<string, ModelChoice>;
+  // lets a channel without an agent of its own get a new one (with a fresh workspace)
+  dedicatedChannelAgents?: boolean;
+}
+
+export interface ServiceTokenStatus {
+  kind: "launchd" | "systemd";
+  path: string;
+  masked: string | null;
+  matches: boolean;
+}
+
+export interface GatewayTokenStatus {
+  // gateway.auth.mode; null when the gateway runs without auth
+  mode: string | null;
+  masked: string | null;
+  services: ServiceTokenStatus[];
+  consistent: boolean;
+  issues: string[];
+}
+
+export interface AgentModelConfig {
+  primary?: string;
+  fallbacks?: string[];
+}
+
+export interface BindingMatch {
+  channel: string;
+  accountId?: string;
+  peer?: unknown;
+}
+
+export interface AgentAuthProfile {
+  id: string;
+  provider: string | null;
+  masked: string | null;
+}
+
+export interface AgentSummary {
+  id: string;
+  name: string | null;
+  is_default: boolean;
+  explicit: boolean;
+  model: AgentModelConfig | null;
+  effective_model: string | null;
+  agent_dir: string;
+  auth_profiles: AgentAuthProfile[];
+  bindings: BindingMatch[];
 }